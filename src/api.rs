@@ -0,0 +1,620 @@
+//! Players, teams, and stadiums in the shape returned by the official Blaseball API (for example,
+//! `/database/players` and `/database/team`).
+//!
+//! Deserialize the API's JSON into these types, then convert them into simx types with `From`.
+//! Fields simx doesn't model (items, stats, ritual, and so on) are ignored, and fields missing
+//! from older eras take their default values.
+//!
+//! Going the other way, [`StreamGame`] serializes a game in the shape of a `schedule` entry from
+//! `/events/streamData`, for frontends built against the real API.
+
+use crate::id::{BallparkId, GameId, PlayerId, TeamId};
+use crate::sim::{BALLS_NEEDED, HOME_BASE, OUTS_NEEDED, STRIKES_NEEDED};
+use crate::{Game, Inning, RngSource, Sim, TeamSelect, Weather};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Player {
+    pub id: PlayerId,
+    pub name: String,
+
+    pub thwackability: f64,
+    pub moxie: f64,
+    pub divinity: f64,
+    pub musclitude: f64,
+    pub patheticism: f64,
+    pub buoyancy: f64,
+    pub base_thirst: f64,
+    pub laserlikeness: f64,
+    pub ground_friction: f64,
+    pub continuation: f64,
+    pub indulgence: f64,
+    pub martyrdom: f64,
+    pub tragicness: f64,
+    pub shakespearianism: f64,
+    pub suppression: f64,
+    pub unthwackability: f64,
+    pub coldness: f64,
+    pub overpowerment: f64,
+    pub ruthlessness: f64,
+    pub omniscience: f64,
+    pub tenaciousness: f64,
+    pub watchfulness: f64,
+    pub anticapitalism: f64,
+    pub chasiness: f64,
+    pub pressurization: f64,
+    pub cinnamon: f64,
+
+    pub soul: Option<u16>,
+    pub peanut_allergy: Option<bool>,
+    pub fate: Option<u8>,
+    pub blood: Option<u8>,
+    pub coffee: Option<u8>,
+
+    pub perm_attr: Vec<String>,
+    pub seas_attr: Vec<String>,
+    pub week_attr: Vec<String>,
+    pub game_attr: Vec<String>,
+}
+
+impl From<Player> for crate::Player {
+    fn from(player: Player) -> crate::Player {
+        crate::Player {
+            id: player.id,
+            name: player.name,
+            thwackability: player.thwackability,
+            moxie: player.moxie,
+            divinity: player.divinity,
+            musclitude: player.musclitude,
+            patheticism: player.patheticism,
+            buoyancy: player.buoyancy,
+            base_thirst: player.base_thirst,
+            laserlikeness: player.laserlikeness,
+            ground_friction: player.ground_friction,
+            continuation: player.continuation,
+            indulgence: player.indulgence,
+            martyrdom: player.martyrdom,
+            tragicness: player.tragicness,
+            shakespearianism: player.shakespearianism,
+            suppression: player.suppression,
+            unthwackability: player.unthwackability,
+            coldness: player.coldness,
+            overpowerment: player.overpowerment,
+            ruthlessness: player.ruthlessness,
+            omniscience: player.omniscience,
+            tenaciousness: player.tenaciousness,
+            watchfulness: player.watchfulness,
+            anticapitalism: player.anticapitalism,
+            chasiness: player.chasiness,
+            pressurization: player.pressurization,
+            cinnamon: player.cinnamon,
+            soul: player.soul.unwrap_or_default(),
+            peanut_allergy: player.peanut_allergy.unwrap_or_default(),
+            fate: player.fate.unwrap_or_default(),
+            blood: player.blood.unwrap_or_default(),
+            coffee: player.coffee.unwrap_or_default(),
+            mods: collect_mods(
+                player.perm_attr,
+                player.seas_attr,
+                player.week_attr,
+                player.game_attr,
+            ),
+            marked_by: None,
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Team {
+    pub id: TeamId,
+    pub location: String,
+    pub nickname: String,
+    pub shorthand: String,
+    pub emoji: String,
+    pub main_color: String,
+    pub secondary_color: String,
+    pub lineup: Vec<PlayerId>,
+    pub rotation: Vec<PlayerId>,
+    /// Introduced in Season 12, replacing `bench` and `bullpen`.
+    pub shadows: Option<Vec<PlayerId>>,
+    pub bench: Vec<PlayerId>,
+    pub bullpen: Vec<PlayerId>,
+    pub rotation_slot: usize,
+
+    pub perm_attr: Vec<String>,
+    pub seas_attr: Vec<String>,
+    pub week_attr: Vec<String>,
+    pub game_attr: Vec<String>,
+}
+
+impl From<Team> for crate::Team {
+    fn from(team: Team) -> crate::Team {
+        crate::Team {
+            id: team.id,
+            location: team.location,
+            nickname: team.nickname,
+            shorthand: team.shorthand,
+            emoji: team.emoji,
+            main_color: team.main_color,
+            secondary_color: team.secondary_color,
+            lineup: team.lineup,
+            rotation: team.rotation,
+            shadows: team
+                .shadows
+                .unwrap_or_else(|| team.bench.into_iter().chain(team.bullpen).collect()),
+            rotation_slot: team.rotation_slot,
+            mods: collect_mods(
+                team.perm_attr,
+                team.seas_attr,
+                team.week_attr,
+                team.game_attr,
+            ),
+            positions: std::collections::BTreeMap::new(),
+            league: None,
+        }
+    }
+}
+
+/// A ballpark, which the API calls a stadium.
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Stadium {
+    pub id: BallparkId,
+    pub team_id: TeamId,
+    pub name: String,
+    pub nickname: String,
+    pub ominousness: f64,
+    pub forwardness: f64,
+    pub obtuseness: f64,
+    pub grandiosity: f64,
+    pub fortification: f64,
+    pub elongation: f64,
+    pub inconvenience: f64,
+    pub viscosity: f64,
+    pub hype: f64,
+    pub mysticism: f64,
+    pub luxuriousness: f64,
+    pub filthiness: f64,
+    pub birds: i32,
+}
+
+impl Default for Stadium {
+    fn default() -> Stadium {
+        let ballpark = crate::Ballpark::default();
+        Stadium {
+            id: ballpark.id,
+            team_id: ballpark.team_id,
+            name: ballpark.name,
+            nickname: ballpark.nickname,
+            ominousness: ballpark.ominousness,
+            forwardness: ballpark.forwardness,
+            obtuseness: ballpark.obtuseness,
+            grandiosity: ballpark.grandiosity,
+            fortification: ballpark.fortification,
+            elongation: ballpark.elongation,
+            inconvenience: ballpark.inconvenience,
+            viscosity: ballpark.viscosity,
+            hype: ballpark.hype,
+            mysticism: ballpark.mysticism,
+            luxuriousness: ballpark.luxuriousness,
+            filthiness: ballpark.filthiness,
+            birds: ballpark.birds,
+        }
+    }
+}
+
+impl From<Stadium> for crate::Ballpark {
+    fn from(stadium: Stadium) -> crate::Ballpark {
+        crate::Ballpark {
+            id: stadium.id,
+            team_id: stadium.team_id,
+            name: stadium.name,
+            nickname: stadium.nickname,
+            ominousness: stadium.ominousness,
+            forwardness: stadium.forwardness,
+            obtuseness: stadium.obtuseness,
+            grandiosity: stadium.grandiosity,
+            fortification: stadium.fortification,
+            elongation: stadium.elongation,
+            inconvenience: stadium.inconvenience,
+            viscosity: stadium.viscosity,
+            hype: stadium.hype,
+            mysticism: stadium.mysticism,
+            luxuriousness: stadium.luxuriousness,
+            filthiness: stadium.filthiness,
+            birds: stadium.birds,
+        }
+    }
+}
+
+fn collect_mods(
+    perm: Vec<String>,
+    seas: Vec<String>,
+    week: Vec<String>,
+    game: Vec<String>,
+) -> Vec<String> {
+    let mut mods = Vec::new();
+    for m in perm.into_iter().chain(seas).chain(week).chain(game) {
+        if !mods.contains(&m) {
+            mods.push(m);
+        }
+    }
+    mods
+}
+
+/// A game in the shape of a `schedule` entry from `/events/streamData`. Create these with
+/// [`Sim::stream_schedule`].
+///
+/// Fields simx doesn't model (odds, the stadium, and so on) are omitted.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+#[allow(clippy::struct_excessive_bools)] // the API's shape, not ours
+pub struct StreamGame {
+    pub id: GameId,
+    pub season: u16,
+    pub day: u16,
+
+    pub away_team: TeamId,
+    pub away_team_name: String,
+    pub away_team_nickname: String,
+    pub away_team_emoji: String,
+    pub away_team_color: String,
+    pub away_team_secondary_color: String,
+    pub away_score: u16,
+    pub away_pitcher: Option<PlayerId>,
+    pub away_pitcher_name: String,
+    pub away_batter: Option<PlayerId>,
+    pub away_batter_name: String,
+    pub away_balls: u8,
+    pub away_strikes: u8,
+    pub away_outs: u8,
+    pub away_bases: u8,
+
+    pub home_team: TeamId,
+    pub home_team_name: String,
+    pub home_team_nickname: String,
+    pub home_team_emoji: String,
+    pub home_team_color: String,
+    pub home_team_secondary_color: String,
+    pub home_score: u16,
+    pub home_pitcher: Option<PlayerId>,
+    pub home_pitcher_name: String,
+    pub home_batter: Option<PlayerId>,
+    pub home_batter_name: String,
+    pub home_balls: u8,
+    pub home_strikes: u8,
+    pub home_outs: u8,
+    pub home_bases: u8,
+
+    /// Starts from 0 for the first inning.
+    pub inning: usize,
+    pub top_of_inning: bool,
+    pub half_inning_outs: u8,
+    pub at_bat_balls: u8,
+    pub at_bat_strikes: u8,
+    /// Starts from 0 for first base.
+    pub bases_occupied: Vec<u8>,
+    pub base_runners: Vec<PlayerId>,
+    pub base_runner_names: Vec<String>,
+    pub baserunner_count: usize,
+
+    /// The weather's index in the API's list of weathers (see [`weather_index`]).
+    pub weather: u8,
+    pub shame: bool,
+    pub last_update: String,
+    pub game_start: bool,
+    pub game_complete: bool,
+    pub finalized: bool,
+    pub is_postseason: bool,
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Today's games, in the shape of the `schedule` field from `/events/streamData`.
+    pub fn stream_schedule(&self) -> Vec<StreamGame> {
+        self.games_today()
+            .iter()
+            .map(|game| self.stream_game(game))
+            .collect()
+    }
+
+    #[allow(clippy::too_many_lines)] // it's one long field mapping
+    fn stream_game(&self, game: &Game) -> StreamGame {
+        let player_name = |player: Option<PlayerId>| {
+            player
+                .and_then(|id| self.players().get(&id))
+                .map(|player| player.name.clone())
+                .unwrap_or_default()
+        };
+        let team = |select: TeamSelect| {
+            let data = game.teams.select(select);
+            let team = self.teams().get(&data.id);
+            let batter = game.at_bat.filter(|_| game.inning.batting() == select);
+            (
+                data.id,
+                team.map(crate::Team::name).unwrap_or_default(),
+                team.map(|team| team.nickname.clone()).unwrap_or_default(),
+                team.map(|team| team.emoji.clone()).unwrap_or_default(),
+                team.map(|team| team.main_color.clone()).unwrap_or_default(),
+                team.map(|team| team.secondary_color.clone())
+                    .unwrap_or_default(),
+                data.runs,
+                data.pitcher,
+                player_name(data.pitcher),
+                batter,
+                player_name(batter),
+            )
+        };
+        let (
+            away_team,
+            away_team_name,
+            away_team_nickname,
+            away_team_emoji,
+            away_team_color,
+            away_team_secondary_color,
+            away_score,
+            away_pitcher,
+            away_pitcher_name,
+            away_batter,
+            away_batter_name,
+        ) = team(TeamSelect::Away);
+        let (
+            home_team,
+            home_team_name,
+            home_team_nickname,
+            home_team_emoji,
+            home_team_color,
+            home_team_secondary_color,
+            home_score,
+            home_pitcher,
+            home_pitcher_name,
+            home_batter,
+            home_batter_name,
+        ) = team(TeamSelect::Home);
+        let date = self.database.date;
+
+        StreamGame {
+            id: game.id,
+            season: date.season,
+            day: date.day,
+
+            away_team,
+            away_team_name,
+            away_team_nickname,
+            away_team_emoji,
+            away_team_color,
+            away_team_secondary_color,
+            away_score,
+            away_pitcher,
+            away_pitcher_name,
+            away_batter,
+            away_batter_name,
+            away_balls: BALLS_NEEDED,
+            away_strikes: STRIKES_NEEDED,
+            away_outs: OUTS_NEEDED,
+            away_bases: HOME_BASE,
+
+            home_team,
+            home_team_name,
+            home_team_nickname,
+            home_team_emoji,
+            home_team_color,
+            home_team_secondary_color,
+            home_score,
+            home_pitcher,
+            home_pitcher_name,
+            home_batter,
+            home_batter_name,
+            home_balls: BALLS_NEEDED,
+            home_strikes: STRIKES_NEEDED,
+            home_outs: OUTS_NEEDED,
+            home_bases: HOME_BASE,
+
+            inning: game.inning.number().saturating_sub(1),
+            top_of_inning: game.inning.batting() == TeamSelect::Away,
+            half_inning_outs: game.outs,
+            at_bat_balls: game.balls,
+            at_bat_strikes: game.strikes,
+            bases_occupied: game
+                .baserunners
+                .iter()
+                .map(|runner| runner.base.saturating_sub(1))
+                .collect(),
+            base_runners: game
+                .baserunners
+                .iter()
+                .map(|runner| runner.player)
+                .collect(),
+            base_runner_names: game
+                .baserunners
+                .iter()
+                .map(|runner| player_name(Some(runner.player)))
+                .collect(),
+            baserunner_count: game.baserunners.len(),
+
+            weather: weather_index(game.weather),
+            shame: game.outcome.as_ref().is_some_and(|outcome| outcome.shamed),
+            last_update: game.last_update.clone(),
+            game_start: game.inning != Inning::default(),
+            game_complete: game.is_finished(),
+            finalized: game.is_finished(),
+            is_postseason: self.phase() == crate::Phase::Postseason,
+        }
+    }
+}
+
+/// The index of `weather` in the API's list of weathers, where 0 is Void and 1 is Sun 2.
+pub fn weather_index(weather: Weather) -> u8 {
+    match weather {
+        Weather::Sun2 => 1,
+        Weather::Eclipse => 7,
+        Weather::Glitter => 8,
+        Weather::Blooddrain => 9,
+        Weather::Peanuts => 10,
+        Weather::Birds => 11,
+        Weather::Feedback => 12,
+        Weather::Reverb => 13,
+        Weather::BlackHole => 14,
+        Weather::Coffee => 15,
+        Weather::Coffee2 => 16,
+        Weather::Coffee3s => 17,
+        Weather::Flooding => 18,
+        Weather::Salmon => 19,
+        Weather::PolarityPlus => 20,
+        Weather::PolarityMinus => 21,
+        Weather::SunPointOne => 24,
+        Weather::SumSun => 25,
+        Weather::Night => 29,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Player, Stadium, Team};
+    use crate::{AwayHome, Date, Game, Inning, Weather};
+    use serde_json::json;
+
+    #[test]
+    fn player() {
+        // trimmed from `/database/players`, with fields simx doesn't model left in
+        let json = json!({
+            "id": "b7adbbcc-0679-43f3-a939-07f009a393db",
+            "name": "Nagomi Mcdaniel",
+            "moxie": 0.5,
+            "baseThirst": 0.25,
+            "groundFriction": 0.75,
+            "peanutAllergy": true,
+            "soul": 7,
+            "hittingRating": 0.3,
+            "items": [{"id": "00000000-0000-0000-0000-000000000001", "name": "Bat"}],
+            "ritual": "Yoga",
+            "permAttr": ["SHELLED"],
+            "seasAttr": [],
+            "weekAttr": ["SHELLED", "PARTY_TIME"],
+        });
+        let player = crate::Player::from(serde_json::from_value::<Player>(json).unwrap());
+        assert_eq!(player.name, "Nagomi Mcdaniel");
+        assert!((player.moxie - 0.5).abs() < f64::EPSILON);
+        assert!((player.base_thirst - 0.25).abs() < f64::EPSILON);
+        assert!((player.ground_friction - 0.75).abs() < f64::EPSILON);
+        assert!(player.peanut_allergy);
+        assert_eq!(player.soul, 7);
+        // missing from older eras
+        assert_eq!((player.fate, player.blood, player.coffee), (0, 0, 0));
+        assert_eq!(player.mods, ["SHELLED", "PARTY_TIME"]);
+    }
+
+    #[test]
+    fn team() {
+        let bench = "2e86de11-a2dd-4b91-b8b0-0a8dd5a3b0e7";
+        let bullpen = "ef9f8b95-9e73-49cd-be54-60f84858a285";
+        // before Season 12, teams had a bench and a bullpen instead of shadows
+        let old = json!({
+            "id": "747b8e4a-7e50-4638-a973-ea7950a3e739",
+            "location": "Hades",
+            "nickname": "Tigers",
+            "lineup": ["b7adbbcc-0679-43f3-a939-07f009a393db"],
+            "rotation": ["a1628d97-16ca-4a75-b8df-569bae02bef9"],
+            "bench": [bench],
+            "bullpen": [bullpen],
+            "rotationSlot": 3,
+            "totalShames": 12,
+            "permAttr": ["FIREPROOF"],
+        });
+        let team = crate::Team::from(serde_json::from_value::<Team>(old).unwrap());
+        assert_eq!(team.name(), "Hades Tigers");
+        assert_eq!(team.rotation_slot, 3);
+        assert_eq!(
+            team.shadows,
+            [bench.parse().unwrap(), bullpen.parse().unwrap()]
+        );
+        assert_eq!(team.mods, ["FIREPROOF"]);
+
+        let new = json!({
+            "id": "747b8e4a-7e50-4638-a973-ea7950a3e739",
+            "shadows": [bullpen],
+            "bench": [bench],
+        });
+        let team = crate::Team::from(serde_json::from_value::<Team>(new).unwrap());
+        assert_eq!(team.shadows, [bullpen.parse().unwrap()]);
+    }
+
+    #[test]
+    fn stadium() {
+        let json = json!({
+            "id": "cfb57d7c-4118-4b0a-85cc-4e3a51a66cb6",
+            "teamId": "747b8e4a-7e50-4638-a973-ea7950a3e739",
+            "name": "Tiger Island",
+            "hype": 0.9,
+            "birds": 40,
+            "renoCost": 1000,
+        });
+        let ballpark = crate::Ballpark::from(serde_json::from_value::<Stadium>(json).unwrap());
+        assert_eq!(ballpark.name, "Tiger Island");
+        assert!((ballpark.hype - 0.9).abs() < f64::EPSILON);
+        assert_eq!(ballpark.birds, 40);
+        // missing fields take simx's defaults rather than zero
+        let default = crate::Ballpark::default();
+        assert!((ballpark.mysticism - default.mysticism).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn stream_game() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let teams = sim.games_today()[0].teams.as_ref().map(|team| team.id);
+        let [away, home] =
+            [teams.away, teams.home].map(|team| sim.teams().get(&team).unwrap().clone());
+        let game = Game::builder(teams)
+            .inning(Inning::Bottom(3))
+            .at_bat(Some(home.lineup[0]))
+            .outs(1)
+            .strikes(2)
+            .baserunner(home.lineup[1], 1)
+            .baserunner(home.lineup[2], 3)
+            .runs(AwayHome { away: 2, home: 1 })
+            .pitchers(AwayHome {
+                away: Some(away.rotation[0]),
+                home: Some(home.rotation[0]),
+            })
+            .weather(Weather::Eclipse)
+            .try_build(&sim)
+            .unwrap();
+        sim.start_day(Date { season: 0, day: 1 }, vec![game])
+            .unwrap();
+
+        let stream = &sim.stream_schedule()[0];
+        let name = |player| sim.players().get(&player).unwrap().name.clone();
+        assert_eq!(stream.inning, 2);
+        assert!(!stream.top_of_inning);
+        assert_eq!(stream.half_inning_outs, 1);
+        assert_eq!(stream.at_bat_strikes, 2);
+        assert_eq!((stream.away_score, stream.home_score), (2, 1));
+        // the home team is batting, so only they have a batter
+        assert_eq!(stream.away_batter, None);
+        assert_eq!(stream.away_batter_name, "");
+        assert_eq!(stream.home_batter, Some(home.lineup[0]));
+        assert_eq!(stream.home_batter_name, name(home.lineup[0]));
+        assert_eq!(stream.away_pitcher_name, name(away.rotation[0]));
+        assert_eq!(stream.home_pitcher_name, name(home.rotation[0]));
+        // bases are numbered from 0 for first base
+        assert_eq!(stream.bases_occupied, [0, 2]);
+        assert_eq!(stream.base_runners, [home.lineup[1], home.lineup[2]]);
+        assert_eq!(
+            stream.base_runner_names,
+            [name(home.lineup[1]), name(home.lineup[2])]
+        );
+        assert_eq!(stream.baserunner_count, 2);
+        assert_eq!(stream.weather, 7);
+        assert!(stream.game_start);
+        assert!(!stream.game_complete);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::id::TeamId;
+use crate::{Ballpark, Database, Game, Mutation};
+
+/// How many fans fit in a ballpark.
+const CAPACITY: u32 = 40_000;
+
+// How far a ballpark's hype moves toward each home game's share of a full house.
+const HYPE_RATE: f64 = 0.1;
+
+impl Database {
+    /// The ballpark a team plays its home games in, if it has one.
+    pub(crate) fn ballpark_of(&self, team: TeamId) -> Option<&Ballpark> {
+        self.ballparks
+            .values()
+            .find(|ballpark| ballpark.team_id == team)
+    }
+
+    /// Work out the crowd for each of today's games that doesn't have one yet, and let each crowd
+    /// move the hype of the home team's ballpark.
+    pub(crate) fn draw_crowds(&mut self) {
+        for i in 0..self.games_today.len() {
+            if self.games_today[i].attendance.is_some() {
+                continue;
+            }
+            let share = self.crowd_share(&self.games_today[i]);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let attendance = (share * f64::from(CAPACITY)).round() as u32;
+            self.games_today[i].attendance = Some(attendance);
+
+            let home = self.games_today[i].teams.home.id;
+            if let Some(ballpark) = self.ballpark_of(home).map(|ballpark| ballpark.id) {
+                let ballpark_data = ballpark.load_mut(self);
+                ballpark_data.hype += (share - ballpark_data.hype) * HYPE_RATE;
+                self.record(Mutation::BallparkChanged { ballpark });
+            }
+        }
+    }
+
+    /// The share of a full house that turns out for a game, between 0 and 1.
+    ///
+    /// Fans turn out for teams that are winning this season, for rivalries (teams close together
+    /// in the standings), and for luxurious, hyped-up ballparks.
+    fn crowd_share(&self, game: &Game) -> f64 {
+        let stats = self
+            .stats
+            .iter()
+            .find(|stats| stats.season == self.date.season);
+        // smoothed so that teams that haven't played yet count as .500
+        let winning = game.teams.as_ref().map(|team| {
+            let record = stats.and_then(|stats| stats.teams.get(&team.id));
+            record.map_or(0.5, |record| {
+                (f64::from(record.wins) + 1.0) / (f64::from(record.wins + record.losses) + 2.0)
+            })
+        });
+        let contention = f64::midpoint(winning.away, winning.home);
+        let rivalry = 1.0 - (winning.away - winning.home).abs();
+        let ballpark = self
+            .ballpark_of(game.teams.home.id)
+            .cloned()
+            .unwrap_or_default();
+
+        (0.2 + (0.3 * contention)
+            + (0.15 * rivalry)
+            + (0.2 * ballpark.luxuriousness)
+            + (0.15 * ballpark.hype))
+            .clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Ballpark, Date, Game, Mutation};
+
+    #[test]
+    fn attendance() {
+        let mut sim = crate::sim::tests::two_games();
+        let games = sim.games_today().to_vec();
+        assert!(games.iter().all(|game| game.attendance.is_some()));
+
+        let home = games[0].teams.home.id;
+        let ballpark = Ballpark {
+            id: crate::id::BallparkId::new(),
+            team_id: home,
+            luxuriousness: 1.0,
+            ..Ballpark::default()
+        };
+        let id = ballpark.id;
+        sim.add_ballpark(ballpark).unwrap();
+        sim.take_audit_log();
+
+        let fresh = games
+            .iter()
+            .map(|game| Game::new(game.teams.as_ref().map(|team| team.id)))
+            .collect();
+        sim.start_day(Date { season: 0, day: 1 }, fresh).unwrap();
+        let game = &sim.games_today()[0];
+        assert!(game.attendance > games[0].attendance);
+        let hype = sim.ballparks().get(&id).unwrap().hype;
+        assert!(hype > 0.0);
+        assert!(sim
+            .audit_log()
+            .iter()
+            .any(|entry| entry.mutation == Mutation::BallparkChanged { ballpark: id }));
+
+        sim.tick();
+        assert!(sim.games_today()[0]
+            .last_update
+            .ends_with("fans are in attendance."));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::id::{BallparkId, GameId, LeagueId, PlayerId, TeamId};
+use crate::{Database, Date, Injection, RngSource, RosterSlot, Sim};
+use serde::{Deserialize, Serialize};
+
+/// A change made to the database while running the sim, either by [`Sim::tick`] or by an
+/// offseason process like [`Sim::run_election`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[non_exhaustive]
+pub enum Mutation {
+    /// A new player was added to the database.
+    PlayerAdded { player: PlayerId },
+    /// A player was removed from the database, along with every reference to them. Their
+    /// removals from team rosters are recorded first. See [`Sim::remove_player_force`].
+    PlayerRemoved { player: PlayerId },
+    /// One or more of a player's attributes changed.
+    PlayerChanged { player: PlayerId },
+    /// A player was given a new name.
+    PlayerRenamed { player: PlayerId, name: String },
+    /// A player was added to a team's roster.
+    RosterAdded {
+        team: TeamId,
+        player: PlayerId,
+        slot: RosterSlot,
+    },
+    /// A player was removed from a team's roster, like when they're released into the free
+    /// agent pool.
+    RosterRemoved {
+        team: TeamId,
+        player: PlayerId,
+        slot: RosterSlot,
+    },
+    /// A player in a team's lineup was taken out of a game in progress, and `substitute` came in
+    /// from the shadows to take their place. See [`Sim::substitute`].
+    Substituted {
+        game: GameId,
+        team: TeamId,
+        player: PlayerId,
+        substitute: PlayerId,
+    },
+    /// The runner on `base` in a game in progress was replaced. See
+    /// [`Sim::replace_baserunner`].
+    BaserunnerReplaced {
+        game: GameId,
+        base: u8,
+        player: PlayerId,
+        replacement: PlayerId,
+    },
+    /// A team's pitcher in a game in progress was changed. See [`Sim::change_pitcher`].
+    PitcherChanged {
+        game: GameId,
+        team: TeamId,
+        pitcher: PlayerId,
+    },
+    /// One of today's games was edited with [`Sim::update_game`].
+    GameChanged { game: GameId },
+    /// One of today's games was suspended. See [`Sim::suspend_game`].
+    GameSuspended { game: GameId, reason: String },
+    /// A suspended game was resumed, either today with [`Sim::resume_game`] or on a later day by
+    /// [`Sim::start_day`].
+    GameResumed { game: GameId },
+    /// One of today's games was cancelled. See [`Sim::cancel_game`].
+    GameCancelled { game: GameId, reason: String },
+    /// A team forfeited one of today's games. See [`Sim::forfeit_game`].
+    GameForfeited {
+        game: GameId,
+        team: TeamId,
+        reason: String,
+    },
+    /// Something outside the sim made a change with [`Sim::inject`]. This is followed by any
+    /// other mutations the injection made.
+    Injected { injection: Injection },
+    /// A new team was added to the database.
+    TeamAdded { team: TeamId },
+    /// A team was edited.
+    TeamChanged { team: TeamId },
+    /// A team moved to a new location or was given new names, and is now called `name`. See
+    /// [`Sim::rebrand_team`].
+    TeamRebranded { team: TeamId, name: String },
+    /// A team will be rebranded as `name` when the next season starts. See
+    /// [`Sim::rebrand_team`].
+    RebrandScheduled { team: TeamId, name: String },
+    /// A team's waiting rebrand was called off. See [`Sim::cancel_rebrand`].
+    RebrandCancelled { team: TeamId },
+    /// A team's rotation slot changed.
+    RotationAdvanced { team: TeamId, slot: usize },
+    /// A modification was added to a team.
+    ModAdded { team: TeamId, name: String },
+    /// A modification was removed from a team.
+    ModRemoved { team: TeamId, name: String },
+    /// A new ballpark was added to the database.
+    BallparkAdded { ballpark: BallparkId },
+    /// A ballpark's attributes changed, like its hype after a home game's crowd.
+    BallparkChanged { ballpark: BallparkId },
+    /// A modification was added to a player. `source` is the player responsible, if any, like
+    /// the pitcher whose Debt marked a batter.
+    PlayerModAdded {
+        player: PlayerId,
+        name: String,
+        source: Option<PlayerId>,
+    },
+    /// A modification was removed from a player.
+    PlayerModRemoved { player: PlayerId, name: String },
+    /// A player won one of a season's awards, like "Best Batter" or "hits leader". Leaders of
+    /// [leagues](crate::League) other than the main one name their league. See
+    /// [`Sim::hand_out_awards`].
+    AwardWon {
+        season: u16,
+        player: PlayerId,
+        award: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        league: Option<LeagueId>,
+    },
+}
+
+/// A [`Mutation`], along with when it happened.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AuditEntry {
+    pub date: Date,
+    /// The number of ticks since the day started, including the tick that made this change.
+    pub tick: u64,
+    pub mutation: Mutation,
+}
+
+impl Database {
+    pub(crate) fn record(&mut self, mutation: Mutation) {
+        if self.skip_audit {
+            return;
+        }
+        self.audit_log.push(AuditEntry {
+            date: self.date,
+            tick: self.tick,
+            mutation,
+        });
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Changes made to the database since the audit log was last taken.
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        &self.database.audit_log
+    }
+
+    /// Take the audit log, leaving it empty.
+    pub fn take_audit_log(&mut self) -> Vec<AuditEntry> {
+        std::mem::take(&mut self.database.audit_log)
+    }
+
+    /// Choose whether changes are added to the audit log. The log is saved with the sim and grows
+    /// until it's [taken](Sim::take_audit_log), so a sim that nobody mirrors can turn it off;
+    /// turning it off keeps what's already in the log.
+    ///
+    /// This is a setting rather than part of the sim's state, so it isn't saved.
+    pub fn set_audit_log(&mut self, enabled: bool) {
+        self.database.skip_audit = !enabled;
+    }
+}
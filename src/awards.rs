@@ -0,0 +1,343 @@
+use crate::id::{LeagueId, PlayerId};
+use crate::{
+    BattingStats, Database, DatabaseError, Mutation, PitchingStats, RngSource, SeasonStats, Sim,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+/// The awards for one season, handed out with [`Sim::hand_out_awards`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct SeasonAwards {
+    pub season: u16,
+    /// The batter with the most total bases, plus walks, plus stolen bases less times caught
+    /// stealing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best_batter: Option<PlayerId>,
+    /// The pitcher with the most outs and strikeouts, less twice the runs allowed and each walk
+    /// and hit allowed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best_pitcher: Option<PlayerId>,
+    /// The player at the top of the idol board when the awards were handed out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idol: Option<PlayerId>,
+    /// The main league's leader in each category, with their total. Categories nobody recorded
+    /// any of are left out.
+    ///
+    /// Players count toward the league of the team they're on when the awards are handed out;
+    /// players who aren't on a team count toward the main league.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub leaders: BTreeMap<AwardCategory, (PlayerId, u32)>,
+    /// The leaders in each of the other [leagues](crate::League), like
+    /// [`SeasonAwards::leaders`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub league_leaders: BTreeMap<LeagueId, BTreeMap<AwardCategory, (PlayerId, u32)>>,
+}
+
+/// A stat the league leader is awarded for. See [`SeasonAwards::leaders`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum AwardCategory {
+    Hits,
+    HomeRuns,
+    RunsBattedIn,
+    StolenBases,
+    Walks,
+    /// Strikeouts by a pitcher.
+    Strikeouts,
+    OutsRecorded,
+}
+
+impl AwardCategory {
+    pub const ALL: [AwardCategory; 7] = [
+        AwardCategory::Hits,
+        AwardCategory::HomeRuns,
+        AwardCategory::RunsBattedIn,
+        AwardCategory::StolenBases,
+        AwardCategory::Walks,
+        AwardCategory::Strikeouts,
+        AwardCategory::OutsRecorded,
+    ];
+
+    /// A player's total in this category, going by their batting stats for batting categories
+    /// and their pitching stats for the rest.
+    fn total(self, batting: Option<&BattingStats>, pitching: Option<&PitchingStats>) -> u32 {
+        match self {
+            AwardCategory::Hits => batting.map_or(0, |stats| stats.hits),
+            AwardCategory::HomeRuns => batting.map_or(0, |stats| stats.home_runs),
+            AwardCategory::RunsBattedIn => batting.map_or(0, |stats| stats.runs_batted_in),
+            AwardCategory::StolenBases => batting.map_or(0, |stats| stats.stolen_bases),
+            AwardCategory::Walks => batting.map_or(0, |stats| stats.walks),
+            AwardCategory::Strikeouts => pitching.map_or(0, |stats| stats.strikeouts),
+            AwardCategory::OutsRecorded => pitching.map_or(0, |stats| stats.outs_recorded),
+        }
+    }
+}
+
+impl Display for AwardCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AwardCategory::Hits => "hits",
+            AwardCategory::HomeRuns => "home runs",
+            AwardCategory::RunsBattedIn => "runs batted in",
+            AwardCategory::StolenBases => "stolen bases",
+            AwardCategory::Walks => "walks",
+            AwardCategory::Strikeouts => "strikeouts",
+            AwardCategory::OutsRecorded => "outs recorded",
+        })
+    }
+}
+
+/// How much a batter contributed over a season, for the best batter award.
+fn batting_value(stats: &BattingStats) -> i64 {
+    // in i64, so stats that don't add up (say, from an edited save) can't underflow
+    let [hits, doubles, triples, home_runs] =
+        [stats.hits, stats.doubles, stats.triples, stats.home_runs].map(i64::from);
+    let singles = hits - doubles - triples - home_runs;
+    let total_bases = singles + 2 * doubles + 3 * triples + 4 * home_runs;
+    total_bases + i64::from(stats.walks) + i64::from(stats.stolen_bases)
+        - i64::from(stats.caught_stealing)
+}
+
+/// How much a pitcher contributed over a season, for the best pitcher award.
+fn pitching_value(stats: &PitchingStats) -> i64 {
+    i64::from(stats.outs_recorded) + i64::from(stats.strikeouts)
+        - 2 * i64::from(stats.runs_allowed)
+        - i64::from(stats.walks)
+        - i64::from(stats.hits_allowed)
+}
+
+/// The player with the highest value, with ties going to the lower player ID so the result is
+/// stable.
+fn best<T>(stats: &BTreeMap<PlayerId, T>, value: impl Fn(&T) -> i64) -> Option<PlayerId> {
+    stats
+        .iter()
+        .map(|(player, stats)| (value(stats), *player))
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
+        .map(|(_, player)| player)
+}
+
+/// The leader among `players` in each category, leaving out categories nobody recorded any of.
+fn leaders(
+    stats: &SeasonStats,
+    players: &BTreeSet<PlayerId>,
+) -> BTreeMap<AwardCategory, (PlayerId, u32)> {
+    let mut leaders = BTreeMap::new();
+    for category in AwardCategory::ALL {
+        let leader = players
+            .iter()
+            .map(|player| {
+                let total = category.total(stats.batting.get(player), stats.pitching.get(player));
+                (total, *player)
+            })
+            .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        if let Some((total, player)) = leader.filter(|(total, _)| *total > 0) {
+            leaders.insert(category, (player, total));
+        }
+    }
+    leaders
+}
+
+impl Database {
+    fn player_name(&self, player: PlayerId) -> String {
+        self.players
+            .get(&player)
+            .map_or_else(|| player.to_string(), |data| data.name.clone())
+    }
+
+    /// The league of the team a player is on, or `None` (the main league) if they aren't on one.
+    fn player_league(&self, player: PlayerId) -> Option<LeagueId> {
+        let (team, _) = self.roster_slot_of(player).ok()?;
+        self.teams.get(&team)?.league
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Work out a season's awards from its stats and the idol board, and keep them (see
+    /// [`Sim::awards`]). Handing out a season's awards again replaces them.
+    ///
+    /// Returns a list of events describing each award. Each award is also recorded in the
+    /// [audit log](Sim::audit_log) as a [`Mutation::AwardWon`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the season has no stats.
+    pub fn hand_out_awards(&mut self, season: u16) -> Result<Vec<String>, DatabaseError> {
+        let stats = self
+            .stats(season)
+            .ok_or(DatabaseError::NoStats { season })?;
+        let mut players_by_league = BTreeMap::<_, BTreeSet<_>>::new();
+        for player in stats.batting.keys().chain(stats.pitching.keys()) {
+            let league = self.database.player_league(*player);
+            players_by_league.entry(league).or_default().insert(*player);
+        }
+        let mut league_leaders = players_by_league
+            .iter()
+            .map(|(league, players)| (*league, leaders(stats, players)))
+            .collect::<BTreeMap<_, _>>();
+        let awards = SeasonAwards {
+            season,
+            best_batter: best(&stats.batting, batting_value),
+            best_pitcher: best(&stats.pitching, pitching_value),
+            idol: self.database.idols.top(1).first().copied(),
+            leaders: league_leaders.remove(&None).unwrap_or_default(),
+            league_leaders: league_leaders
+                .into_iter()
+                .filter_map(|(league, leaders)| Some((league?, leaders)))
+                .collect(),
+        };
+
+        let database = &self.database;
+        let season_name = u32::from(season) + 1;
+        let mut events = Vec::new();
+        let mut won = Vec::new();
+        let mut announce = |player: Option<PlayerId>, award: &str| {
+            if let Some(player) = player {
+                let name = database.player_name(player);
+                events.push(format!("{name} is the {award} of Season {season_name}."));
+                won.push((player, award.to_owned(), None));
+            }
+        };
+        announce(awards.best_batter, "Best Batter");
+        announce(awards.best_pitcher, "Best Pitcher");
+        announce(awards.idol, "Idol");
+        let leagues = std::iter::once((None, &awards.leaders)).chain(
+            awards
+                .league_leaders
+                .iter()
+                .map(|(league, leaders)| (Some(*league), leaders)),
+        );
+        for (league, leaders) in leagues {
+            let league_name = match league.and_then(|league| database.leagues.get(&league)) {
+                Some(data) => data.name.clone(),
+                None => "league".to_owned(),
+            };
+            for (category, (player, total)) in leaders {
+                let name = database.player_name(*player);
+                events.push(format!(
+                    "{name} leads the {league_name} with {total} {category}."
+                ));
+                won.push((*player, format!("{category} leader"), league));
+            }
+        }
+
+        for (player, award, league) in won {
+            self.database.record(Mutation::AwardWon {
+                season,
+                player,
+                award,
+                league,
+            });
+        }
+        let awards_kept = &mut self.database.history.awards;
+        awards_kept.retain(|kept| kept.season != season);
+        let index = awards_kept.partition_point(|kept| kept.season < season);
+        awards_kept.insert(index, awards);
+        Ok(events)
+    }
+
+    /// The awards handed out for a season, if they have been.
+    pub fn awards(&self, season: u16) -> Option<&SeasonAwards> {
+        self.database
+            .history
+            .awards
+            .iter()
+            .find(|awards| awards.season == season)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{batting_value, AwardCategory};
+    use crate::id::LeagueId;
+    use crate::{BattingStats, League, Mutation, Sim};
+
+    #[test]
+    fn awards() {
+        let mut sim = crate::sim::tests::two_games();
+        assert!(sim.hand_out_awards(0).is_err());
+        sim.finish_day();
+        let pitcher = sim.games_today()[0].teams.home.pitcher.unwrap();
+        sim.add_idol_votes([(pitcher, 10)]).unwrap();
+        let league = League {
+            id: LeagueId::new(),
+            name: "Short Circuits".into(),
+        };
+        sim.add_league(league.clone()).unwrap();
+        let other_game = sim.games_today()[1].clone();
+        for team in other_game.teams.iter() {
+            sim.update_team(team.id, |team| team.league = Some(league.id))
+                .unwrap();
+        }
+
+        sim.take_audit_log();
+        let events = sim.hand_out_awards(0).unwrap();
+        let awards = sim.awards(0).unwrap().clone();
+        let stats = sim.stats(0).unwrap();
+        assert_eq!(awards.idol, Some(pitcher));
+        let best = awards.best_batter.unwrap();
+        let value = batting_value(&stats.batting[&best]);
+        assert!(stats.batting.values().all(|s| batting_value(s) <= value));
+
+        // each league has its own leaders
+        let in_league = |player| {
+            other_game.teams.iter().any(|team| {
+                sim.teams()
+                    .get(&team.id)
+                    .unwrap()
+                    .roster()
+                    .any(|p| *p == player)
+            })
+        };
+        let (leader, hits) = awards.leaders[&AwardCategory::Hits];
+        assert!(!in_league(leader));
+        assert_eq!(stats.batting[&leader].hits, hits);
+        let side_leaders = &awards.league_leaders[&league.id];
+        let (side_leader, side_hits) = side_leaders[&AwardCategory::Hits];
+        assert!(in_league(side_leader));
+        for (player, batting) in &stats.batting {
+            let most = if in_league(*player) { side_hits } else { hits };
+            assert!(batting.hits <= most);
+        }
+        assert!(events
+            .iter()
+            .any(|event| event.ends_with("is the Idol of Season 1.")));
+        assert!(events
+            .iter()
+            .any(|event| event.contains("leads the Short Circuits with")));
+        assert_eq!(events.len(), 3 + awards.leaders.len() + side_leaders.len());
+
+        // every award is in the audit log
+        let log = sim.take_audit_log();
+        assert_eq!(log.len(), events.len());
+        assert!(log.iter().any(|entry| entry.mutation
+            == Mutation::AwardWon {
+                season: 0,
+                player: side_leader,
+                award: "hits leader".into(),
+                league: Some(league.id),
+            }));
+
+        // handing out the awards again replaces them, and they're kept in the saved sim
+        sim.hand_out_awards(0).unwrap();
+        let json = serde_json::to_string(&sim).unwrap();
+        let other: Sim = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.awards(0), Some(&awards));
+        assert_eq!(other.awards(1), None);
+    }
+
+    #[test]
+    fn inconsistent_stats() {
+        // more extra-base hits than hits, as an edited save might have
+        let stats = BattingStats {
+            hits: 1,
+            home_runs: 2,
+            ..BattingStats::default()
+        };
+        assert_eq!(batting_value(&stats), 7);
+    }
+}
@@ -1,6 +1,10 @@
+use crate::database::{CheckEntity, Database};
 use crate::id::{BallparkId, TeamId};
+use crate::{DatabaseError, RngSource};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[non_exhaustive]
 pub struct Ballpark {
     pub id: BallparkId,
@@ -45,3 +49,42 @@ impl Default for Ballpark {
         }
     }
 }
+
+impl Ballpark {
+    /// Generate a new ballpark for a team. Attributes that default to 0.5 are rolled between 0.4
+    /// and 0.6; the rest start at their defaults.
+    pub(crate) fn generate(rng: &mut impl RngSource, team_id: TeamId, name: String) -> Ballpark {
+        let mut roll = || rng.next_range(0.4, 0.6);
+        Ballpark {
+            id: BallparkId::new(),
+            team_id,
+            name,
+            ominousness: roll(),
+            forwardness: roll(),
+            obtuseness: roll(),
+            grandiosity: roll(),
+            fortification: roll(),
+            elongation: roll(),
+            inconvenience: roll(),
+            viscosity: roll(),
+            mysticism: roll(),
+            ..Ballpark::default()
+        }
+    }
+}
+
+impl CheckEntity for Ballpark {
+    fn problems(&self, database: &Database) -> Vec<DatabaseError> {
+        let mut problems = Vec::new();
+        if self.id.0.is_nil() {
+            problems.push(DatabaseError::NilId);
+        }
+        if !database.teams.contains_key(&self.team_id) {
+            problems.push(DatabaseError::BadReference {
+                kind: "team",
+                id: self.team_id.0,
+            });
+        }
+        problems
+    }
+}
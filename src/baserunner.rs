@@ -0,0 +1,216 @@
+use crate::id::PlayerId;
+use crate::Game;
+use serde::{Deserialize, Serialize};
+
+/// A runner on base in a game. See [`Game::baserunners`].
+///
+/// Saves from before baserunners were structs stored each one as a `[player, base]` pair, which
+/// still loads, as a runner who reached some [other](Reached::Other) way with no responsible
+/// pitcher.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(from = "BaserunnerRepr")]
+#[non_exhaustive]
+pub struct Baserunner {
+    pub player: PlayerId,
+    /// The base the runner is on, starting from 1 for first base.
+    pub base: u8,
+    pub reached: Reached,
+    /// Whether the runner has to move up if the batter reaches base, because every base behind
+    /// them is occupied. The sim works this out again after each play and never reads it, so
+    /// setting it has no effect.
+    #[serde(default)]
+    pub forced: bool,
+    /// The pitcher charged if the runner scores: the one pitching when they reached base.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pitcher: Option<PlayerId>,
+}
+
+impl Baserunner {
+    /// A runner put on `base` some [other](Reached::Other) way, with no responsible pitcher.
+    pub fn new(player: PlayerId, base: u8) -> Baserunner {
+        Baserunner {
+            player,
+            base,
+            reached: Reached::Other,
+            forced: false,
+            pitcher: None,
+        }
+    }
+
+    pub(crate) fn reached(
+        player: PlayerId,
+        base: u8,
+        reached: Reached,
+        pitcher: Option<PlayerId>,
+    ) -> Baserunner {
+        Baserunner {
+            reached,
+            pitcher,
+            ..Baserunner::new(player, base)
+        }
+    }
+
+    /// This runner on another base.
+    pub(crate) fn to(&self, base: u8) -> Baserunner {
+        Baserunner {
+            base,
+            ..self.clone()
+        }
+    }
+}
+
+/// How a runner got on base.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Reached {
+    Walk,
+    HitByPitch,
+    /// A base hit, including a bunt the batter beat out.
+    Hit,
+    /// Put on base by editing the game or building it mid-inning, or loaded from an old save.
+    #[default]
+    Other,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+enum BaserunnerRepr {
+    Pair(PlayerId, u8),
+    Struct {
+        player: PlayerId,
+        base: u8,
+        #[serde(default)]
+        reached: Reached,
+        #[serde(default)]
+        forced: bool,
+        #[serde(default)]
+        pitcher: Option<PlayerId>,
+    },
+}
+
+impl From<BaserunnerRepr> for Baserunner {
+    fn from(repr: BaserunnerRepr) -> Baserunner {
+        match repr {
+            BaserunnerRepr::Pair(player, base) => Baserunner::new(player, base),
+            BaserunnerRepr::Struct {
+                player,
+                base,
+                reached,
+                forced,
+                pitcher,
+            } => Baserunner {
+                player,
+                base,
+                reached,
+                forced,
+                pitcher,
+            },
+        }
+    }
+}
+
+impl Game {
+    /// Work out which runners are forced, after the runners have moved.
+    pub(crate) fn update_forced(&mut self) {
+        let occupied = self.bases_occupied();
+        for runner in &mut self.baserunners {
+            runner.forced = (1..runner.base).all(|base| occupied.contains(&base));
+        }
+    }
+
+    /// Each runner and the base they're on, as recorded in a [`Play`](crate::Play).
+    pub(crate) fn runner_positions(&self) -> Vec<(PlayerId, u8)> {
+        self.baserunners
+            .iter()
+            .map(|runner| (runner.player, runner.base))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Baserunner, Reached};
+    use crate::id::PlayerId;
+    use serde_json::json;
+
+    #[test]
+    fn old_saves() {
+        let player = PlayerId::new();
+        let old: Baserunner = serde_json::from_value(json!([player, 2])).unwrap();
+        assert_eq!(old, Baserunner::new(player, 2));
+
+        let pitcher = PlayerId::new();
+        let runner = Baserunner {
+            forced: true,
+            ..Baserunner::reached(player, 1, Reached::Walk, Some(pitcher))
+        };
+        let value = serde_json::to_value(&runner).unwrap();
+        assert_eq!(value["reached"], "walk");
+        assert_eq!(serde_json::from_value::<Baserunner>(value).unwrap(), runner);
+    }
+
+    #[test]
+    fn stale_forced() {
+        #[derive(Debug)]
+        struct AlwaysBall;
+
+        impl crate::Formulas for AlwaysBall {
+            fn strike(&self, _pitch: &crate::Pitch<'_>) -> f64 {
+                0.0
+            }
+
+            fn swing(&self, _pitch: &crate::Pitch<'_>, _strike: bool) -> f64 {
+                0.0
+            }
+        }
+
+        // a save that says nobody is forced still walks the runner on first to second
+        let mut sim = crate::sim::tests::two_games();
+        sim.set_formulas(AlwaysBall);
+        sim.tick_until(|games| games[0].at_bat.is_some());
+        let game = sim.games_today()[0].clone();
+        let batting = sim
+            .teams()
+            .get(&game.teams.select(game.inning.batting()).id);
+        let runner = batting.unwrap().shadows[0];
+        sim.update_game(game.id, |game| {
+            game.balls = 3;
+            game.baserunners.push(Baserunner::new(runner, 1));
+        })
+        .unwrap();
+        sim.database.games_today[0].baserunners[0].forced = false;
+        sim.tick_until(|games| games[0].plays.len() > game.plays.len());
+        let game = &sim.games_today()[0];
+        let play = game.plays.last().unwrap();
+        assert_eq!(play.event, crate::PlayEvent::Walk);
+        assert_eq!(play.baserunners_after, [(runner, 2), (play.batter, 1)]);
+        assert!(game.baserunners.iter().all(|runner| runner.forced));
+    }
+
+    #[test]
+    fn runners() {
+        let mut sim = crate::sim::tests::two_games();
+        let mut walked = false;
+        while sim.games_today().iter().any(|game| !game.is_finished()) {
+            sim.tick();
+            for game in sim.games_today() {
+                let occupied = game.bases_occupied();
+                let pitcher = game.teams.select(game.inning.fielding()).pitcher;
+                for runner in &game.baserunners {
+                    assert_eq!(
+                        runner.forced,
+                        (1..runner.base).all(|base| occupied.contains(&base))
+                    );
+                    assert_ne!(runner.reached, Reached::Other);
+                    assert!(runner.pitcher.is_some());
+                    walked |= runner.reached == Reached::Walk && runner.pitcher == pitcher;
+                }
+            }
+        }
+        assert!(walked);
+    }
+}
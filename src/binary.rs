@@ -0,0 +1,100 @@
+use crate::{RngSource, Sim};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{Read, Write};
+
+// `Sim` relies on `#[serde(flatten)]` and internally tagged enums, which only work with
+// self-describing formats; CBOR is the most compact one that round-trips everything.
+
+#[derive(Debug, thiserror::Error)]
+pub enum BinaryError {
+    #[error("failed to encode sim")]
+    Encode(#[from] ciborium::ser::Error<std::io::Error>),
+    #[error("failed to decode sim")]
+    Decode(#[from] ciborium::de::Error<std::io::Error>),
+}
+
+impl<R: RngSource + Serialize> Sim<R> {
+    /// Write the sim in a compact binary format (CBOR), including the RNG state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_binary<W: Write>(&self, writer: W) -> Result<(), BinaryError> {
+        Ok(ciborium::into_writer(self, writer)?)
+    }
+
+    /// Serialize the sim in a compact binary format (CBOR), including the RNG state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sim cannot be encoded.
+    pub fn to_binary(&self) -> Result<Vec<u8>, BinaryError> {
+        let mut buf = Vec::new();
+        self.write_binary(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+impl<R: RngSource + DeserializeOwned> Sim<R> {
+    /// Read a sim written by [`Sim::write_binary`] or [`Sim::to_binary`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, or if the data is not a valid sim (including if the
+    /// database is inconsistent).
+    pub fn read_binary<Rd: Read>(reader: Rd) -> Result<Sim<R>, BinaryError> {
+        Ok(ciborium::from_reader(reader)?)
+    }
+
+    /// Deserialize a sim produced by [`Sim::to_binary`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a valid sim (including if the database is
+    /// inconsistent).
+    pub fn from_binary(bytes: &[u8]) -> Result<Sim<R>, BinaryError> {
+        Sim::read_binary(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AwayHome, Date, Expansion, Game, RosterSize, Sim, Team, TeamId};
+
+    fn sim() -> Sim {
+        let mut sim = Sim::seeded(1, 2);
+        let mut teams = Vec::new();
+        for nickname in ["Firefighters", "Lovers"] {
+            let team = Team {
+                id: TeamId::new(),
+                nickname: nickname.into(),
+                ..Team::default()
+            };
+            let expansion = Expansion {
+                roster: Some(RosterSize::STANDARD),
+                ballpark: Some(format!("{} Park", nickname)),
+                ..Expansion::default()
+            };
+            teams.push(sim.add_expansion_team(team, expansion).unwrap());
+        }
+        let game = Game::new(AwayHome {
+            away: teams[0],
+            home: teams[1],
+        });
+        sim.start_day(Date::default(), vec![game]).unwrap();
+        for _ in 0..50 {
+            sim.tick();
+        }
+        sim
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let sim = sim();
+        let rebuilt: Sim = Sim::from_binary(&sim.to_binary()?)?;
+        assert_eq!(rebuilt, sim);
+        assert!(sim.to_binary()?.len() < serde_json::to_vec(&sim)?.len());
+        Ok(())
+    }
+}
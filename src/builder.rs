@@ -0,0 +1,207 @@
+use crate::database::CheckEntity;
+use crate::id::{GameId, LeagueId, PlayerId, TeamId};
+use crate::sim::{BALLS_NEEDED, OUTS_NEEDED, STRIKES_NEEDED};
+use crate::{
+    AwayHome, Baserunner, DatabaseError, Game, Inning, Player, RngSource, Sim, Team, Weather,
+};
+use std::collections::BTreeSet;
+
+// Each builder wraps the object it builds, starting from its default (with a new random ID), and
+// has a setter for each field.
+macro_rules! builder {
+    (
+        $(#[$meta:meta])*
+        $builder:ident for $ty:ident { $($field:ident: $field_ty:ty,)* }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        #[must_use]
+        pub struct $builder($ty);
+
+        impl $builder {
+            $(
+                pub fn $field(mut self, $field: impl Into<$field_ty>) -> $builder {
+                    self.0.$field = $field.into();
+                    self
+                }
+            )*
+
+            pub fn build(self) -> $ty {
+                self.0
+            }
+        }
+    };
+}
+
+builder! {
+    /// Builds a [`Player`]. Created with [`Player::builder`].
+    PlayerBuilder for Player {
+        id: PlayerId,
+        name: String,
+        thwackability: f64,
+        moxie: f64,
+        divinity: f64,
+        musclitude: f64,
+        patheticism: f64,
+        buoyancy: f64,
+        base_thirst: f64,
+        laserlikeness: f64,
+        ground_friction: f64,
+        continuation: f64,
+        indulgence: f64,
+        martyrdom: f64,
+        tragicness: f64,
+        shakespearianism: f64,
+        suppression: f64,
+        unthwackability: f64,
+        coldness: f64,
+        overpowerment: f64,
+        ruthlessness: f64,
+        omniscience: f64,
+        tenaciousness: f64,
+        watchfulness: f64,
+        anticapitalism: f64,
+        chasiness: f64,
+        pressurization: f64,
+        cinnamon: f64,
+        soul: u16,
+        peanut_allergy: bool,
+        fate: u8,
+        blood: u8,
+        coffee: u8,
+        mods: Vec<String>,
+    }
+}
+
+builder! {
+    /// Builds a [`Team`]. Created with [`Team::builder`].
+    TeamBuilder for Team {
+        id: TeamId,
+        location: String,
+        nickname: String,
+        shorthand: String,
+        emoji: String,
+        main_color: String,
+        secondary_color: String,
+        lineup: Vec<PlayerId>,
+        rotation: Vec<PlayerId>,
+        shadows: Vec<PlayerId>,
+        rotation_slot: usize,
+        mods: Vec<String>,
+        league: Option<LeagueId>,
+    }
+}
+
+builder! {
+    /// Builds a [`Game`]. Created with [`Game::builder`].
+    GameBuilder for Game {
+        id: GameId,
+        inning: Inning,
+        at_bat: Option<PlayerId>,
+        balls: u8,
+        strikes: u8,
+        outs: u8,
+        baserunners: Vec<Baserunner>,
+        weather: Weather,
+    }
+}
+
+impl GameBuilder {
+    pub fn runs(mut self, runs: AwayHome<u16>) -> GameBuilder {
+        for (team, runs) in self.0.teams.iter_mut().zip(runs) {
+            team.runs = runs;
+        }
+        self
+    }
+
+    pub fn pitchers(mut self, pitchers: AwayHome<Option<PlayerId>>) -> GameBuilder {
+        for (team, pitcher) in self.0.teams.iter_mut().zip(pitchers) {
+            team.pitcher = pitcher;
+        }
+        self
+    }
+
+    /// Put `runner` on `base`, in addition to any runners already set.
+    pub fn baserunner(mut self, runner: PlayerId, base: u8) -> GameBuilder {
+        self.0.baserunners.push(Baserunner::new(runner, base));
+        self
+    }
+
+    /// Build the game, checking that it refers only to teams and players in `sim` and that its
+    /// state is one a game could actually be in, so it can be passed to [`Sim::start_day`] to
+    /// play out a scenario from the middle of a game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game refers to anything not in `sim`, or if:
+    /// - the count or number of outs would already have ended the plate appearance or inning
+    /// - a runner is on a base that doesn't exist, or two runners share a base
+    /// - a player is both batting and on base, or on base twice
+    /// - a team's pitcher is not on that team's roster
+    pub fn try_build<R: RngSource>(self, sim: &Sim<R>) -> Result<Game, DatabaseError> {
+        let mut game = self.0;
+        game.check(&sim.database)?;
+        let invalid = |reason| DatabaseError::InvalidGameState {
+            game: game.id,
+            reason,
+        };
+        if game.balls >= BALLS_NEEDED {
+            return Err(invalid("too many balls"));
+        }
+        if game.strikes >= STRIKES_NEEDED {
+            return Err(invalid("too many strikes"));
+        }
+        if game.outs >= OUTS_NEEDED {
+            return Err(invalid("too many outs"));
+        }
+        let mut bases = BTreeSet::new();
+        let mut runners = game.at_bat.into_iter().collect::<BTreeSet<_>>();
+        for runner in &game.baserunners {
+            if runner.base == 0 || runner.base >= game.home_base() {
+                return Err(invalid("runner on a base that doesn't exist"));
+            }
+            if !bases.insert(runner.base) {
+                return Err(invalid("two runners on the same base"));
+            }
+            if !runners.insert(runner.player) {
+                return Err(invalid("player is batting or on base more than once"));
+            }
+        }
+        for data in game.teams.iter() {
+            if let Some(pitcher) = data.pitcher {
+                if !data.id.load(&sim.database).roster().any(|p| *p == pitcher) {
+                    return Err(invalid("pitcher is not on their team's roster"));
+                }
+            }
+        }
+        game.update_forced();
+        Ok(game)
+    }
+}
+
+impl Player {
+    /// Start building a player with a new random ID and every attribute set to zero.
+    pub fn builder() -> PlayerBuilder {
+        PlayerBuilder(Player {
+            id: PlayerId::new(),
+            ..Player::default()
+        })
+    }
+}
+
+impl Team {
+    /// Start building a team with a new random ID and an empty roster.
+    pub fn builder() -> TeamBuilder {
+        TeamBuilder(Team {
+            id: TeamId::new(),
+            ..Team::default()
+        })
+    }
+}
+
+impl Game {
+    /// Start building a game between `teams`, like [`Game::new`].
+    pub fn builder(teams: AwayHome<TeamId>) -> GameBuilder {
+        GameBuilder(Game::new(teams))
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{Date, RngSource, Sim};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    #[error("failed to access checkpoint {}", .path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("failed to read checkpoint {}", .path.display())]
+    Json {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("no checkpoint for season {}, day {}", .0.season, .0.day)]
+    NotFound(Date),
+}
+
+/// A directory of sim checkpoints, one per date.
+///
+/// Checkpoints are JSON files named like `season-11-day-42.json`, or `season-11-day-42.json.gz`
+/// when compressed. Saving a checkpoint for a date that already has one replaces it.
+#[derive(Debug, Clone)]
+pub struct Checkpoints {
+    dir: PathBuf,
+    compress: bool,
+    interval: u16,
+}
+
+impl Checkpoints {
+    /// Keep checkpoints in `dir`, which is created when the first checkpoint is saved. By
+    /// default, checkpoints are compressed and [`Checkpoints::save_if_due`] saves every day.
+    pub fn new(dir: impl Into<PathBuf>) -> Checkpoints {
+        Checkpoints {
+            dir: dir.into(),
+            compress: true,
+            interval: 1,
+        }
+    }
+
+    /// Whether to gzip new checkpoints. Both kinds can be restored either way.
+    #[must_use]
+    pub fn compress(mut self, compress: bool) -> Checkpoints {
+        self.compress = compress;
+        self
+    }
+
+    /// Save a checkpoint every `interval` days (by day number) in
+    /// [`Checkpoints::save_if_due`].
+    #[must_use]
+    pub fn interval(mut self, interval: u16) -> Checkpoints {
+        self.interval = interval.max(1);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Save a checkpoint of `sim` for its current date.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint can't be written.
+    pub fn save<R: RngSource + Serialize>(&self, sim: &Sim<R>) -> Result<PathBuf, CheckpointError> {
+        let path = self.path(sim.date(), self.compress);
+        let io_err = |source| CheckpointError::Io {
+            path: path.clone(),
+            source,
+        };
+        fs::create_dir_all(&self.dir).map_err(io_err)?;
+        // write to a temporary file first so a crash mid-write doesn't clobber a good checkpoint
+        let tmp = path.with_extension("tmp");
+        let file = BufWriter::new(File::create(&tmp).map_err(io_err)?);
+        let json_err = |source| CheckpointError::Json {
+            path: path.clone(),
+            source,
+        };
+        if self.compress {
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            serde_json::to_writer(&mut encoder, sim).map_err(json_err)?;
+            encoder
+                .finish()
+                .and_then(|mut f| f.flush())
+                .map_err(io_err)?;
+        } else {
+            let mut file = file;
+            serde_json::to_writer(&mut file, sim).map_err(json_err)?;
+            file.flush().map_err(io_err)?;
+        }
+        fs::rename(&tmp, &path).map_err(io_err)?;
+        // don't leave the other kind of checkpoint around for the same date
+        let other = self.path(sim.date(), !self.compress);
+        if other.exists() {
+            fs::remove_file(&other).map_err(io_err)?;
+        }
+        Ok(path)
+    }
+
+    /// Save a checkpoint if the sim's day is a multiple of the interval and there isn't one for
+    /// that date yet. Call this after each day of a long run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint can't be written.
+    pub fn save_if_due<R: RngSource + Serialize>(
+        &self,
+        sim: &Sim<R>,
+    ) -> Result<Option<PathBuf>, CheckpointError> {
+        let date = sim.date();
+        if !date.day.is_multiple_of(self.interval) || self.find(date).is_some() {
+            return Ok(None);
+        }
+        self.save(sim).map(Some)
+    }
+
+    /// The dates with checkpoints, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory can't be read. A directory that doesn't exist yet has
+    /// no checkpoints.
+    pub fn list(&self) -> Result<Vec<Date>, CheckpointError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => {
+                return Err(CheckpointError::Io {
+                    path: self.dir.clone(),
+                    source,
+                })
+            }
+        };
+        let mut dates = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|source| CheckpointError::Io {
+                path: self.dir.clone(),
+                source,
+            })?;
+            if let Some(date) = entry.file_name().to_str().and_then(parse_name) {
+                dates.push(date);
+            }
+        }
+        dates.sort();
+        dates.dedup();
+        Ok(dates)
+    }
+
+    /// The most recent checkpoint's date, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory can't be read.
+    pub fn latest(&self) -> Result<Option<Date>, CheckpointError> {
+        Ok(self.list()?.pop())
+    }
+
+    /// Load the checkpoint for `date`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no checkpoint for `date`, or if it can't be read.
+    pub fn restore<R: RngSource + DeserializeOwned>(
+        &self,
+        date: Date,
+    ) -> Result<Sim<R>, CheckpointError> {
+        let (path, compressed) = self.find(date).ok_or(CheckpointError::NotFound(date))?;
+        let file = BufReader::new(File::open(&path).map_err(|source| CheckpointError::Io {
+            path: path.clone(),
+            source,
+        })?);
+        let result = if compressed {
+            serde_json::from_reader(BufReader::new(GzDecoder::new(file)))
+        } else {
+            serde_json::from_reader(file)
+        };
+        result.map_err(|source| CheckpointError::Json { path, source })
+    }
+
+    fn find(&self, date: Date) -> Option<(PathBuf, bool)> {
+        [true, false]
+            .into_iter()
+            .map(|compressed| (self.path(date, compressed), compressed))
+            .find(|(path, _)| path.exists())
+    }
+
+    fn path(&self, date: Date, compressed: bool) -> PathBuf {
+        self.dir.join(format!(
+            "season-{}-day-{}.json{}",
+            date.season,
+            date.day,
+            if compressed { ".gz" } else { "" }
+        ))
+    }
+}
+
+fn parse_name(name: &str) -> Option<Date> {
+    let name = name.strip_suffix(".gz").unwrap_or(name);
+    let (season, day) = name
+        .strip_prefix("season-")?
+        .strip_suffix(".json")?
+        .split_once("-day-")?;
+    Some(Date {
+        season: season.parse().ok()?,
+        day: day.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Checkpoints;
+    use crate::{Date, Sim};
+
+    #[test]
+    fn save_and_restore() {
+        let dir = std::env::temp_dir().join(format!("simx-checkpoints-{}", std::process::id()));
+        let mut sim = Sim::seeded(1, 2);
+        for compress in [true, false] {
+            let checkpoints = Checkpoints::new(&dir).compress(compress).interval(2);
+            for day in 0..4 {
+                sim.start_day(Date { season: 1, day }, Vec::new()).unwrap();
+                checkpoints.save_if_due(&sim).unwrap();
+            }
+            assert_eq!(
+                checkpoints.list().unwrap(),
+                [Date { season: 1, day: 0 }, Date { season: 1, day: 2 }]
+            );
+            let restored: Sim = checkpoints.restore(Date { season: 1, day: 2 }).unwrap();
+            assert_eq!(restored.date(), Date { season: 1, day: 2 });
+            assert!(checkpoints.restore::<crate::Rng>(Date::default()).is_err());
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Loading historical data from [Chronicler](https://github.com/xSke/Chronicler) v2 entity
+//! dumps, such as the responses from `/v2/versions?type=player`.
+
+use crate::api::{Player, Stadium, Team};
+use crate::{DatabaseError, Problem, RngSource, Sim};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// One version of an entity.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Version<T> {
+    pub entity_id: Uuid,
+    /// An RFC 3339 timestamp in UTC.
+    pub valid_from: String,
+    pub data: T,
+}
+
+/// A page of results from the `/v2/versions` or `/v2/entities` endpoints.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Page<T> {
+    pub items: Vec<Version<T>>,
+    pub next_page: Option<String>,
+}
+
+/// Versions of every entity type simx can load.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct Versions {
+    pub players: Vec<Version<Player>>,
+    pub teams: Vec<Version<Team>>,
+    pub stadiums: Vec<Version<Stadium>>,
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Add every player, team, and ballpark as it was at `timestamp` (an RFC 3339 timestamp in
+    /// UTC). Entities with no version valid at `timestamp` are skipped.
+    ///
+    /// Entities that can't be added (see [`Sim::add_player`], [`Sim::add_team`], and
+    /// [`Sim::add_ballpark`]) are skipped too, and returned as problems. Dumps are often
+    /// incomplete, so a team whose roster names a player missing from the dump is left out
+    /// rather than failing the whole load, along with its ballpark.
+    ///
+    /// Timestamps are compared as strings after normalizing the UTC offset, which is correct for
+    /// the format Chronicler uses.
+    pub fn load_chronicler(&mut self, versions: Versions, timestamp: &str) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        for player in as_of(versions.players, timestamp) {
+            let player = crate::Player::from(player);
+            let id = player.id.0;
+            if let Err(error) = self.add_player(player) {
+                problems.push(skipped("player", id, error));
+            }
+        }
+        for team in as_of(versions.teams, timestamp) {
+            let team = crate::Team::from(team);
+            let id = team.id.0;
+            if let Err(error) = self.add_team(team) {
+                problems.push(skipped("team", id, error));
+            }
+        }
+        for stadium in as_of(versions.stadiums, timestamp) {
+            let ballpark = crate::Ballpark::from(stadium);
+            let id = ballpark.id.0;
+            if let Err(error) = self.add_ballpark(ballpark) {
+                problems.push(skipped("ballpark", id, error));
+            }
+        }
+        problems
+    }
+}
+
+fn skipped(kind: &'static str, id: Uuid, error: DatabaseError) -> Problem {
+    Problem {
+        kind,
+        id: Some(id),
+        error,
+    }
+}
+
+fn as_of<T>(versions: Vec<Version<T>>, timestamp: &str) -> Vec<T> {
+    let timestamp = normalize(timestamp);
+    let mut latest: BTreeMap<Uuid, (String, T)> = BTreeMap::new();
+    for version in versions {
+        let valid_from = normalize(&version.valid_from);
+        if valid_from > timestamp {
+            continue;
+        }
+        match latest.get(&version.entity_id) {
+            Some((existing, _)) if *existing >= valid_from => {}
+            _ => {
+                latest.insert(version.entity_id, (valid_from, version.data));
+            }
+        }
+    }
+    latest.into_values().map(|(_, data)| data).collect()
+}
+
+fn normalize(timestamp: &str) -> String {
+    timestamp
+        .strip_suffix('Z')
+        .or_else(|| timestamp.strip_suffix("+00:00"))
+        .unwrap_or(timestamp)
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Page, Versions};
+    use crate::id::{PlayerId, TeamId};
+    use crate::{DatabaseError, Sim};
+    use serde_json::json;
+
+    const NAGOMI: &str = "b7adbbcc-0679-43f3-a939-07f009a393db";
+    const MISSING: &str = "a1628d97-16ca-4a75-b8df-569bae02bef9";
+    const TIGERS: &str = "747b8e4a-7e50-4638-a973-ea7950a3e739";
+    const CRABS: &str = "8d87c468-699a-47a8-b40d-cfb73a5660ad";
+
+    fn versions() -> Versions {
+        let players: Page<_> = serde_json::from_value(json!({
+            "nextPage": null,
+            "items": [
+                {
+                    "entityId": NAGOMI,
+                    "validFrom": "2020-08-01T00:00:00Z",
+                    "data": {"id": NAGOMI, "name": "Nagomi Mcdaniel", "moxie": 0.25},
+                },
+                {
+                    "entityId": NAGOMI,
+                    "validFrom": "2020-09-01T00:00:00.000+00:00",
+                    "data": {"id": NAGOMI, "name": "Nagomi Mcdaniel", "moxie": 0.5},
+                },
+                {
+                    "entityId": NAGOMI,
+                    "validFrom": "2020-10-01T00:00:00Z",
+                    "data": {"id": NAGOMI, "name": "Nagomi Mcdaniel", "moxie": 0.75},
+                },
+            ],
+        }))
+        .unwrap();
+        let teams: Page<_> = serde_json::from_value(json!({
+            "nextPage": "AAAA",
+            "items": [
+                {
+                    "entityId": TIGERS,
+                    "validFrom": "2020-08-01T00:00:00Z",
+                    "data": {"id": TIGERS, "lineup": [NAGOMI], "rotation": [MISSING]},
+                },
+                {
+                    "entityId": CRABS,
+                    "validFrom": "2020-08-01T00:00:00Z",
+                    "data": {"id": CRABS, "lineup": [NAGOMI]},
+                },
+            ],
+        }))
+        .unwrap();
+        let stadiums: Page<_> = serde_json::from_value(json!({
+            "nextPage": null,
+            "items": [{
+                "entityId": "cfb57d7c-4118-4b0a-85cc-4e3a51a66cb6",
+                "validFrom": "2020-08-01T00:00:00Z",
+                "data": {"id": "cfb57d7c-4118-4b0a-85cc-4e3a51a66cb6", "teamId": TIGERS},
+            }],
+        }))
+        .unwrap();
+        Versions {
+            players: players.items,
+            teams: teams.items,
+            stadiums: stadiums.items,
+        }
+    }
+
+    #[test]
+    fn as_of() {
+        let mut sim = Sim::seeded(1, 2);
+        sim.load_chronicler(versions(), "2020-09-15T00:00:00Z");
+        let nagomi = sim
+            .players()
+            .get(&NAGOMI.parse::<PlayerId>().unwrap())
+            .unwrap();
+        assert!((nagomi.moxie - 0.5).abs() < f64::EPSILON);
+
+        let mut sim = Sim::seeded(1, 2);
+        sim.load_chronicler(versions(), "2020-07-01T00:00:00Z");
+        assert!(sim.players().is_empty());
+    }
+
+    #[test]
+    fn missing_player() {
+        let mut sim = Sim::seeded(1, 2);
+        let problems = sim.load_chronicler(versions(), "2020-09-15T00:00:00Z");
+        let tigers = TIGERS.parse::<TeamId>().unwrap();
+        assert!(sim.teams().get(&tigers).is_none());
+        assert!(sim.teams().get(&CRABS.parse().unwrap()).is_some());
+        assert!(sim.ballparks().is_empty());
+
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].kind, "team");
+        assert_eq!(problems[0].id, Some(tigers.0));
+        assert!(matches!(
+            problems[0].error,
+            DatabaseError::BadReference { kind: "player", id } if id.to_string() == MISSING
+        ));
+        assert_eq!(problems[1].kind, "ballpark");
+        assert!(sim.validate().is_ok());
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{Game, Rng, RngSource};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+/// Seeds the commentary stream, which is derived from the game and how far into it the pitch is.
+/// Commentary never touches the sim's RNG, so it can't change how a game plays out.
+const COMMENTARY_SEED: (u64, u64) = (0x636f_6d6d_656e_7461, 0x7279_2073_7472_6d00);
+
+/// Play-by-play flavor for a pitch. This is chosen by its own RNG after the sim has decided what
+/// the pitch does, and nothing in the sim reads it back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct PitchDetail {
+    pub pitch_type: PitchType,
+    /// Where the pitch crossed the plate, for pitches the batter didn't swing at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<PitchLocation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum PitchType {
+    Fastball,
+    Sinker,
+    Cutter,
+    Slider,
+    Curveball,
+    Changeup,
+    Splitter,
+    Knuckleball,
+}
+
+/// Where a pitch the batter took crossed the plate. The first four are in the strike zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum PitchLocation {
+    DownTheMiddle,
+    OnTheCorner,
+    AtTheKnees,
+    AtTheLetters,
+    High,
+    Low,
+    Inside,
+    Outside,
+    InTheDirt,
+}
+
+impl PitchType {
+    /// Each pitch type, with how often it's thrown relative to the others.
+    const WEIGHTS: [(PitchType, f64); 8] = [
+        (PitchType::Fastball, 35.0),
+        (PitchType::Sinker, 10.0),
+        (PitchType::Cutter, 8.0),
+        (PitchType::Slider, 15.0),
+        (PitchType::Curveball, 12.0),
+        (PitchType::Changeup, 12.0),
+        (PitchType::Splitter, 5.0),
+        (PitchType::Knuckleball, 3.0),
+    ];
+
+    fn roll(rng: &mut impl RngSource) -> PitchType {
+        let total = PitchType::WEIGHTS
+            .iter()
+            .map(|(_, weight)| weight)
+            .sum::<f64>();
+        let mut roll = rng.next_labeled("pitch type") * total;
+        PitchType::WEIGHTS
+            .into_iter()
+            .find(|(_, weight)| {
+                roll -= weight;
+                roll < 0.0
+            })
+            .map_or(PitchType::Fastball, |(pitch_type, _)| pitch_type)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            PitchType::Fastball => "Fastball",
+            PitchType::Sinker => "Sinker",
+            PitchType::Cutter => "Cutter",
+            PitchType::Slider => "Slider",
+            PitchType::Curveball => "Curveball",
+            PitchType::Changeup => "Changeup",
+            PitchType::Splitter => "Splitter",
+            PitchType::Knuckleball => "Knuckleball",
+        }
+    }
+}
+
+impl PitchLocation {
+    const IN_ZONE: [PitchLocation; 4] = [
+        PitchLocation::DownTheMiddle,
+        PitchLocation::OnTheCorner,
+        PitchLocation::AtTheKnees,
+        PitchLocation::AtTheLetters,
+    ];
+    const OUT_OF_ZONE: [PitchLocation; 5] = [
+        PitchLocation::High,
+        PitchLocation::Low,
+        PitchLocation::Inside,
+        PitchLocation::Outside,
+        PitchLocation::InTheDirt,
+    ];
+
+    fn roll(rng: &mut impl RngSource, in_zone: bool) -> PitchLocation {
+        let choices = if in_zone {
+            &PitchLocation::IN_ZONE[..]
+        } else {
+            &PitchLocation::OUT_OF_ZONE[..]
+        };
+        rng.choose_labeled("pitch location", choices)
+            .copied()
+            .unwrap_or(PitchLocation::DownTheMiddle)
+    }
+
+    /// Whether the location is in the strike zone.
+    pub fn is_in_zone(self) -> bool {
+        PitchLocation::IN_ZONE.contains(&self)
+    }
+}
+
+impl Display for PitchType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name().to_lowercase())
+    }
+}
+
+impl Display for PitchLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PitchLocation::DownTheMiddle => "down the middle",
+            PitchLocation::OnTheCorner => "on the corner",
+            PitchLocation::AtTheKnees => "at the knees",
+            PitchLocation::AtTheLetters => "at the letters",
+            PitchLocation::High => "high",
+            PitchLocation::Low => "low",
+            PitchLocation::Inside => "inside",
+            PitchLocation::Outside => "outside",
+            PitchLocation::InTheDirt => "in the dirt",
+        })
+    }
+}
+
+/// Written as the start of a sentence, like "Curveball on the corner".
+impl Display for PitchDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.pitch_type.name())?;
+        if let Some(location) = self.location {
+            write!(f, " {location}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Rolls commentary for one pitch of a game.
+pub(crate) struct Commentary(Rng);
+
+impl Commentary {
+    /// The commentary stream for the next play of `game`.
+    pub(crate) fn new(game: &Game) -> Commentary {
+        let key = game.id.0.as_u128() ^ (game.plays.len() as u128);
+        Commentary(Rng::derived(COMMENTARY_SEED, key))
+    }
+
+    pub(crate) fn pitch(&mut self) -> PitchDetail {
+        PitchDetail {
+            pitch_type: PitchType::roll(&mut self.0),
+            location: None,
+        }
+    }
+
+    /// Add where a pitch the batter took crossed the plate.
+    pub(crate) fn locate(&mut self, detail: &mut PitchDetail, in_zone: bool) {
+        detail.location = Some(PitchLocation::roll(&mut self.0, in_zone));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PlayEvent;
+
+    #[test]
+    fn pitch_detail() {
+        let mut sim = crate::sim::tests::two_games();
+        let mut other = sim.clone();
+        sim.set_tracing(true);
+        while !sim.games_today()[0].is_finished() {
+            sim.tick();
+            // commentary never draws from the sim's RNG
+            assert!(sim
+                .trace()
+                .unwrap()
+                .iter()
+                .all(|roll| !matches!(roll.label, Some("pitch type" | "pitch location"))));
+        }
+
+        let game = &sim.games_today()[0];
+        let mut called = 0;
+        for play in &game.plays {
+            let Some(detail) = play.pitch else {
+                assert!(matches!(
+                    play.event,
+                    PlayEvent::StolenBase { .. } | PlayEvent::CaughtStealing { .. }
+                ));
+                continue;
+            };
+            match play.event {
+                PlayEvent::Strike { swinging: false } => {
+                    assert!(detail.location.unwrap().is_in_zone());
+                    let text = format!("{detail}. Strike, looking.");
+                    assert!(play.description.starts_with(&text));
+                    called += 1;
+                }
+                PlayEvent::Ball | PlayEvent::Walk => {
+                    assert!(!detail.location.unwrap().is_in_zone());
+                }
+                PlayEvent::Strike { swinging: true } => assert_eq!(detail.location, None),
+                _ => {}
+            }
+        }
+        assert!(called > 0);
+
+        // the same game gets the same commentary
+        other.finish_day();
+        let pitches =
+            |game: &crate::Game| game.plays.iter().map(|play| play.pitch).collect::<Vec<_>>();
+        assert_eq!(pitches(&other.games_today()[0]), pitches(game));
+    }
+}
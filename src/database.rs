@@ -1,18 +1,155 @@
-use crate::id::{PlayerId, TeamId};
-use crate::{Date, Game, Player, Team};
+use crate::election::OpenElection;
+use crate::formulas::FormulaSet;
+use crate::history::History;
+use crate::id::{BallparkId, GameId, LeagueId, PlayerId, TeamId};
+use crate::{
+    AuditEntry, Ballpark, Date, EmptyRotation, Forecast, Game, GameRules, IdolBoard, League,
+    MachinePolicy, Player, RatingConfig, RosterPosition, RosterSlot, SeasonConfig, SeasonStats,
+    Table, Team, TeamIdentity,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Display};
 use uuid::Uuid;
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct Database {
     #[serde(flatten)]
     pub(crate) date: Date,
+    #[serde(default)]
+    pub(crate) season_config: SeasonConfig,
+    /// See [`Sim::set_home_advantage`](crate::Sim::set_home_advantage).
+    #[serde(default)]
+    pub(crate) home_advantage: f64,
+    /// See [`Sim::set_empty_rotation`](crate::Sim::set_empty_rotation).
+    #[serde(default)]
+    pub(crate) empty_rotation: EmptyRotation,
+    /// See [`Sim::set_machine_policy`](crate::Sim::set_machine_policy).
+    #[serde(default)]
+    pub(crate) machine_policy: MachinePolicy,
+    /// See [`Sim::set_rating_config`](crate::Sim::set_rating_config).
+    #[serde(default)]
+    pub(crate) rating_config: RatingConfig,
+    /// See [`Sim::rating`](crate::Sim::rating).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) ratings: BTreeMap<TeamId, f64>,
+    /// See [`Sim::set_rules`](crate::Sim::set_rules).
+    #[serde(default)]
+    pub(crate) regular_season_rules: GameRules,
+    #[serde(default)]
+    pub(crate) postseason_rules: GameRules,
+    /// The number of ticks since the day started.
+    #[serde(default)]
+    pub(crate) tick: u64,
 
-    pub(crate) teams: BTreeMap<TeamId, Team>,
-    pub(crate) players: BTreeMap<PlayerId, Player>,
+    pub(crate) teams: Table<TeamId, Team>,
+    pub(crate) players: Table<PlayerId, Player>,
+    #[serde(default)]
+    pub(crate) ballparks: Table<BallparkId, Ballpark>,
+    /// Leagues other than the main league. See [`Sim::leagues`](crate::Sim::leagues).
+    #[serde(default, skip_serializing_if = "Table::is_empty")]
+    pub(crate) leagues: Table<LeagueId, League>,
 
     pub(crate) games_today: Vec<Game>,
+
+    /// See [`Sim::forecast`](crate::Sim::forecast).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) forecast: Vec<Forecast>,
+
+    /// See [`Sim::free_agents`](crate::Sim::free_agents).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) free_agents: Vec<PlayerId>,
+
+    #[serde(default)]
+    pub(crate) idols: IdolBoard,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) audit_log: Vec<AuditEntry>,
+
+    /// Stats for each season with a finished game, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) stats: Vec<SeasonStats>,
+
+    #[serde(default, skip_serializing_if = "History::is_empty")]
+    pub(crate) history: History,
+    /// Rebrands waiting for the next season. See [`Sim::rebrand_team`](crate::Sim::rebrand_team).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) rebrands: BTreeMap<TeamId, TeamIdentity>,
+
+    /// An election accepting votes from outside simx.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) election: Option<OpenElection>,
+
+    #[serde(skip)]
+    pub(crate) check_policy: CheckPolicy,
+    #[serde(skip)]
+    pub(crate) formulas: FormulaSet,
+    /// See [`Sim::set_play_descriptions`](crate::Sim::set_play_descriptions).
+    #[serde(skip)]
+    pub(crate) skip_descriptions: bool,
+    /// See [`Sim::set_audit_log`](crate::Sim::set_audit_log).
+    #[serde(skip)]
+    pub(crate) skip_audit: bool,
+}
+
+// The check policy, formulas, and whether plays keep descriptions or changes are audited are
+// settings rather than part of the sim's state, so they aren't compared.
+impl PartialEq for Database {
+    fn eq(&self, other: &Self) -> bool {
+        let Database {
+            date,
+            season_config,
+            home_advantage,
+            empty_rotation,
+            machine_policy,
+            rating_config,
+            ratings,
+            regular_season_rules,
+            postseason_rules,
+            tick,
+            teams,
+            players,
+            ballparks,
+            leagues,
+            games_today,
+            forecast,
+            free_agents,
+            idols,
+            audit_log,
+            stats,
+            history,
+            rebrands,
+            election,
+            check_policy: _,
+            formulas: _,
+            skip_descriptions: _,
+            skip_audit: _,
+        } = self;
+        *date == other.date
+            && *season_config == other.season_config
+            && *home_advantage == other.home_advantage
+            && *empty_rotation == other.empty_rotation
+            && *machine_policy == other.machine_policy
+            && *rating_config == other.rating_config
+            && *ratings == other.ratings
+            && *regular_season_rules == other.regular_season_rules
+            && *postseason_rules == other.postseason_rules
+            && *tick == other.tick
+            && *teams == other.teams
+            && *players == other.players
+            && *ballparks == other.ballparks
+            && *leagues == other.leagues
+            && *games_today == other.games_today
+            && *forecast == other.forecast
+            && *free_agents == other.free_agents
+            && *idols == other.idols
+            && *audit_log == other.audit_log
+            && *stats == other.stats
+            && *history == other.history
+            && *rebrands == other.rebrands
+            && *election == other.election
+    }
 }
 
 impl Database {
@@ -21,45 +158,181 @@ impl Database {
     // mush) in the core sim, we will define some invariants about what must be correct in the
     // database. This function checks those invariants during:
     //
-    // 1. The `Deserialize` implementation of `Database`
-    // 2. When debug assertions are enabled, any mutable methods on `Sim`
+    // 1. The `Deserialize` implementation of `Sim`, after any schema migrations
+    // 2. After ticks and other mutable methods on `Sim`, depending on the `CheckPolicy` (by
+    //    default, only when debug assertions are enabled)
     pub(crate) fn check_consistency(&self) -> Result<(), String> {
+        let report = self.validate();
+        if report.is_ok() {
+            Ok(())
+        } else {
+            Err(report.to_string())
+        }
+    }
+
+    pub(crate) fn validate(&self) -> ValidationReport {
         let mut problems = Vec::new();
 
         macro_rules! key_check {
             ($iter:expr, $kind:expr) => {
                 for (key, obj) in $iter {
                     if obj.id != *key {
-                        problems.push(format!("- {} {}: keyed with {}", $kind, obj.id, key));
+                        problems.push(Problem {
+                            kind: $kind,
+                            id: Some(obj.id.0),
+                            error: DatabaseError::KeyMismatch { key: key.0 },
+                        });
                     }
                 }
             };
         }
         key_check!(&self.teams, "team");
         key_check!(&self.players, "player");
+        key_check!(&self.ballparks, "ballpark");
+        key_check!(&self.leagues, "league");
 
         macro_rules! check_method {
             ($iter:expr, $kind:expr) => {
                 for obj in $iter {
                     for error in CheckEntity::problems(obj, self) {
-                        problems.push(format!("- {} {}: {}", $kind, obj.id, error));
+                        problems.push(Problem {
+                            kind: $kind,
+                            id: Some(obj.id.0),
+                            error,
+                        });
                     }
                 }
             };
         }
         check_method!(self.teams.values(), "team");
         check_method!(self.players.values(), "player");
+        check_method!(self.ballparks.values(), "ballpark");
+        check_method!(self.leagues.values(), "league");
         check_method!(&self.games_today, "game");
 
-        if problems.is_empty() {
-            Ok(())
-        } else {
-            Err(problems.join("\n"))
+        let mut seen = HashSet::new();
+        for player in &self.free_agents {
+            let mut problem = |error| {
+                problems.push(Problem {
+                    kind: "free agent",
+                    id: Some(player.0),
+                    error,
+                });
+            };
+            if !self.players.contains_key(player) {
+                problem(DatabaseError::BadReference {
+                    kind: "player",
+                    id: player.0,
+                });
+            }
+            if !seen.insert(*player) {
+                problem(DatabaseError::DuplicatePlayer { player: *player });
+            }
+            if let Ok((team, _)) = self.roster_slot_of(*player) {
+                problem(DatabaseError::PlayerInUse {
+                    player: *player,
+                    kind: "team",
+                    id: team.0,
+                });
+            }
+        }
+
+        for team in self.rebrands.keys() {
+            if !self.teams.contains_key(team) {
+                problems.push(Problem {
+                    kind: "pending rebrand",
+                    id: Some(team.0),
+                    error: DatabaseError::BadReference {
+                        kind: "team",
+                        id: team.0,
+                    },
+                });
+            }
+        }
+
+        for player in self.idols.players() {
+            if !self.players.contains_key(player) {
+                problems.push(Problem {
+                    kind: "idol board",
+                    id: None,
+                    error: DatabaseError::BadReference {
+                        kind: "player",
+                        id: player.0,
+                    },
+                });
+            }
+        }
+
+        ValidationReport { problems }
+    }
+
+    /// Everything in the database that refers to a player, as `(kind, id)` pairs.
+    pub(crate) fn player_references(&self, player: PlayerId) -> Vec<(&'static str, Uuid)> {
+        let mut references = Vec::new();
+        for team in self.teams.values() {
+            if team.roster().any(|p| *p == player) {
+                references.push(("team", team.id.0));
+            }
+        }
+        for game in &self.games_today {
+            if game.players().any(|p| *p == player) {
+                references.push(("game", game.id.0));
+            }
+        }
+        if self.idols.players().any(|p| *p == player) {
+            references.push(("idol board", Uuid::nil()));
+        }
+        if self.free_agents.contains(&player) {
+            references.push(("free agents", Uuid::nil()));
         }
+        references
     }
 
-    pub(crate) fn debug_check(&self) {
-        debug_assert_eq!(self.check_consistency(), Ok(()));
+    pub(crate) fn check_after_mutation(&self) {
+        if self.check_policy == CheckPolicy::AfterMutation {
+            assert_eq!(self.check_consistency(), Ok(()));
+        }
+    }
+
+    pub(crate) fn check_after_tick(&self) {
+        if self.check_policy != CheckPolicy::Never {
+            assert_eq!(self.check_consistency(), Ok(()));
+        }
+    }
+
+    pub(crate) fn try_check_after_tick(&self) -> Result<(), DatabaseError> {
+        if self.check_policy != CheckPolicy::Never {
+            let report = self.validate();
+            if !report.is_ok() {
+                return Err(DatabaseError::Inconsistent(report));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// When the sim checks its database for consistency problems (which are always bugs, either in
+/// simx or in a custom [`RngSource`](crate::RngSource)). A failed check panics.
+///
+/// Checks are always run when deserializing a sim, regardless of this policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckPolicy {
+    /// Never check. This is the default when debug assertions are disabled.
+    Never,
+    /// Check after every tick.
+    AfterTick,
+    /// Check after every tick and every other method that changes the database. This is the
+    /// default when debug assertions are enabled.
+    AfterMutation,
+}
+
+impl Default for CheckPolicy {
+    fn default() -> CheckPolicy {
+        if cfg!(debug_assertions) {
+            CheckPolicy::AfterMutation
+        } else {
+            CheckPolicy::Never
+        }
     }
 }
 
@@ -67,10 +340,117 @@ impl Database {
 pub enum DatabaseError {
     #[error("object ID is nil")]
     NilId,
+    #[error("keyed with {key}")]
+    KeyMismatch { key: Uuid },
     #[error("reference to nonexistent {kind} {id}")]
     BadReference { kind: &'static str, id: Uuid },
     #[error("player {player} is on the roster multiple times")]
     DuplicatePlayer { player: PlayerId },
+    #[error("player {player} is not on any team's roster")]
+    NotRostered { player: PlayerId },
+    #[error("player {player} is not a free agent")]
+    NotFreeAgent { player: PlayerId },
+    #[error("player {player} is already in the database")]
+    PlayerExists { player: PlayerId },
+    #[error("player {player} is not in team {team}'s lineup")]
+    NotInLineup { team: TeamId, player: PlayerId },
+    #[error("player {player} is not in team {team}'s shadows")]
+    NotInShadows { team: TeamId, player: PlayerId },
+    #[error("player {player} can't pitch for the team in the field in game {game}")]
+    CannotPitch { game: GameId, player: PlayerId },
+    #[error("{kind} {id} {} mod {name}", if *present { "already has" } else { "doesn't have" })]
+    ModConflict {
+        kind: &'static str,
+        id: Uuid,
+        name: String,
+        present: bool,
+    },
+    #[error("name is empty")]
+    EmptyName,
+    #[error("{value} is not a finite number")]
+    NotFinite { value: f64 },
+    #[error("team {team} has no player in {slot}")]
+    EmptySlot { team: TeamId, slot: RosterSlot },
+    #[error("{slot} is out of range for team {team}")]
+    SlotOutOfRange { team: TeamId, slot: RosterSlot },
+    #[error("team {team} needs at least {minimum} players in its {position}")]
+    RosterTooSmall {
+        team: TeamId,
+        position: RosterPosition,
+        minimum: usize,
+    },
+    #[error("database is inconsistent:\n{0}")]
+    Inconsistent(ValidationReport),
+    #[error("nobody is on base {base} in game {game}")]
+    EmptyBase { game: GameId, base: u8 },
+    #[error("game {game} is in an invalid state: {reason}")]
+    InvalidGameState { game: GameId, reason: &'static str },
+    #[error("game {game} is already over")]
+    GameOver { game: GameId },
+    #[error("game {game} is scheduled more than once")]
+    DuplicateGame { game: GameId },
+    #[error("no election is open")]
+    NoElection,
+    #[error("an election is already open")]
+    ElectionOpen,
+    #[error("there is no {kind} {index} on the ballot")]
+    NoSuchProposal { kind: &'static str, index: usize },
+    #[error("no games have finished in season {season}")]
+    NoStats { season: u16 },
+    #[error("game {game} is between teams in different leagues")]
+    CrossLeague { game: GameId },
+    #[error("team {team} is not in the election's league")]
+    NotInLeague { team: TeamId },
+    #[error("player {player} is still referenced by {kind} {id}")]
+    PlayerInUse {
+        player: PlayerId,
+        kind: &'static str,
+        id: Uuid,
+    },
+}
+
+/// A problem with an object in the database.
+#[derive(Debug)]
+pub struct Problem {
+    /// What kind of object has the problem, like `"team"` or `"player"`.
+    pub kind: &'static str,
+    /// The object's ID, if it has one.
+    pub id: Option<Uuid>,
+    pub error: DatabaseError,
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(id) = self.id {
+            write!(f, "{} {}: {}", self.kind, id, self.error)
+        } else {
+            write!(f, "{}: {}", self.kind, self.error)
+        }
+    }
+}
+
+/// Every problem found by [`Sim::validate`](crate::Sim::validate).
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub problems: Vec<Problem>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, problem) in self.problems.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "- {}", problem)?;
+        }
+        Ok(())
+    }
 }
 
 pub(crate) trait CheckEntity {
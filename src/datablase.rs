@@ -0,0 +1,217 @@
+use crate::id::{GameId, PlayerId, TeamId};
+use crate::sim::{HOME_BASE, OUTS_NEEDED};
+use crate::{
+    AwayHome, Game, Play, PlayEvent, RngSource, RunnerMove, RunnerResult, Sim, TeamSelect,
+};
+use serde::Serialize;
+
+/// A row in the shape of SIBR's Datablase `game_events` table: one per plate appearance, plus one
+/// per stolen base attempt.
+///
+/// Columns simx doesn't model (sacrifices, double plays, errors, and so on) are omitted.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+#[allow(clippy::struct_excessive_bools)] // the table's shape, not ours
+pub struct GameEvent {
+    pub game_id: GameId,
+    pub season: u16,
+    pub day: u16,
+    pub event_index: usize,
+    pub event_type: &'static str,
+    /// Starts from 0 for the first inning.
+    pub inning: usize,
+    pub top_of_inning: bool,
+    pub outs_before_play: u8,
+    pub outs_on_play: u8,
+    pub batter_id: PlayerId,
+    pub batter_team_id: TeamId,
+    pub pitcher_id: PlayerId,
+    pub pitcher_team_id: TeamId,
+    /// The score before the play.
+    pub home_score: u16,
+    pub away_score: u16,
+    pub pitches: Vec<&'static str>,
+    pub total_strikes: usize,
+    pub total_balls: usize,
+    pub total_fouls: usize,
+    pub is_last_event_for_plate_appearance: bool,
+    pub bases_hit: u8,
+    pub runs_batted_in: u16,
+    pub batter_base_after_play: u8,
+    /// Occupied bases before the play, starting from 1 for first base.
+    pub bases_occupied_before: Vec<u8>,
+    /// Occupied bases after the play, starting from 1 for first base.
+    pub bases_occupied_after: Vec<u8>,
+    pub event_text: Vec<String>,
+    /// Every runner on the play, including the batter if the play ended their plate appearance.
+    pub base_runners: Vec<GameEventBaseRunner>,
+}
+
+/// A row in the shape of the Datablase `game_event_base_runners` table: where one runner went on
+/// a play. See [`Play::runner_moves`](crate::Play::runner_moves).
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+#[allow(clippy::struct_excessive_bools)] // the table's shape, not ours
+pub struct GameEventBaseRunner {
+    pub runner_id: PlayerId,
+    /// Starts from 1 for first base, or 0 for the batter.
+    pub base_before_play: u8,
+    /// The home base if the runner scored, or 0 if they were put out.
+    pub base_after_play: u8,
+    pub was_base_stolen: bool,
+    pub was_caught_stealing: bool,
+}
+
+fn base_runners(game: &Game, play: &Play) -> Vec<GameEventBaseRunner> {
+    play.runner_moves()
+        .into_iter()
+        .map(|RunnerMove { runner, from, to }| GameEventBaseRunner {
+            runner_id: runner,
+            base_before_play: from,
+            base_after_play: match to {
+                RunnerResult::Base(base) => base,
+                RunnerResult::Scored => game.home_base(),
+                RunnerResult::Out => 0,
+            },
+            was_base_stolen: play.event
+                == PlayEvent::StolenBase {
+                    runner,
+                    base: from + 1,
+                },
+            was_caught_stealing: play.event
+                == PlayEvent::CaughtStealing {
+                    runner,
+                    base: from + 1,
+                },
+        })
+        .collect()
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Every plate appearance and stolen base attempt in today's games so far, as Datablase
+    /// `game_events` rows.
+    pub fn game_events(&self) -> Vec<GameEvent> {
+        self.games_today()
+            .iter()
+            .flat_map(|game| self.datablase_events(game))
+            .collect()
+    }
+
+    fn datablase_events(&self, game: &Game) -> Vec<GameEvent> {
+        let date = self.database.date;
+        let mut events = Vec::new();
+        let mut score = AwayHome { away: 0, home: 0 };
+        let mut pitches = Vec::new();
+        let mut event_text = Vec::new();
+        for play in &game.plays {
+            let batting = play.inning.batting();
+            let fielding = play.inning.fielding();
+            pitches.extend(pitch_code(&play.event));
+            event_text.push(play.description.clone());
+
+            let (event_type, bases_hit) = match &play.event {
+                PlayEvent::Walk => ("WALK", 0),
+                PlayEvent::HitByPitch => ("HIT_BY_PITCH", 0),
+                PlayEvent::Strikeout { .. } => ("STRIKEOUT", 0),
+                PlayEvent::Flyout { .. }
+                | PlayEvent::GroundOut { .. }
+                | PlayEvent::LineOut { .. }
+                | PlayEvent::PopOut { .. }
+                | PlayEvent::FoulOut { .. }
+                | PlayEvent::BuntPopout { .. } => ("OUT", 0),
+                PlayEvent::SacrificeBunt { .. } => ("SACRIFICE", 0),
+                PlayEvent::HomeRun => ("HOME_RUN", HOME_BASE),
+                PlayEvent::Hit { bases: 1 } | PlayEvent::BuntHit => ("SINGLE", 1),
+                PlayEvent::Hit { bases: 2 } => ("DOUBLE", 2),
+                PlayEvent::Hit { bases } => ("TRIPLE", *bases),
+                PlayEvent::StolenBase { .. } => ("STOLEN_BASE", 0),
+                PlayEvent::CaughtStealing { .. } => ("CAUGHT_STEALING", 0),
+                PlayEvent::Ball | PlayEvent::Strike { .. } | PlayEvent::Foul => {
+                    // not the end of anything; keep collecting pitches
+                    continue;
+                }
+                PlayEvent::AwardedRun { team } => {
+                    // not an event of its own, but later events' scores count it
+                    *score.select_mut(*team) += play.runs;
+                    continue;
+                }
+            };
+            let ends_plate_appearance = play.event.ends_plate_appearance();
+            let count = |code| pitches.iter().filter(|p| **p == code).count();
+            events.push(GameEvent {
+                game_id: game.id,
+                season: date.season,
+                day: date.day,
+                event_index: events.len(),
+                event_type,
+                inning: play.inning.number().saturating_sub(1),
+                top_of_inning: batting == TeamSelect::Away,
+                outs_before_play: play.outs_before,
+                outs_on_play: play.outs_on_play,
+                batter_id: play.batter,
+                batter_team_id: game.teams.select(batting).id,
+                pitcher_id: play.pitcher,
+                pitcher_team_id: game.teams.select(fielding).id,
+                home_score: score.home,
+                away_score: score.away,
+                pitches: pitches.clone(),
+                total_strikes: count("C") + count("S") + count("F") + count("X"),
+                total_balls: count("B"),
+                total_fouls: count("F"),
+                is_last_event_for_plate_appearance: ends_plate_appearance,
+                bases_hit,
+                runs_batted_in: if ends_plate_appearance { play.runs } else { 0 },
+                batter_base_after_play: if ends_plate_appearance {
+                    play.baserunners_after
+                        .iter()
+                        .find(|(runner, _)| *runner == play.batter)
+                        .map_or(
+                            if bases_hit == HOME_BASE { HOME_BASE } else { 0 },
+                            |(_, base)| *base,
+                        )
+                } else {
+                    0
+                },
+                bases_occupied_before: play.baserunners_before.iter().map(|(_, b)| *b).collect(),
+                bases_occupied_after: play.baserunners_after.iter().map(|(_, b)| *b).collect(),
+                event_text: std::mem::take(&mut event_text),
+                base_runners: base_runners(game, play),
+            });
+
+            *score.select_mut(batting) += play.runs;
+            if ends_plate_appearance || play.outs_before + play.outs_on_play >= OUTS_NEEDED {
+                pitches.clear();
+            }
+        }
+        events
+    }
+}
+
+/// The Datablase's code for a pitch: ball, called or swinging strike, foul, hit by pitch, or in
+/// play.
+fn pitch_code(event: &PlayEvent) -> Option<&'static str> {
+    match event {
+        PlayEvent::Ball | PlayEvent::Walk => Some("B"),
+        PlayEvent::Strike { swinging: false } | PlayEvent::Strikeout { swinging: false } => {
+            Some("C")
+        }
+        PlayEvent::Strike { swinging: true } | PlayEvent::Strikeout { swinging: true } => Some("S"),
+        PlayEvent::Foul => Some("F"),
+        PlayEvent::HitByPitch => Some("H"),
+        PlayEvent::Flyout { .. }
+        | PlayEvent::GroundOut { .. }
+        | PlayEvent::LineOut { .. }
+        | PlayEvent::PopOut { .. }
+        | PlayEvent::FoulOut { .. }
+        | PlayEvent::HomeRun
+        | PlayEvent::Hit { .. }
+        | PlayEvent::SacrificeBunt { .. }
+        | PlayEvent::BuntPopout { .. }
+        | PlayEvent::BuntHit => Some("X"),
+        PlayEvent::StolenBase { .. }
+        | PlayEvent::CaughtStealing { .. }
+        | PlayEvent::AwardedRun { .. } => None,
+    }
+}
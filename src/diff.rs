@@ -0,0 +1,223 @@
+use crate::id::{GameId, PlayerId, TeamId};
+use crate::{Baserunner, Date, Game, Inning, RngSource, RosterPosition, Sim};
+use serde::{Deserialize, Serialize};
+
+/// What changed between two states of a sim, produced by [`Sim::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SimDiff {
+    pub changes: Vec<Change>,
+}
+
+impl SimDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// A single changed field. Changes carry the new value.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[non_exhaustive]
+pub enum Change {
+    DateChanged {
+        date: Date,
+    },
+
+    /// A game was added to today's games. Its full state is in the newer sim.
+    GameAdded {
+        game: GameId,
+    },
+    GameRemoved {
+        game: GameId,
+    },
+    LastUpdate {
+        game: GameId,
+        last_update: String,
+    },
+    Score {
+        game: GameId,
+        team: TeamId,
+        runs: u16,
+    },
+    Inning {
+        game: GameId,
+        #[serde(flatten)]
+        inning: Inning,
+    },
+    Count {
+        game: GameId,
+        balls: u8,
+        strikes: u8,
+        outs: u8,
+    },
+    Batter {
+        game: GameId,
+        batter: Option<PlayerId>,
+    },
+    Pitcher {
+        game: GameId,
+        team: TeamId,
+        pitcher: Option<PlayerId>,
+    },
+    Baserunners {
+        game: GameId,
+        baserunners: Vec<Baserunner>,
+    },
+    Winner {
+        game: GameId,
+        team: TeamId,
+    },
+
+    PlayerAdded {
+        player: PlayerId,
+    },
+    PlayerRemoved {
+        player: PlayerId,
+    },
+    /// One or more of a player's attributes changed.
+    PlayerChanged {
+        player: PlayerId,
+    },
+
+    TeamAdded {
+        team: TeamId,
+    },
+    TeamRemoved {
+        team: TeamId,
+    },
+    /// Something about a team changed. If its roster changed, there is also a `RosterChanged`
+    /// for each changed position.
+    TeamChanged {
+        team: TeamId,
+    },
+    RosterChanged {
+        team: TeamId,
+        position: RosterPosition,
+        players: Vec<PlayerId>,
+    },
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Compare two states of a sim, such as before and after a tick.
+    ///
+    /// Games are matched by ID, so this works best on two states of the same sim.
+    pub fn diff(before: &Sim<R>, after: &Sim<R>) -> SimDiff {
+        let mut changes = Vec::new();
+        let (old, new) = (&before.database, &after.database);
+
+        if old.date != new.date {
+            changes.push(Change::DateChanged { date: new.date });
+        }
+
+        for game in &old.games_today {
+            if !new.games_today.iter().any(|g| g.id == game.id) {
+                changes.push(Change::GameRemoved { game: game.id });
+            }
+        }
+        for game in &new.games_today {
+            if let Some(old_game) = old.games_today.iter().find(|g| g.id == game.id) {
+                diff_game(&mut changes, old_game, game);
+            } else {
+                changes.push(Change::GameAdded { game: game.id });
+            }
+        }
+
+        for id in old.players.keys() {
+            if !new.players.contains_key(id) {
+                changes.push(Change::PlayerRemoved { player: *id });
+            }
+        }
+        for (id, player) in &new.players {
+            match old.players.get(id) {
+                None => changes.push(Change::PlayerAdded { player: *id }),
+                Some(old_player) if old_player != player => {
+                    changes.push(Change::PlayerChanged { player: *id });
+                }
+                Some(_) => {}
+            }
+        }
+
+        for id in old.teams.keys() {
+            if !new.teams.contains_key(id) {
+                changes.push(Change::TeamRemoved { team: *id });
+            }
+        }
+        for (id, team) in &new.teams {
+            match old.teams.get(id) {
+                None => changes.push(Change::TeamAdded { team: *id }),
+                Some(old_team) if old_team != team => {
+                    changes.push(Change::TeamChanged { team: *id });
+                    for position in RosterPosition::ALL {
+                        if old_team.players(position) != team.players(position) {
+                            changes.push(Change::RosterChanged {
+                                team: *id,
+                                position,
+                                players: team.players(position).to_vec(),
+                            });
+                        }
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        SimDiff { changes }
+    }
+}
+
+fn diff_game(changes: &mut Vec<Change>, old: &Game, new: &Game) {
+    let game = new.id;
+    if old.last_update != new.last_update {
+        changes.push(Change::LastUpdate {
+            game,
+            last_update: new.last_update.clone(),
+        });
+    }
+    for (old_team, new_team) in old.teams.as_ref().zip(new.teams.as_ref()) {
+        if old_team.runs != new_team.runs {
+            changes.push(Change::Score {
+                game,
+                team: new_team.id,
+                runs: new_team.runs,
+            });
+        }
+        if old_team.pitcher != new_team.pitcher {
+            changes.push(Change::Pitcher {
+                game,
+                team: new_team.id,
+                pitcher: new_team.pitcher,
+            });
+        }
+    }
+    if old.inning != new.inning {
+        changes.push(Change::Inning {
+            game,
+            inning: new.inning,
+        });
+    }
+    if (old.balls, old.strikes, old.outs) != (new.balls, new.strikes, new.outs) {
+        changes.push(Change::Count {
+            game,
+            balls: new.balls,
+            strikes: new.strikes,
+            outs: new.outs,
+        });
+    }
+    if old.at_bat != new.at_bat {
+        changes.push(Change::Batter {
+            game,
+            batter: new.at_bat,
+        });
+    }
+    if old.baserunners != new.baserunners {
+        changes.push(Change::Baserunners {
+            game,
+            baserunners: new.baserunners.clone(),
+        });
+    }
+    if let (None, Some(team)) = (old.winner, new.winner) {
+        changes.push(Change::Winner { game, team });
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{RngSource, Sim};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::{self, Display, Write};
+
+// Where to look first: the RNG is usually the earliest sign of nondeterminism, followed by the
+// state that it drives.
+const PRIORITY: &[&str] = &[
+    "rng",
+    "season",
+    "day",
+    "tick",
+    "games_today",
+    "players",
+    "teams",
+];
+
+/// A single field that differs between two sims, from [`Sim::divergence`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Divergence {
+    /// Where the field is in the sim's serialized form, like `games_today[0].teams.home.runs` or
+    /// `players.<id>.moxie`.
+    pub path: String,
+    /// The field's value in the first sim, or `None` if it's missing.
+    pub left: Option<Value>,
+    /// The field's value in the second sim, or `None` if it's missing.
+    pub right: Option<Value>,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<Value>| {
+            value
+                .as_ref()
+                .map_or_else(|| "(missing)".to_owned(), Value::to_string)
+        };
+        write!(
+            f,
+            "{}: {} != {}",
+            self.path,
+            show(&self.left),
+            show(&self.right)
+        )
+    }
+}
+
+impl<R: RngSource + Serialize> Sim<R> {
+    /// Compare this sim to another field by field, for tracking down nondeterminism across
+    /// platforms or changes to the simulation. Returns every difference, most telling first: the
+    /// RNG state, then the date and tick, today's games, players, teams, and everything else.
+    ///
+    /// RNG states (the sim's and any game's) are compared as a whole, and reported as a single
+    /// difference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either sim can't be represented as JSON, which would be a bug.
+    pub fn divergence(&self, other: &Sim<R>) -> Vec<Divergence> {
+        let left = serde_json::to_value(self).expect("sims serialize to JSON");
+        let right = serde_json::to_value(other).expect("sims serialize to JSON");
+        let mut divergences = Vec::new();
+        compare(
+            &mut String::new(),
+            Some(&left),
+            Some(&right),
+            false,
+            &mut divergences,
+        );
+        divergences
+    }
+}
+
+fn compare(
+    path: &mut String,
+    left: Option<&Value>,
+    right: Option<&Value>,
+    whole: bool,
+    out: &mut Vec<Divergence>,
+) {
+    if left == right {
+        return;
+    }
+    let len = path.len();
+    match (left, right) {
+        (Some(Value::Object(l)), Some(Value::Object(r))) if !whole => {
+            let mut keys = l.keys().chain(r.keys()).collect::<Vec<_>>();
+            keys.sort_by_key(|key| {
+                let priority = if path.is_empty() {
+                    PRIORITY.iter().position(|p| p == key)
+                } else {
+                    None
+                };
+                (priority.unwrap_or(PRIORITY.len()), *key)
+            });
+            keys.dedup();
+            for key in keys {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+                compare(path, l.get(key), r.get(key), key == "rng", out);
+                path.truncate(len);
+            }
+        }
+        (Some(Value::Array(l)), Some(Value::Array(r))) if !whole => {
+            for i in 0..l.len().max(r.len()) {
+                write!(path, "[{i}]").unwrap();
+                compare(path, l.get(i), r.get(i), false, out);
+                path.truncate(len);
+            }
+        }
+        _ => out.push(Divergence {
+            path: path.clone(),
+            left: left.cloned(),
+            right: right.cloned(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn divergence() {
+        let sim = crate::sim::tests::two_games();
+        assert!(sim.divergence(&sim.clone()).is_empty());
+
+        let mut other = sim.clone();
+        for _ in 0..5 {
+            other.tick();
+        }
+        let divergences = sim.divergence(&other);
+        assert_eq!(divergences[0].path, "rng");
+        assert_eq!(divergences[1].path, "tick");
+        assert!(divergences[2].path.starts_with("games_today[0]."));
+        assert_eq!(
+            divergences[1].to_string(),
+            format!("tick: 0 != {}", other.database.tick)
+        );
+    }
+}
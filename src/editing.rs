@@ -0,0 +1,135 @@
+use crate::id::PlayerId;
+use crate::{Attribute, Database, DatabaseError, Mutation, RngSource, Sim};
+use std::ops::RangeInclusive;
+
+/// The values [`Sim::set_attribute`] clamps to: the range a generated player's attributes fall
+/// in. Boosts can still push attributes past it.
+pub const ATTRIBUTE_RANGE: RangeInclusive<f64> = 0.0..=1.0;
+
+impl Database {
+    /// Boost each of a player's attributes (see [`Player::boost`](crate::Player::boost)).
+    pub(crate) fn boost_player(&mut self, player: PlayerId, amount: f64) {
+        player.load_mut(self).boost(amount);
+        self.record(Mutation::PlayerChanged { player });
+    }
+}
+
+fn check_finite(value: f64) -> Result<(), DatabaseError> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(DatabaseError::NotFinite { value })
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Change a player's name. Leading and trailing whitespace is trimmed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not in the database, or if the name is empty.
+    pub fn rename_player(&mut self, player: PlayerId, name: &str) -> Result<(), DatabaseError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DatabaseError::EmptyName);
+        }
+        let data = player.try_load_mut(&mut self.database)?;
+        if data.name == name {
+            return Ok(());
+        }
+        name.clone_into(&mut data.name);
+        self.database.record(Mutation::PlayerRenamed {
+            player,
+            name: name.to_owned(),
+        });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Set one of a player's attributes, clamped to [`ATTRIBUTE_RANGE`]. Returns the value the
+    /// attribute was set to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not in the database, or if `value` is NaN or infinite.
+    pub fn set_attribute(
+        &mut self,
+        player: PlayerId,
+        attribute: Attribute,
+        value: f64,
+    ) -> Result<f64, DatabaseError> {
+        check_finite(value)?;
+        let value = value.clamp(*ATTRIBUTE_RANGE.start(), *ATTRIBUTE_RANGE.end());
+        *player
+            .try_load_mut(&mut self.database)?
+            .attribute_mut(attribute) = value;
+        self.database.record(Mutation::PlayerChanged { player });
+        self.database.check_after_mutation();
+        Ok(value)
+    }
+
+    /// Boost each of a player's attributes by `amount`, as an election's blessing would (see
+    /// [`Player::boost`](crate::Player::boost)). A negative amount makes the player worse.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not in the database, or if `amount` is NaN or infinite.
+    pub fn apply_boost(&mut self, player: PlayerId, amount: f64) -> Result<(), DatabaseError> {
+        check_finite(amount)?;
+        player.try_load(&self.database)?;
+        self.database.boost_player(player, amount);
+        self.database.check_after_mutation();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attribute, DatabaseError, Mutation};
+
+    #[test]
+    fn editing() {
+        let mut sim = crate::sim::tests::two_games();
+        let team = sim.games_today()[0].teams.home.id;
+        let player = sim.teams().get(&team).unwrap().lineup[0];
+        sim.take_audit_log();
+
+        sim.rename_player(player, "  Nagomi Mcdaniel ").unwrap();
+        assert_eq!(sim.players().get(&player).unwrap().name, "Nagomi Mcdaniel");
+        assert!(matches!(
+            sim.rename_player(player, " "),
+            Err(DatabaseError::EmptyName)
+        ));
+
+        let moxie = sim.set_attribute(player, Attribute::Moxie, 1.5).unwrap();
+        assert!((moxie - 1.0).abs() < 1e-9);
+        let patheticism = sim
+            .set_attribute(player, Attribute::Patheticism, -0.5)
+            .unwrap();
+        assert!(patheticism.abs() < 1e-9);
+        assert!(sim
+            .set_attribute(player, Attribute::Moxie, f64::NAN)
+            .is_err());
+        let data = sim.players().get(&player).unwrap();
+        assert!((data.attribute(Attribute::Moxie) - 1.0).abs() < 1e-9);
+        assert!(data.patheticism.abs() < 1e-9);
+
+        let before = data.thwackability;
+        sim.apply_boost(player, 0.1).unwrap();
+        let data = sim.players().get(&player).unwrap();
+        assert!((data.thwackability - before - 0.1).abs() < 1e-9);
+        assert!((data.patheticism + 0.1).abs() < 1e-9);
+        assert!(sim.apply_boost(player, f64::INFINITY).is_err());
+        assert!(sim.apply_boost(crate::id::PlayerId::new(), 0.1).is_err());
+
+        let log = sim.take_audit_log();
+        assert_eq!(
+            log[0].mutation,
+            Mutation::PlayerRenamed {
+                player,
+                name: "Nagomi Mcdaniel".into(),
+            }
+        );
+        assert_eq!(log.len(), 4);
+    }
+}
@@ -0,0 +1,665 @@
+use crate::id::{LeagueId, PlayerId, TeamId};
+use crate::{
+    Database, DatabaseError, Mutation, Player, RngSource, RosterPosition, RosterSlot, Sim,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The ballot for an offseason election.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Election {
+    /// The [league](crate::League) holding the election, or `None` for the main league. Only
+    /// its teams can win blessings, enact wills, or be affected by decrees.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub league: Option<LeagueId>,
+    /// Decrees apply to every team in the league.
+    pub decrees: Vec<Proposal>,
+    /// Each blessing is won by a single team, drawn by lottery weighted by that team's votes.
+    pub blessings: Vec<Proposal>,
+    /// Each team enacts the will its fans voted for most, with ties broken by a roll.
+    #[serde(default)]
+    pub wills: Vec<Proposal>,
+    /// How many decrees pass, in order of most votes.
+    #[serde(alias = "decreesPassed")]
+    pub decrees_passed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Proposal {
+    pub title: String,
+    pub effect: Effect,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[non_exhaustive]
+pub enum Effect {
+    /// Boost every attribute of every player on the team by `amount`. See [`Player::boost`].
+    Boost { amount: f64 },
+    /// Add a modification to the team.
+    AddMod { name: String },
+    /// Generate a new player with the given name and add them to the team's lineup.
+    Recruit { name: String },
+    /// Swap a random lineup player with a random lineup player from another team in the league.
+    Exchange,
+}
+
+/// Vote totals for an [`Election`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Tally {
+    /// Votes for each decree, in the same order as `Election::decrees`. Missing entries are
+    /// counted as zero votes.
+    pub decrees: Vec<u64>,
+    /// Votes for each blessing by each team, in the same order as `Election::blessings`.
+    pub blessings: Vec<BTreeMap<TeamId, u64>>,
+    /// Each team's votes for each will, in the same order as `Election::wills`.
+    #[serde(default)]
+    pub wills: BTreeMap<TeamId, Vec<u64>>,
+}
+
+/// Votes from outside simx, for [`Sim::submit_votes`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[non_exhaustive]
+pub enum Votes {
+    /// Votes for a decree, by its index in `Election::decrees`.
+    Decree { decree: usize, votes: u64 },
+    /// Votes by a team's fans for a blessing, by its index in `Election::blessings`.
+    Blessing {
+        blessing: usize,
+        team: TeamId,
+        votes: u64,
+    },
+    /// Votes by a team's fans for a will, by its index in `Election::wills`.
+    Will {
+        will: usize,
+        team: TeamId,
+        votes: u64,
+    },
+    /// Votes for a player on the idol board. These can be submitted whether or not an election
+    /// is open.
+    Idol { player: PlayerId, votes: u64 },
+}
+
+/// An election resolved by [`Sim::resolve_election`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ElectionResults {
+    /// The votes as they stood when the election was resolved.
+    pub tally: Tally,
+    /// Events describing each outcome, as from [`Sim::run_election`].
+    pub events: Vec<String>,
+}
+
+/// An election accepting votes.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct OpenElection {
+    election: Election,
+    tally: Tally,
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Resolve an election and apply the effects of each winning decree and blessing.
+    ///
+    /// Returns a list of events describing each outcome.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tally references team IDs that are not in the database, or teams
+    /// outside the election's league. No effects are applied in this case.
+    pub fn run_election(
+        &mut self,
+        election: &Election,
+        tally: &Tally,
+    ) -> Result<Vec<String>, DatabaseError> {
+        for team in tally
+            .blessings
+            .iter()
+            .flat_map(BTreeMap::keys)
+            .chain(tally.wills.keys())
+        {
+            if team.try_load(&self.database)?.league != election.league {
+                return Err(DatabaseError::NotInLeague { team: *team });
+            }
+        }
+
+        let rng = &mut self.rng;
+        let database = &mut self.database;
+        let mut events = Vec::new();
+
+        // Decrees with the most votes pass; ties are broken by a roll.
+        let mut ranked = election
+            .decrees
+            .iter()
+            .enumerate()
+            .map(|(i, decree)| {
+                let votes = tally.decrees.get(i).copied().unwrap_or_default();
+                (votes, rng.next_f64(), decree)
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+        for (_, _, decree) in ranked.into_iter().take(election.decrees_passed) {
+            events.push(format!("Decree passed: {}", decree.title));
+            for team in database.league_teams(election.league) {
+                if let Some(event) = decree.effect.apply(rng, database, team) {
+                    events.push(event);
+                }
+            }
+        }
+
+        for (i, blessing) in election.blessings.iter().enumerate() {
+            let winner = tally
+                .blessings
+                .get(i)
+                .and_then(|votes| choose_weighted(rng, votes));
+            if let Some(team) = winner {
+                events.push(format!(
+                    "The {} won {}.",
+                    team.load(database).name(),
+                    blessing.title
+                ));
+                if let Some(event) = blessing.effect.apply(rng, database, team) {
+                    events.push(event);
+                }
+            }
+        }
+
+        for (team, votes) in &tally.wills {
+            let most = votes.iter().copied().max().unwrap_or_default();
+            if most == 0 {
+                continue;
+            }
+            let tied = election
+                .wills
+                .iter()
+                .zip(votes)
+                .filter(|(_, votes)| **votes == most)
+                .map(|(will, _)| will)
+                .collect::<Vec<_>>();
+            let will = if tied.len() == 1 {
+                tied.first().copied()
+            } else {
+                rng.choose(tied)
+            };
+            if let Some(will) = will {
+                events.push(format!(
+                    "The {} enacted {}.",
+                    team.load(database).name(),
+                    will.title
+                ));
+                if let Some(event) = will.effect.apply(rng, database, *team) {
+                    events.push(event);
+                }
+            }
+        }
+
+        database.check_after_mutation();
+        Ok(events)
+    }
+
+    /// Open an election to votes from outside simx, submitted with [`Sim::submit_votes`] and
+    /// resolved with [`Sim::resolve_election`]. The open election and its votes so far are part
+    /// of the sim's state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an election is already open.
+    pub fn open_election(&mut self, election: Election) -> Result<(), DatabaseError> {
+        if self.database.election.is_some() {
+            return Err(DatabaseError::ElectionOpen);
+        }
+        self.database.election = Some(OpenElection {
+            election,
+            tally: Tally::default(),
+        });
+        Ok(())
+    }
+
+    /// The open election and its votes so far, if there is one.
+    pub fn open_election_tally(&self) -> Option<(&Election, &Tally)> {
+        self.database
+            .election
+            .as_ref()
+            .map(|open| (&open.election, &open.tally))
+    }
+
+    /// Add votes to the open election (or the idol board), such as tallies collected by a
+    /// website. Votes accumulate across calls.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any votes are for a proposal that isn't on the open election's
+    /// ballot (or if no election is open), for a team or player that isn't in the database, or
+    /// from a team outside the election's league. No votes are added in this case.
+    pub fn submit_votes<I>(&mut self, votes: I) -> Result<(), DatabaseError>
+    where
+        I: IntoIterator<Item = Votes>,
+    {
+        let votes = votes.into_iter().collect::<Vec<_>>();
+        for vote in &votes {
+            self.check_votes(vote)?;
+        }
+
+        let mut idols = Vec::new();
+        for vote in votes {
+            let open = self.database.election.as_mut();
+            match (vote, open) {
+                (Votes::Decree { decree, votes }, Some(open)) => {
+                    let tally = &mut open.tally.decrees;
+                    if tally.len() <= decree {
+                        tally.resize(decree + 1, 0);
+                    }
+                    tally[decree] = tally[decree].saturating_add(votes);
+                }
+                (
+                    Votes::Blessing {
+                        blessing,
+                        team,
+                        votes,
+                    },
+                    Some(open),
+                ) => {
+                    let tally = &mut open.tally.blessings;
+                    if tally.len() <= blessing {
+                        tally.resize_with(blessing + 1, BTreeMap::new);
+                    }
+                    let entry = tally[blessing].entry(team).or_default();
+                    *entry = entry.saturating_add(votes);
+                }
+                (Votes::Will { will, team, votes }, Some(open)) => {
+                    let tally = open.tally.wills.entry(team).or_default();
+                    if tally.len() <= will {
+                        tally.resize(will + 1, 0);
+                    }
+                    tally[will] = tally[will].saturating_add(votes);
+                }
+                (Votes::Idol { player, votes }, _) => idols.push((player, votes)),
+                // checked above
+                (_, None) => {}
+            }
+        }
+        self.add_idol_votes(idols)
+    }
+
+    fn check_votes(&self, votes: &Votes) -> Result<(), DatabaseError> {
+        let ballot = self.database.election.as_ref().map(|open| &open.election);
+        let check = |kind: &'static str, index: usize, len: fn(&Election) -> usize| match ballot {
+            Some(ballot) if index < len(ballot) => Ok(()),
+            Some(_) => Err(DatabaseError::NoSuchProposal { kind, index }),
+            None => Err(DatabaseError::NoElection),
+        };
+        let check_team = |team: TeamId| {
+            let league = team.try_load(&self.database)?.league;
+            if ballot.is_some_and(|ballot| ballot.league != league) {
+                return Err(DatabaseError::NotInLeague { team });
+            }
+            Ok(())
+        };
+        match votes {
+            Votes::Decree { decree, .. } => check("decree", *decree, |e| e.decrees.len()),
+            Votes::Blessing { blessing, team, .. } => {
+                check("blessing", *blessing, |e| e.blessings.len())?;
+                check_team(*team)
+            }
+            Votes::Will { will, team, .. } => {
+                check("will", *will, |e| e.wills.len())?;
+                check_team(*team)
+            }
+            Votes::Idol { player, .. } => player.try_load(&self.database).map(drop),
+        }
+    }
+
+    /// Close the open election and resolve it with [`Sim::run_election`], using the votes
+    /// submitted so far. The outcomes are still decided by the sim's RNG; the votes only weight
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no election is open, or if [`Sim::run_election`] fails. The election
+    /// stays open in this case.
+    pub fn resolve_election(&mut self) -> Result<ElectionResults, DatabaseError> {
+        let open = self
+            .database
+            .election
+            .take()
+            .ok_or(DatabaseError::NoElection)?;
+        match self.run_election(&open.election, &open.tally) {
+            Ok(events) => Ok(ElectionResults {
+                tally: open.tally,
+                events,
+            }),
+            Err(err) => {
+                self.database.election = Some(open);
+                Err(err)
+            }
+        }
+    }
+}
+
+impl Effect {
+    fn apply(
+        &self,
+        rng: &mut impl RngSource,
+        database: &mut Database,
+        team_id: TeamId,
+    ) -> Option<String> {
+        match self {
+            Effect::Boost { amount } => {
+                let team = team_id.load(database);
+                let roster = team
+                    .lineup
+                    .iter()
+                    .chain(&team.rotation)
+                    .chain(&team.shadows)
+                    .copied()
+                    .collect::<Vec<PlayerId>>();
+                for player in roster {
+                    database.boost_player(player, *amount);
+                }
+                None
+            }
+            Effect::AddMod { name } => {
+                let team = team_id.load_mut(database);
+                if team.mods.contains(name) {
+                    None
+                } else {
+                    team.mods.push(name.clone());
+                    let event = format!("The {} gained {}.", team.nickname, name);
+                    database.record(Mutation::ModAdded {
+                        team: team_id,
+                        name: name.clone(),
+                    });
+                    Some(event)
+                }
+            }
+            Effect::Recruit { name } => {
+                // roll the ID too, so a seeded election recruits the same player every time
+                let player_id = PlayerId::rolled(rng);
+                let player = Player {
+                    id: player_id,
+                    ..Player::generate_with_name(rng, name.clone())
+                };
+                database.players.insert(player_id, player);
+                database.record(Mutation::PlayerAdded { player: player_id });
+                let team = team_id.load_mut(database);
+                let slot = RosterSlot {
+                    position: RosterPosition::Lineup,
+                    index: team.lineup.len(),
+                };
+                team.lineup.push(player_id);
+                let event = format!("{} joined the {}.", name, team.nickname);
+                database.record(Mutation::RosterAdded {
+                    team: team_id,
+                    player: player_id,
+                    slot,
+                });
+                Some(event)
+            }
+            Effect::Exchange => {
+                let league = team_id.load(database).league;
+                let other_id = rng.choose(
+                    database
+                        .league_teams(league)
+                        .into_iter()
+                        .filter(|id| *id != team_id)
+                        .collect::<Vec<_>>(),
+                )?;
+                let slot_a = RosterSlot {
+                    position: RosterPosition::Lineup,
+                    index: rng.choose(0..team_id.load(database).lineup.len())?,
+                };
+                let slot_b = RosterSlot {
+                    position: RosterPosition::Lineup,
+                    index: rng.choose(0..other_id.load(database).lineup.len())?,
+                };
+                let player_a = team_id.load(database).get(slot_a)?;
+                let player_b = other_id.load(database).get(slot_b)?;
+                database.trade(team_id, slot_a, other_id, slot_b).ok()?;
+                database.record_trade(team_id, slot_a, other_id, slot_b);
+                Some(format!(
+                    "{} and {} swapped teams.",
+                    player_a.load(database).name,
+                    player_b.load(database).name
+                ))
+            }
+        }
+    }
+}
+
+fn choose_weighted(rng: &mut impl RngSource, votes: &BTreeMap<TeamId, u64>) -> Option<TeamId> {
+    let total: u64 = votes.values().sum();
+    if total == 0 {
+        return None;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let mut roll = rng.next_f64() * total as f64;
+    for (team, count) in votes {
+        #[allow(clippy::cast_precision_loss)]
+        let count = *count as f64;
+        if roll < count {
+            return Some(*team);
+        }
+        roll -= count;
+    }
+    // floating point rounding can leave us past the end; fall back to the last team with votes
+    votes
+        .iter()
+        .rev()
+        .find(|(_, count)| **count > 0)
+        .map(|(team, _)| *team)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Effect, Election, Proposal, Tally, Votes};
+    use crate::id::TeamId;
+    use crate::{DatabaseError, Mutation, Sim};
+    use std::collections::BTreeMap;
+
+    fn proposal(title: &str, effect: Effect) -> Proposal {
+        Proposal {
+            title: title.to_owned(),
+            effect,
+        }
+    }
+
+    #[test]
+    fn run_election() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let teams = sim.teams().keys().copied().collect::<Vec<TeamId>>();
+        let add_mod = |name: &str| Effect::AddMod {
+            name: name.to_owned(),
+        };
+        let election = Election {
+            decrees: vec![
+                proposal("Unpopular", add_mod("UNPOPULAR")),
+                proposal("Popular", add_mod("POPULAR")),
+            ],
+            decrees_passed: 1,
+            blessings: vec![proposal("Boosted", Effect::Boost { amount: 0.1 })],
+            wills: vec![
+                proposal(
+                    "Recruited",
+                    Effect::Recruit {
+                        name: "Rookie".to_owned(),
+                    },
+                ),
+                proposal("Exchanged", Effect::Exchange),
+            ],
+            ..Election::default()
+        };
+        let tally = Tally {
+            decrees: vec![1, 5],
+            blessings: vec![BTreeMap::from([(teams[0], 3)])],
+            wills: BTreeMap::from([(teams[1], vec![2, 1])]),
+        };
+        let before = sim.clone();
+        let mut other = sim.clone();
+        let events = sim.run_election(&election, &tally).unwrap();
+
+        // the same sim resolves the same election the same way, down to the recruit's ID
+        assert_eq!(other.run_election(&election, &tally).unwrap(), events);
+        assert_eq!(sim, other);
+
+        // decrees: only the most popular passes, for every team
+        assert_eq!(events[0], "Decree passed: Popular");
+        for team in sim.teams().values() {
+            assert!(team.has_mod("POPULAR") && !team.has_mod("UNPOPULAR"));
+        }
+
+        // blessings: the only team with votes wins, and its players are boosted
+        assert!(events.iter().any(|e| e.ends_with("won Boosted.")));
+        let thwack = |sim: &Sim, team: TeamId| {
+            let player = sim.teams().get(&team).unwrap().lineup[0];
+            sim.players().get(&player).unwrap().thwackability
+        };
+        assert!(thwack(&sim, teams[0]) > thwack(&before, teams[0]));
+        assert!((thwack(&sim, teams[2]) - thwack(&before, teams[2])).abs() < 1e-9);
+
+        // wills: the team enacts the will it voted for most
+        assert!(events.iter().any(|e| e.ends_with("enacted Recruited.")));
+        assert!(events.iter().any(|e| e.starts_with("Rookie joined the ")));
+        let lineup = &sim.teams().get(&teams[1]).unwrap().lineup;
+        let rookie = sim.players().get(lineup.last().unwrap()).unwrap();
+        assert_eq!(rookie.name, "Rookie");
+        assert_eq!(sim.players().len(), before.players().len() + 1);
+        assert!(!events.iter().any(|e| e.contains("swapped teams")));
+    }
+
+    #[test]
+    fn exchange() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let team = *sim.teams().keys().next().unwrap();
+        let election = Election {
+            blessings: vec![proposal("Exchanged", Effect::Exchange)],
+            ..Election::default()
+        };
+        let tally = Tally {
+            blessings: vec![BTreeMap::from([(team, 1)])],
+            ..Tally::default()
+        };
+        let before = sim.clone();
+        sim.take_audit_log();
+        let events = sim.run_election(&election, &tally).unwrap();
+        assert!(events.last().unwrap().ends_with("swapped teams."));
+
+        // replaying the audit log's roster changes onto the old rosters gets the new ones
+        let mut mirror = before.teams().clone();
+        for entry in sim.audit_log() {
+            match entry.mutation {
+                Mutation::RosterRemoved { team, player, slot } => {
+                    let lineup = &mut mirror.get_mut(&team).unwrap().lineup;
+                    assert_eq!(lineup.remove(slot.index), player);
+                }
+                Mutation::RosterAdded { team, player, slot } => {
+                    let lineup = &mut mirror.get_mut(&team).unwrap().lineup;
+                    lineup.insert(slot.index, player);
+                }
+                _ => {}
+            }
+        }
+        assert_ne!(*sim.teams(), *before.teams());
+        assert_eq!(mirror, *sim.teams());
+    }
+
+    #[test]
+    fn submit_votes() {
+        let mut sim = crate::sim::tests::two_games();
+        let team = *sim.teams().keys().next().unwrap();
+        let player = sim.teams().get(&team).unwrap().lineup[0];
+        let recruit = |name: &str| Proposal {
+            title: name.to_owned(),
+            effect: Effect::Recruit {
+                name: name.to_owned(),
+            },
+        };
+        let election = Election {
+            blessings: vec![recruit("Blessed")],
+            wills: vec![recruit("Willed"), recruit("Unwilled")],
+            ..Election::default()
+        };
+
+        // idol votes don't need an election
+        sim.submit_votes([Votes::Idol { player, votes: 3 }])
+            .unwrap();
+        assert!(matches!(
+            sim.submit_votes([Votes::Decree {
+                decree: 0,
+                votes: 1
+            }]),
+            Err(DatabaseError::NoElection)
+        ));
+
+        sim.open_election(election.clone()).unwrap();
+        assert!(matches!(
+            sim.open_election(election),
+            Err(DatabaseError::ElectionOpen)
+        ));
+        let bad = [
+            Votes::Idol { player, votes: 1 },
+            Votes::Decree {
+                decree: 0,
+                votes: 1,
+            },
+        ];
+        assert!(matches!(
+            sim.submit_votes(bad),
+            Err(DatabaseError::NoSuchProposal {
+                kind: "decree",
+                index: 0
+            })
+        ));
+        assert!(sim
+            .submit_votes([Votes::Will {
+                will: 0,
+                team: TeamId::new(),
+                votes: 1
+            }])
+            .is_err());
+        assert_eq!(sim.idol_board().votes(player), 3);
+
+        for _ in 0..2 {
+            sim.submit_votes([
+                Votes::Blessing {
+                    blessing: 0,
+                    team,
+                    votes: 5,
+                },
+                Votes::Will {
+                    will: 0,
+                    team,
+                    votes: 2,
+                },
+                Votes::Will {
+                    will: 1,
+                    team,
+                    votes: 1,
+                },
+            ])
+            .unwrap();
+        }
+        let json = serde_json::to_string(&sim).unwrap();
+        let other: Sim = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.open_election_tally(), sim.open_election_tally());
+
+        let results = sim.resolve_election().unwrap();
+        assert!(sim.open_election_tally().is_none());
+        assert_eq!(results.tally.blessings[0][&team], 10);
+        assert_eq!(results.tally.wills[&team], [4, 2]);
+        assert!(results.events.iter().any(|e| e.contains("won Blessed")));
+        assert!(results.events.iter().any(|e| e.contains("enacted Willed")));
+        assert!(matches!(
+            sim.resolve_election(),
+            Err(DatabaseError::NoElection)
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+use crate::database::CheckEntity;
+use crate::id::TeamId;
+use crate::{
+    Ballpark, DatabaseError, Mutation, Player, RngSource, RosterPosition, RosterSize, Sim, Team,
+};
+
+/// Options for [`Sim::add_expansion_team`].
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct Expansion {
+    /// Generate players to fill the team's roster up to this size.
+    pub roster: Option<RosterSize>,
+    /// Names for generated players, used in order. Once these run out, players are named after
+    /// the team.
+    pub names: Vec<String>,
+    /// Generate a ballpark with this name for the team.
+    pub ballpark: Option<String>,
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Add a new team to the database, generating players and a ballpark for it as requested.
+    /// Either everything is added, or nothing is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - the team's ID is nil (an all-zero UUID)
+    /// - the team references player IDs that are not in the database
+    /// - the team's roster (after generating players) is too small to be scheduled
+    pub fn add_expansion_team(
+        &mut self,
+        mut team: Team,
+        expansion: Expansion,
+    ) -> Result<TeamId, DatabaseError> {
+        team.check(&self.database)?;
+
+        let mut players = Vec::new();
+        if let Some(size) = expansion.roster {
+            let mut names = expansion.names.into_iter();
+            for position in RosterPosition::ALL {
+                while team.players(position).len() < size.get(position) {
+                    let name = names.next().unwrap_or_else(|| {
+                        format!("{} Player {}", team.nickname, players.len() + 1)
+                    });
+                    let player = Player::generate_with_name(&mut self.rng, name);
+                    team.players_mut(position).push(player.id);
+                    players.push(player);
+                }
+            }
+        }
+        team.check_schedulable()?;
+
+        let ballpark = expansion
+            .ballpark
+            .map(|name| Ballpark::generate(&mut self.rng, team.id, name));
+
+        let team_id = team.id;
+        for player in players {
+            let player_id = player.id;
+            self.database.players.insert(player_id, player);
+            self.database
+                .record(Mutation::PlayerAdded { player: player_id });
+        }
+        self.database.teams.insert(team_id, team);
+        self.database.record(Mutation::TeamAdded { team: team_id });
+        if let Some(ballpark) = ballpark {
+            let ballpark_id = ballpark.id;
+            self.database.ballparks.insert(ballpark_id, ballpark);
+            self.database.record(Mutation::BallparkAdded {
+                ballpark: ballpark_id,
+            });
+        }
+        self.database.check_after_mutation();
+        Ok(team_id)
+    }
+}
@@ -0,0 +1,228 @@
+use crate::id::{PlayerId, TeamId};
+use crate::{DatabaseError, Mutation, RngSource, Sim, Team};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+/// Where a player in a team's lineup plays in the field. Set with [`Sim::set_position`].
+///
+/// Teams that assign positions send each ball to a fielder who could field it (see
+/// [`BattedBall`]). Teams that don't send every ball to anyone in the lineup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Position {
+    Catcher,
+    FirstBase,
+    SecondBase,
+    ThirdBase,
+    Shortstop,
+    LeftField,
+    CenterField,
+    RightField,
+    /// Bats, but never fields.
+    DesignatedHitter,
+}
+
+/// How a ball in play left the bat, rolled before anything else about it (see
+/// [`Formulas::batted_ball`](crate::Formulas::batted_ball)). It decides who can field the ball,
+/// what kind of out it makes, and how far it can go for a hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum BattedBall {
+    /// Fielded by the infield and the catcher. Never leaves the park or goes for a triple.
+    Grounder,
+    /// Fielded by the infield and the outfield. Hard to catch.
+    LineDrive,
+    /// Fielded by the outfield.
+    FlyBall,
+    /// Fielded by the infield and the catcher, and almost always caught. Foul outs are popups.
+    Popup,
+}
+
+impl BattedBall {
+    pub const ALL: [BattedBall; 4] = [
+        BattedBall::Grounder,
+        BattedBall::LineDrive,
+        BattedBall::FlyBall,
+        BattedBall::Popup,
+    ];
+
+    /// Whether the ball is in the air, so runners have to tag up if it's caught.
+    pub fn is_in_air(self) -> bool {
+        self != BattedBall::Grounder
+    }
+}
+
+impl Display for BattedBall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BattedBall::Grounder => "grounder",
+            BattedBall::LineDrive => "line drive",
+            BattedBall::FlyBall => "fly ball",
+            BattedBall::Popup => "popup",
+        })
+    }
+}
+
+impl Position {
+    pub const ALL: [Position; 9] = [
+        Position::Catcher,
+        Position::FirstBase,
+        Position::SecondBase,
+        Position::ThirdBase,
+        Position::Shortstop,
+        Position::LeftField,
+        Position::CenterField,
+        Position::RightField,
+        Position::DesignatedHitter,
+    ];
+
+    pub fn is_infield(self) -> bool {
+        matches!(
+            self,
+            Position::FirstBase | Position::SecondBase | Position::ThirdBase | Position::Shortstop
+        )
+    }
+
+    pub fn is_outfield(self) -> bool {
+        matches!(
+            self,
+            Position::LeftField | Position::CenterField | Position::RightField
+        )
+    }
+
+    /// Whether a player at this position goes after a kind of ball.
+    pub(crate) fn fields(self, ball: BattedBall) -> bool {
+        match ball {
+            BattedBall::Grounder | BattedBall::Popup => {
+                self.is_infield() || self == Position::Catcher
+            }
+            BattedBall::LineDrive => self.is_infield() || self.is_outfield(),
+            BattedBall::FlyBall => self.is_outfield(),
+        }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Position::Catcher => "catcher",
+            Position::FirstBase => "first base",
+            Position::SecondBase => "second base",
+            Position::ThirdBase => "third base",
+            Position::Shortstop => "shortstop",
+            Position::LeftField => "left field",
+            Position::CenterField => "center field",
+            Position::RightField => "right field",
+            Position::DesignatedHitter => "designated hitter",
+        })
+    }
+}
+
+impl Team {
+    /// Where a player in the lineup plays in the field, if the team has assigned them a
+    /// position. Positions kept for players who have since left the lineup don't count.
+    pub fn position(&self, player: PlayerId) -> Option<Position> {
+        self.positions
+            .get(&player)
+            .copied()
+            .filter(|_| self.lineup.contains(&player))
+    }
+
+    /// Whether the team has assigned a position to anyone in its lineup.
+    pub(crate) fn has_positions(&self) -> bool {
+        self.lineup
+            .iter()
+            .any(|player| self.positions.contains_key(player))
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Assign a player in a team's lineup to a position, or clear their position with `None`.
+    /// More than one player can play the same position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the team is not in the database, or if the player is not in its
+    /// lineup.
+    pub fn set_position(
+        &mut self,
+        team: TeamId,
+        player: PlayerId,
+        position: Option<Position>,
+    ) -> Result<(), DatabaseError> {
+        let data = team.try_load_mut(&mut self.database)?;
+        if !data.lineup.contains(&player) {
+            return Err(DatabaseError::NotInLineup { team, player });
+        }
+        match position {
+            Some(position) => data.positions.insert(player, position),
+            None => data.positions.remove(&player),
+        };
+        self.database.record(Mutation::TeamChanged { team });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BattedBall, Position};
+    use crate::{Formulas, PlayEvent, Player, TeamSelect};
+
+    #[derive(Debug)]
+    struct OnlyCenterField;
+
+    impl Formulas for OnlyCenterField {
+        fn fielder_range(&self, _fielder: &Player, position: Position) -> f64 {
+            if position == Position::CenterField || !position.is_outfield() {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+
+    #[test]
+    fn positions() {
+        let mut sim = crate::sim::tests::two_games();
+        let team = sim.games_today()[0].teams.home.id;
+        let lineup = sim.teams().get(&team).unwrap().lineup.clone();
+        for (player, position) in lineup.iter().zip(Position::ALL) {
+            sim.set_position(team, *player, Some(position)).unwrap();
+        }
+        let shadow = sim.teams().get(&team).unwrap().shadows[0];
+        assert!(sim.set_position(team, shadow, None).is_err());
+        let data = sim.teams().get(&team).unwrap();
+        assert_eq!(data.position(lineup[4]), Some(Position::Shortstop));
+        assert_eq!(data.position(shadow), None);
+
+        sim.set_formulas(OnlyCenterField);
+        sim.finish_day();
+        let game = &sim.games_today()[0];
+        let data = sim.teams().get(&team).unwrap();
+        let mut outs = 0;
+        for play in &game.plays {
+            if play.inning.fielding() != TeamSelect::Home {
+                continue;
+            }
+            let (fielder, ball) = match play.event {
+                PlayEvent::Flyout { fielder } => (fielder, BattedBall::FlyBall),
+                PlayEvent::GroundOut { fielder } => (fielder, BattedBall::Grounder),
+                PlayEvent::LineOut { fielder } => (fielder, BattedBall::LineDrive),
+                PlayEvent::PopOut { fielder } => (fielder, BattedBall::Popup),
+                _ => continue,
+            };
+            assert_eq!(play.batted_ball, Some(ball));
+            let position = data.position(fielder).unwrap();
+            if position.is_outfield() {
+                assert_eq!(position, Position::CenterField);
+            }
+            assert!(position.fields(ball));
+            outs += 1;
+        }
+        assert!(outs > 0);
+    }
+}
@@ -0,0 +1,746 @@
+use crate::{Ballpark, Baserunner, BattedBall, Date, Player, Position, RngSource, Sim, Team};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Everything a pitch's rolls can depend on, passed to each method of [`Formulas`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct Pitch<'a> {
+    pub date: Date,
+    pub pitcher: &'a Player,
+    pub batter: &'a Player,
+    pub ballpark: &'a Ballpark,
+    /// The count before the pitch.
+    pub balls: u8,
+    pub strikes: u8,
+    pub outs: u8,
+    /// Baserunners and the base they are on.
+    pub baserunners: &'a [Baserunner],
+    /// The [home advantage](Sim::set_home_advantage) from the batter's side: positive when the
+    /// batter is at home, negative when the pitcher is.
+    pub home_advantage: f64,
+    /// How a ball in play left the bat, once that's been rolled with [`Formulas::batted_ball`].
+    pub batted_ball: Option<BattedBall>,
+    /// Where [`Pitch::term`] records terms, when the sim is [tracing](Sim::set_tracing).
+    pub(crate) terms: Option<&'a RefCell<Vec<Term>>>,
+}
+
+/// A named part of a threshold, reported by a formula with [`Pitch::term`] and recorded in the
+/// [trace](Sim::trace) alongside the roll.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Term {
+    pub name: &'static str,
+    pub value: f64,
+}
+
+impl Pitch<'_> {
+    /// Report a term of the formula being evaluated, and return its value. The default formulas
+    /// report each vibes modifier (named "... vibes"), which scales attributes rather than adding
+    /// to the threshold, and then terms that add up to the threshold: a "base" constant, how much
+    /// each attribute (or group of attributes) and the "ballpark" add, and any adjustment made
+    /// after that, like clamping the threshold or scaling it for how the ball was hit.
+    ///
+    /// Terms are only kept while the sim is [tracing](Sim::set_tracing); otherwise this just
+    /// returns `value`.
+    pub fn term(&self, name: &'static str, value: f64) -> f64 {
+        if let Some(terms) = self.terms {
+            terms.borrow_mut().push(Term { name, value });
+        }
+        value
+    }
+
+    /// Report the difference between the threshold the terms so far add up to, `sum`, and the
+    /// threshold after adjusting it, `adjusted`, as a term named `name` (unless there isn't one),
+    /// and return `adjusted`.
+    #[allow(clippy::float_cmp)]
+    fn adjust(&self, name: &'static str, sum: f64, adjusted: f64) -> f64 {
+        if adjusted != sum && !adjusted.is_nan() {
+            self.term(name, adjusted - sum);
+        }
+        adjusted
+    }
+
+    /// Take the terms reported since they were last taken.
+    pub(crate) fn take_terms(&self) -> Vec<Term> {
+        self.terms
+            .map(|terms| std::mem::take(&mut *terms.borrow_mut()))
+            .unwrap_or_default()
+    }
+
+    fn batter_mod(&self) -> f64 {
+        1.0 + 0.2 * self.batter.vibes(self.date) + self.home_advantage
+    }
+
+    fn pitcher_mod(&self) -> f64 {
+        1.0 + 0.2 * self.pitcher.vibes(self.date) - self.home_advantage
+    }
+
+    fn fielder_mod(&self, fielder: &Player) -> f64 {
+        1.0 + 0.2 * fielder.vibes(self.date) - self.home_advantage
+    }
+
+    fn runner_mod(&self, runner: &Player) -> f64 {
+        1.0 + 0.2 * runner.vibes(self.date) + self.home_advantage
+    }
+}
+
+/// The threshold for each roll made during a pitch. Each roll succeeds if the random number is
+/// less than the threshold.
+///
+/// Every method has a default implementation, mostly following the formulas for season 14 as
+/// reverse-engineered by the [resim](https://github.com/xSke/resim) project, so an
+/// implementation only needs to override the formulas it wants to change. Set the formulas a
+/// sim uses with [`Sim::set_formulas`].
+///
+/// Formulas only decide thresholds. Which rolls are made, and in what order, is up to the sim.
+/// A formula can explain how it got to a threshold by reporting its terms with [`Pitch::term`].
+pub trait Formulas: Debug + Send + Sync {
+    /// Whether a pitcher with [Debt](crate::mods::DEBT) or
+    /// [Refinanced](crate::mods::REFINANCED) hits the batter with the pitch. Only rolled for
+    /// those pitchers.
+    fn bean(&self, pitch: &Pitch<'_>) -> f64 {
+        // not from resim: a guess
+        pitch.term("base", 0.1)
+    }
+
+    /// Whether the pitch is in the strike zone.
+    fn strike(&self, pitch: &Pitch<'_>) -> f64 {
+        let Pitch {
+            pitcher, ballpark, ..
+        } = pitch;
+        let pitcher_mod = pitch.term("pitcher vibes", pitch.pitcher_mod());
+        // NOTE: mostly using the season 14 formula
+        let base = pitch.term("base", 0.2);
+        let ruth = pitch.term("ruthlessness", 0.285 * (pitcher.ruthlessness * pitcher_mod));
+        let fwd = pitch.term("ballpark", 0.2 * ballpark.forwardness);
+        let musc = pitch.term("musclitude", 0.1 * pitch.batter.musclitude);
+        let strike = base + ruth + fwd + musc;
+        pitch.adjust("clamp", strike, strike.min(0.86))
+    }
+
+    /// Whether the batter swings.
+    fn swing(&self, pitch: &Pitch<'_>, strike: bool) -> f64 {
+        let Pitch {
+            pitcher,
+            batter,
+            ballpark,
+            ..
+        } = pitch;
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let pitcher_mod = pitch.term("pitcher vibes", pitch.pitcher_mod());
+
+        if strike {
+            let div = batter.divinity * batter_mod;
+            let musc = batter.musclitude * batter_mod;
+            let thwack = batter.thwackability * batter_mod;
+            let invpath = (1.0 - batter.patheticism) * batter_mod;
+            let ruth = pitcher.ruthlessness * pitcher_mod;
+            let combined = (div + musc + invpath + thwack) / 4.0;
+            let base = pitch.term("base", 0.6);
+            let batting = pitch.term("batting", 0.35 * combined);
+            let ruth = pitch.term("ruthlessness", -(0.2 * ruth));
+            let visc = pitch.term("ballpark", 0.2 * (ballpark.viscosity - 0.5));
+            base + batting + ruth + visc
+        } else {
+            let moxie = batter.moxie * batter_mod;
+            let path = batter.patheticism;
+            let ruth = pitcher.ruthlessness * pitcher_mod;
+            pitch.term("ruthlessness", 12.0 * ruth / 20.0);
+            pitch.term("moxie", -(5.0 * moxie) / 20.0);
+            pitch.term("patheticism", 5.0 * path / 20.0);
+            pitch.term("ballpark", 4.0 * ballpark.viscosity / 20.0);
+            let combined =
+                (12.0 * ruth - 5.0 * moxie + 5.0 * path + 4.0 * ballpark.viscosity) / 20.0;
+            if combined < 0.0 {
+                f64::NAN
+            } else {
+                pitch.adjust("curve", combined, combined.powf(1.5).clamp(0.1, 0.95))
+            }
+        }
+    }
+
+    /// Whether the batter makes contact, after swinging.
+    fn contact(&self, pitch: &Pitch<'_>, strike: bool) -> f64 {
+        let Pitch {
+            pitcher,
+            batter,
+            ballpark,
+            ..
+        } = pitch;
+        let fort = ballpark.fortification - 0.5;
+        let visc = ballpark.viscosity - 0.5;
+        let fwd = ballpark.forwardness - 0.5;
+        let ballpark_sum = (fort + 3.0 * visc - 6.0 * fwd) / 10.0;
+
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let pitcher_mod = pitch.term("pitcher vibes", pitch.pitcher_mod());
+
+        // NOTE: mostly using the season 14 formula
+        if strike {
+            let div = batter.divinity;
+            let musc = batter.musclitude;
+            let thwack = batter.thwackability;
+            let path = batter.patheticism;
+            let combined = (div + musc + thwack - path) / 2.0 * batter_mod;
+            if combined < 0.0 {
+                pitch.term("batting", combined);
+                f64::NAN
+            } else {
+                let ruth = pitch.term(
+                    "ruthlessness",
+                    -(0.08 * (pitcher.ruthlessness * pitcher_mod)),
+                );
+                let ballpark = pitch.term("ballpark", 0.16 * ballpark_sum);
+                let batting = pitch.term("batting", 0.17 * combined.powf(1.2));
+                let base = pitch.term("base", 0.78);
+                let contact = base + ruth + ballpark + batting;
+                pitch.adjust("clamp", contact, contact.min(0.9))
+            }
+        } else {
+            let path = ((1.0 - batter.patheticism) * batter_mod).max(0.0);
+            let ruth = pitch.term(
+                "ruthlessness",
+                -(0.1 * (pitcher.ruthlessness * pitcher_mod)),
+            );
+            let path = pitch.term("patheticism", 0.35 * path.powf(1.5));
+            let ballpark = pitch.term("ballpark", 0.14 * ballpark_sum);
+            let base = pitch.term("base", 0.4);
+            let contact = base + ruth + path + ballpark;
+            pitch.adjust("clamp", contact, contact.min(1.0))
+        }
+    }
+
+    /// Whether the batter bunts, after deciding to swing. Bunting is a strategy rather than
+    /// something the original sim did, so by default batters never bunt.
+    ///
+    /// The sim only rolls for a bunt when this is positive, so a sim that never bunts rolls the
+    /// same numbers as it did before bunts existed.
+    fn bunt(&self, pitch: &Pitch<'_>) -> f64 {
+        let _ = pitch;
+        0.0
+    }
+
+    /// Whether a bunt is popped up and caught by `fielder`.
+    fn bunt_popout(&self, pitch: &Pitch<'_>, fielder: &Player) -> f64 {
+        // not from resim: a guess
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let fielder_mod = pitch.term("fielder vibes", pitch.fielder_mod(fielder));
+        let path = pitch.term(
+            "patheticism",
+            0.15 * (pitch.batter.patheticism * batter_mod),
+        );
+        let omni = pitch.term("omniscience", 0.05 * (fielder.omniscience * fielder_mod));
+        pitch.term("base", 0.1) + path + omni
+    }
+
+    /// Whether a bunt that isn't popped up is beaten out for a single, instead of being a
+    /// sacrifice. `fielder` is the defender who fielded it.
+    fn bunt_hit(&self, pitch: &Pitch<'_>, fielder: &Player) -> f64 {
+        // not from resim: a guess
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let fielder_mod = pitch.term("fielder vibes", pitch.fielder_mod(fielder));
+        let speed = pitch.term(
+            "laserlikeness",
+            0.25 * (pitch.batter.laserlikeness * batter_mod),
+        );
+        let tenacious = pitch.term(
+            "tenaciousness",
+            -(0.1 * (fielder.tenaciousness * fielder_mod)),
+        );
+        pitch.term("base", 0.1) + speed + tenacious
+    }
+
+    /// Whether contact is a foul ball.
+    fn foul(&self, pitch: &Pitch<'_>) -> f64 {
+        let Pitch {
+            batter, ballpark, ..
+        } = pitch;
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let batter_sum =
+            (batter.musclitude + batter.thwackability + batter.divinity) * batter_mod / 3.0;
+        let fwd = 0.1 * ballpark.forwardness;
+        let obt = -(0.1 * ballpark.obtuseness);
+        pitch.term("ballpark", fwd + obt);
+        let batting = pitch.term("batting", 0.1 * batter_sum);
+        pitch.term("base", 0.25) + fwd + obt + batting
+    }
+
+    /// Whether a foul ball is caught for an out by `fielder`.
+    fn foul_out(&self, pitch: &Pitch<'_>, fielder: &Player) -> f64 {
+        // not from resim: a guess
+        let fielder_mod = pitch.term("fielder vibes", pitch.fielder_mod(fielder));
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let omni = pitch.term("omniscience", 0.08 * (fielder.omniscience * fielder_mod));
+        let thwack = pitch.term(
+            "thwackability",
+            -(0.04 * (pitch.batter.thwackability * batter_mod)),
+        );
+        pitch.term("base", 0.06) + omni + thwack
+    }
+
+    /// Whether a ball in play is an out, fielded by `fielder`.
+    fn out(&self, pitch: &Pitch<'_>, fielder: &Player) -> f64 {
+        let Pitch {
+            pitcher,
+            batter,
+            ballpark,
+            ..
+        } = pitch;
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let pitcher_mod = pitch.term("pitcher vibes", pitch.pitcher_mod());
+        let fielder_mod = pitch.term("fielder vibes", pitch.fielder_mod(fielder));
+
+        // rough formula for season 14 from
+        // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_out.ipynb
+        let thwack = pitch.term("thwackability", 0.1 * (batter.thwackability * batter_mod));
+        let unthwack = pitch.term(
+            "unthwackability",
+            -(0.08 * (pitcher.unthwackability * pitcher_mod)),
+        );
+        let omni = pitch.term(
+            "omniscience",
+            -(0.065 * (fielder.omniscience * fielder_mod)),
+        );
+        let grand = ballpark.grandiosity - 0.5;
+        let obt = ballpark.obtuseness - 0.5;
+        let omin = ballpark.ominousness - 0.5;
+        let incon = ballpark.inconvenience - 0.5;
+        let visc = ballpark.viscosity - 0.5;
+        let fwd = ballpark.forwardness - 0.5;
+
+        pitch.term(
+            "ballpark",
+            (0.01 * grand) + (0.0085 * obt) - (0.0033 * omin) - (0.0015 * incon) - (0.0033 * visc)
+                + (0.01 * fwd),
+        );
+
+        let out =
+            pitch.term("base", 0.3115) + thwack + unthwack + omni + (0.01 * grand) + (0.0085 * obt)
+                - (0.0033 * omin)
+                - (0.0015 * incon)
+                - (0.0033 * visc)
+                + (0.01 * fwd);
+
+        // not from resim: a guess at how much harder liners are to catch than other balls
+        let adjusted = match pitch.batted_ball {
+            Some(BattedBall::LineDrive) => out * 0.5,
+            Some(BattedBall::Popup) => out.max(0.9),
+            _ => out,
+        };
+        pitch.adjust("batted ball", out, adjusted)
+    }
+
+    /// How often a batter gets the ball in the air, used by the default
+    /// [`Formulas::batted_ball`].
+    fn fly(&self, pitch: &Pitch<'_>) -> f64 {
+        let Pitch {
+            batter, ballpark, ..
+        } = pitch;
+        let omin = ballpark.ominousness - 0.5;
+
+        // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_fly.ipynb
+        let buoy = pitch.term("buoyancy", 0.3 * batter.buoyancy);
+        let supp = pitch.term("suppression", -(0.16 * batter.suppression));
+        let omin = pitch.term("ballpark", -(0.1 * omin));
+        pitch.term("base", 0.18) + buoy + supp + omin
+    }
+
+    /// How likely a ball in play is to leave the bat as `ball`, relative to the other kinds.
+    /// This is a weight rather than a threshold. Rolled for every ball in play, before anything
+    /// else about it; the result is passed to the later formulas as [`Pitch::batted_ball`].
+    fn batted_ball(&self, pitch: &Pitch<'_>, ball: BattedBall) -> f64 {
+        // not from resim: a guess that splits the flyout roll's balls in the air into flies
+        // and popups, and the rest into grounders and liners
+        let air = self.fly(pitch).clamp(0.0, 1.0);
+        match ball {
+            BattedBall::Grounder => (1.0 - air) * 0.7,
+            BattedBall::LineDrive => (1.0 - air) * 0.3,
+            BattedBall::FlyBall => air * 0.8,
+            BattedBall::Popup => air * 0.2,
+        }
+    }
+
+    /// Whether a ball in play that isn't an out is a home run.
+    fn home_run(&self, pitch: &Pitch<'_>) -> f64 {
+        let Pitch {
+            pitcher,
+            batter,
+            ballpark,
+            ..
+        } = pitch;
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let pitcher_mod = pitch.term("pitcher vibes", pitch.pitcher_mod());
+
+        // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_hr.ipynb
+        let div = pitch.term("divinity", 0.16 * (batter.divinity * batter_mod));
+        let opw = pitcher.overpowerment * pitcher_mod;
+        let supp = pitcher.suppression * pitcher_mod;
+        let opw_supp = pitch.term("pitching", -(0.08 * ((10.0 * opw + supp) / 11.0)));
+
+        let grand = ballpark.grandiosity - 0.5;
+        let fort = ballpark.fortification - 0.5;
+        let visc = ballpark.viscosity - 0.5;
+        let omin = ballpark.ominousness - 0.5;
+        let fwd = ballpark.forwardness - 0.5;
+        let ballpark_sum =
+            (0.4 * grand) + (0.2 * fort) + (0.08 * visc) + (0.08 * omin) - (0.24 * fwd);
+
+        let ballpark_sum = pitch.term("ballpark", -(0.18 * ballpark_sum));
+
+        let home_run = pitch.term("base", 0.12) + div + opw_supp + ballpark_sum;
+
+        // not from resim: a guess that keeps home runs about as common overall, now that only
+        // balls in the air leave the park
+        let adjusted = match pitch.batted_ball {
+            Some(BattedBall::FlyBall) => home_run * 3.0,
+            Some(BattedBall::LineDrive) => home_run * 1.4,
+            Some(BattedBall::Grounder | BattedBall::Popup) => 0.0,
+            None => home_run,
+        };
+        pitch.adjust("batted ball", home_run, adjusted)
+    }
+
+    /// Whether a base hit is a triple, with `fielder` chasing the ball. Checked before
+    /// [`Formulas::double`].
+    fn triple(&self, pitch: &Pitch<'_>, fielder: &Player) -> f64 {
+        let Pitch {
+            pitcher,
+            batter,
+            ballpark,
+            ..
+        } = pitch;
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let pitcher_mod = pitch.term("pitcher vibes", pitch.pitcher_mod());
+        let fielder_mod = pitch.term("fielder vibes", pitch.fielder_mod(fielder));
+        let fwd = ballpark.forwardness - 0.5;
+        let grand = ballpark.grandiosity - 0.5;
+        let obt = ballpark.obtuseness - 0.5;
+        let visc = ballpark.viscosity - 0.5;
+        let omin = ballpark.ominousness - 0.5;
+
+        // season 14
+        // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_triples_kidror.ipynb
+        let gf = pitch.term(
+            "ground friction",
+            0.2 * (batter.ground_friction * batter_mod),
+        );
+        let opw = pitch.term(
+            "overpowerment",
+            -(0.04 * (pitcher.overpowerment * pitcher_mod)),
+        );
+        let chase = pitch.term("chasiness", -(0.06 * (fielder.chasiness * fielder_mod)));
+        pitch.term(
+            "ballpark",
+            (0.02 * fwd) + (0.035 * grand) + (0.035 * obt) - (0.005 * omin) - (0.005 * visc),
+        );
+        let triple = pitch.term("base", 0.05)
+            + gf
+            + opw
+            + chase
+            + (0.02 * fwd)
+            + (0.035 * grand)
+            + (0.035 * obt)
+            - (0.005 * omin)
+            - (0.005 * visc);
+
+        // not from resim: grounders and popups don't go far enough
+        let adjusted = match pitch.batted_ball {
+            Some(BattedBall::Grounder | BattedBall::Popup) => 0.0,
+            _ => triple,
+        };
+        pitch.adjust("batted ball", triple, adjusted)
+    }
+
+    /// Whether a base hit that isn't a triple is a double, with `fielder` chasing the ball.
+    fn double(&self, pitch: &Pitch<'_>, fielder: &Player) -> f64 {
+        let Pitch {
+            pitcher,
+            batter,
+            ballpark,
+            ..
+        } = pitch;
+        let batter_mod = pitch.term("batter vibes", pitch.batter_mod());
+        let pitcher_mod = pitch.term("pitcher vibes", pitch.pitcher_mod());
+        let fielder_mod = pitch.term("fielder vibes", pitch.fielder_mod(fielder));
+        let fwd = ballpark.forwardness - 0.5;
+        let visc = ballpark.viscosity - 0.5;
+        let omin = ballpark.ominousness - 0.5;
+        let elong = ballpark.elongation - 0.5;
+
+        // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_doubles.ipynb
+        let musc = pitch.term("musclitude", 0.2 * (batter.musclitude * batter_mod));
+        let opw = pitch.term(
+            "overpowerment",
+            -(0.04 * (pitcher.overpowerment * pitcher_mod)),
+        );
+        let chase = pitch.term("chasiness", -(0.009 * (fielder.chasiness * fielder_mod)));
+        pitch.term(
+            "ballpark",
+            (0.027 * fwd) - (0.015 * elong) - (0.01 * omin) - (0.008 * visc),
+        );
+        let double = pitch.term("base", 0.165) + musc + opw + chase + (0.027 * fwd)
+            - (0.015 * elong)
+            - (0.01 * omin)
+            - (0.008 * visc);
+
+        // not from resim: popups don't go far enough
+        let adjusted = if pitch.batted_ball == Some(BattedBall::Popup) {
+            0.0
+        } else {
+            double
+        };
+        pitch.adjust("batted ball", double, adjusted)
+    }
+
+    /// Whether a [Reverberating](crate::mods::REVERBERATING) batter (or a
+    /// [Repeating](crate::mods::REPEATING) one, in Reverb weather) bats again after their plate
+    /// appearance. Only rolled for batters with one of these mods.
+    fn reverberate(&self, batter: &Player) -> f64 {
+        // not from resim: a guess
+        let _ = batter;
+        0.2
+    }
+
+    /// Whether a player on a team with [Party Time](crate::mods::PARTY_TIME) parties after one of
+    /// their team's plate appearances. Only rolled for those teams.
+    fn party(&self, team: &Team) -> f64 {
+        // not from resim: a guess
+        let _ = team;
+        0.02
+    }
+
+    /// How likely a fielder at a position is to get to a ball, relative to the other fielders
+    /// who could. This is a weight rather than a threshold. Only used for teams that assign
+    /// [positions](crate::Position).
+    fn fielder_range(&self, fielder: &Player, position: Position) -> f64 {
+        // not from resim: a guess
+        let stat = match position {
+            Position::Catcher => fielder.watchfulness,
+            position if position.is_outfield() => fielder.chasiness,
+            _ => fielder.tenaciousness,
+        };
+        0.5 + stat
+    }
+
+    /// Whether a baserunner takes an extra base on a hit, when the next base is open.
+    /// `fielder` is the defender who fielded the ball.
+    fn advance_on_hit(&self, pitch: &Pitch<'_>, fielder: &Player, runner: &Player) -> f64 {
+        // not from resim: a guess that lets a tenacious fielder hold runners
+        let fielder_mod = pitch.term("fielder vibes", pitch.fielder_mod(fielder));
+        let runner_mod = pitch.term("runner vibes", pitch.runner_mod(runner));
+        let tenacious = pitch.term(
+            "tenaciousness",
+            -(0.4 * (fielder.tenaciousness * fielder_mod)),
+        );
+        let thirst = pitch.term("base thirst", 0.3 * (runner.base_thirst * runner_mod));
+        pitch.term("base", 0.35) + thirst + tenacious
+    }
+
+    /// Whether a baserunner advances a base on an out that doesn't end the half-inning (tagging
+    /// up on a flyout, or running on a ground out), when the next base is open. `fielder` is
+    /// the defender who made the out.
+    fn advance_on_out(
+        &self,
+        pitch: &Pitch<'_>,
+        fielder: &Player,
+        runner: &Player,
+        flyout: bool,
+    ) -> f64 {
+        // not from resim: a guess that lets a watchful fielder hold runners
+        let fielder_mod = pitch.term("fielder vibes", pitch.fielder_mod(fielder));
+        let runner_mod = pitch.term("runner vibes", pitch.runner_mod(runner));
+        let watchful = pitch.term(
+            "watchfulness",
+            -(0.3 * (fielder.watchfulness * fielder_mod)),
+        );
+        let thirst = pitch.term("base thirst", 0.2 * (runner.base_thirst * runner_mod));
+        let base = pitch.term("base", if flyout { 0.3 } else { 0.2 });
+        base + thirst + watchful
+    }
+}
+
+/// The formulas a sim uses unless told otherwise: every default method of [`Formulas`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultFormulas;
+
+impl Formulas for DefaultFormulas {}
+
+/// A sim's formulas, which are a setting rather than part of its state: they aren't serialized
+/// or compared.
+#[derive(Debug, Clone)]
+pub(crate) struct FormulaSet(pub(crate) Arc<dyn Formulas>);
+
+impl Default for FormulaSet {
+    fn default() -> FormulaSet {
+        FormulaSet(Arc::new(DefaultFormulas))
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    pub fn formulas(&self) -> &dyn Formulas {
+        &*self.database.formulas.0
+    }
+
+    /// How much better the home team plays than its attributes alone would say, apart from
+    /// anything its ballpark does.
+    pub fn home_advantage(&self) -> f64 {
+        self.database.home_advantage
+    }
+
+    /// Set how much better the home team plays. In the default formulas, the advantage is
+    /// added to the multiplier vibes already apply to attributes: home batters, pitchers, and
+    /// fielders play as if their attributes were scaled up by `advantage`, and away players as
+    /// if scaled down by it. A negative advantage favors the away team.
+    ///
+    /// This is 0 by default, as in every era of Blaseball: there, the only thing the home team
+    /// had going for it was its ballpark. Formulas that ignore [`Pitch::home_advantage`] ignore
+    /// this setting.
+    pub fn set_home_advantage(&mut self, advantage: f64) {
+        self.database.home_advantage = advantage;
+    }
+
+    /// Replace the formulas used to decide each roll during a pitch. Formulas aren't part of a
+    /// sim's serialized state, so a deserialized sim always starts with [`DefaultFormulas`].
+    pub fn set_formulas<F: Formulas + 'static>(&mut self, formulas: F) {
+        self.database.formulas = FormulaSet(Arc::new(formulas));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Formulas, Pitch};
+    use crate::PlayEvent;
+    use std::collections::BTreeSet;
+
+    #[derive(Debug)]
+    struct AlwaysSwing;
+
+    impl Formulas for AlwaysSwing {
+        fn swing(&self, _pitch: &Pitch<'_>, _strike: bool) -> f64 {
+            1.0
+        }
+
+        fn contact(&self, _pitch: &Pitch<'_>, _strike: bool) -> f64 {
+            0.0
+        }
+    }
+
+    #[test]
+    fn custom_formulas() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.set_formulas(AlwaysSwing);
+        assert_eq!(format!("{:?}", sim.formulas()), "AlwaysSwing");
+        for _ in 0..50 {
+            sim.tick();
+        }
+
+        // every pitch is a swinging strike
+        for game in sim.games_today() {
+            assert!(!game.plays.is_empty());
+            assert!(game.plays.iter().all(|play| matches!(
+                play.event,
+                PlayEvent::Strike { swinging: true } | PlayEvent::Strikeout { swinging: true }
+            )));
+        }
+    }
+
+    /// The labels of rolls against formulas that depend on the ballpark.
+    const BALLPARK_FORMULAS: [&str; 8] = [
+        "strike", "swing", "contact", "foul", "out", "home run", "triple", "double",
+    ];
+
+    #[test]
+    fn threshold_terms() {
+        let mut sim = crate::sim::tests::two_games();
+        let mut untraced = sim.clone();
+        sim.set_tracing(true);
+        let mut labels = BTreeSet::new();
+        while !sim.games_today().iter().all(crate::Game::is_finished) {
+            sim.tick();
+            for roll in sim.trace().unwrap() {
+                let (Some(label), Some(threshold)) = (roll.label, roll.threshold) else {
+                    continue;
+                };
+                if roll.terms.is_empty() || threshold.is_nan() {
+                    continue;
+                }
+                // vibes modifiers scale attributes; everything else adds up to the threshold
+                let sum = roll
+                    .terms
+                    .iter()
+                    .filter(|term| !term.name.ends_with("vibes"))
+                    .map(|term| term.value)
+                    .sum::<f64>();
+                assert!((sum - threshold).abs() < 1e-9, "{label}: {:?}", roll.terms);
+                // and every formula the ballpark goes into says how much it added
+                if BALLPARK_FORMULAS.contains(&label) {
+                    assert!(roll.terms.iter().any(|term| term.name == "ballpark"));
+                }
+                labels.insert(label);
+            }
+            assert!(sim
+                .trace()
+                .unwrap()
+                .iter()
+                .filter(|roll| matches!(roll.label, Some("swing" | "contact" | "out")))
+                .all(|roll| !roll.terms.is_empty()));
+        }
+        for label in BALLPARK_FORMULAS.iter().chain(&["advance"]) {
+            assert!(labels.contains(label), "no {label} rolls in {labels:?}");
+        }
+
+        // reporting terms doesn't change how the game plays out
+        untraced.finish_day();
+        assert_eq!(untraced.games_today()[0].plays, sim.games_today()[0].plays);
+    }
+
+    /// How many games the home team wins over some days of games, and the games played.
+    fn home_wins(
+        mut sim: crate::Sim,
+        days: &[Vec<crate::Game>],
+        advantage: Option<f64>,
+    ) -> (usize, Vec<crate::Game>) {
+        if let Some(advantage) = advantage {
+            sim.set_home_advantage(advantage);
+        }
+        let mut played = Vec::new();
+        for (day, games) in (1..).zip(days) {
+            sim.start_day(crate::Date { season: 0, day }, games.clone())
+                .unwrap();
+            sim.finish_day();
+            played.extend_from_slice(sim.games_today());
+        }
+        let wins = played
+            .iter()
+            .filter(|game| game.teams.home.runs > game.teams.away.runs)
+            .count();
+        (wins, played)
+    }
+
+    #[test]
+    fn home_advantage() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let days = (1..=30).map(|day| sim.round_robin(day)).collect::<Vec<_>>();
+        let (default_wins, default_games) = home_wins(sim.clone(), &days, None);
+        let (home_favored, _) = home_wins(sim.clone(), &days, Some(0.3));
+        let (away_favored, _) = home_wins(sim.clone(), &days, Some(-0.3));
+        assert!(
+            home_favored > default_wins,
+            "{home_favored} <= {default_wins}"
+        );
+        assert!(
+            away_favored < default_wins,
+            "{away_favored} >= {default_wins}"
+        );
+
+        // no advantage is the default, and plays exactly like it
+        let (zero_wins, zero_games) = home_wins(sim, &days, Some(0.0));
+        assert_eq!(zero_wins, default_wins);
+        assert_eq!(zero_games, default_games);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::database::CheckEntity;
+use crate::id::{PlayerId, TeamId};
+use crate::{Database, DatabaseError, Mutation, Player, RngSource, RosterSize, RosterSlot, Sim};
+
+impl Database {
+    fn free_agent_index(&self, player: PlayerId) -> Result<usize, DatabaseError> {
+        self.free_agents
+            .iter()
+            .position(|p| *p == player)
+            .ok_or(DatabaseError::NotFreeAgent { player })
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Add a player to the database as a free agent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player's ID is nil (an all-zero UUID), or if a player with the
+    /// same ID is already in the database (whether rostered, a free agent, or neither).
+    pub fn add_free_agent(&mut self, player: Player) -> Result<(), DatabaseError> {
+        player.check(&self.database)?;
+        let id = player.id;
+        if let Some((kind, reference)) = self.database.player_references(id).into_iter().next() {
+            return Err(DatabaseError::PlayerInUse {
+                player: id,
+                kind,
+                id: reference,
+            });
+        }
+        if self.database.players.contains_key(&id) {
+            return Err(DatabaseError::PlayerExists { player: id });
+        }
+        self.database.players.insert(id, player);
+        self.database.free_agents.push(id);
+        self.database.record(Mutation::PlayerAdded { player: id });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Sign a free agent to a team, inserting them into a roster slot as with
+    /// [`Sim::move_player`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not a free agent, the team is not in the database, or
+    /// `slot.index` is past the end of the target list.
+    pub fn sign_player(
+        &mut self,
+        player: PlayerId,
+        team: TeamId,
+        slot: RosterSlot,
+    ) -> Result<(), DatabaseError> {
+        let index = self.database.free_agent_index(player)?;
+        if slot.index > team.try_load(&self.database)?.players(slot.position).len() {
+            return Err(DatabaseError::SlotOutOfRange { team, slot });
+        }
+        self.database.free_agents.remove(index);
+        self.database.roster_insert(team, slot, player);
+        self.database
+            .record(Mutation::RosterAdded { team, player, slot });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Release a player from their team into the free agent pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not on a team's roster, is in the middle of one of
+    /// today's unfinished games (like its current pitcher or a baserunner), or if releasing them
+    /// would leave their team with fewer players than [`RosterSize::MINIMUM`].
+    pub fn release_player(&mut self, player: PlayerId) -> Result<(), DatabaseError> {
+        let (team, slot) = self.database.roster_slot_of(player)?;
+        let minimum = RosterSize::MINIMUM.get(slot.position);
+        if team.load(&self.database).players(slot.position).len() <= minimum {
+            return Err(DatabaseError::RosterTooSmall {
+                team,
+                position: slot.position,
+                minimum,
+            });
+        }
+        self.database.check_not_playing(player)?;
+        self.database.roster_remove(team, slot);
+        self.database.free_agents.push(player);
+        self.database
+            .record(Mutation::RosterRemoved { team, player, slot });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DatabaseError, Mutation, Player, RosterPosition, RosterSlot};
+
+    #[test]
+    fn free_agents() {
+        let mut sim = crate::sim::tests::two_games();
+        let team = sim.games_today()[0].teams.home.id;
+        let shadow = sim.teams().get(&team).unwrap().shadows[0];
+        let batter = sim.teams().get(&team).unwrap().lineup[0];
+        assert!(sim.free_agents().is_empty());
+
+        // today's pitchers can't leave mid-game
+        sim.tick_until(|games| games[0].teams.home.pitcher.is_some());
+        let pitcher = sim.games_today()[0].teams.home.pitcher.unwrap();
+        assert!(matches!(
+            sim.release_player(pitcher),
+            Err(DatabaseError::PlayerInUse { kind: "game", .. })
+        ));
+        sim.release_player(shadow).unwrap();
+        assert_eq!(sim.free_agents()[0].id, shadow);
+        assert!(!sim.teams().get(&team).unwrap().shadows.contains(&shadow));
+        assert!(sim.remove_player(shadow).is_err());
+        assert!(sim.validate().is_ok());
+
+        let rookie = Player::generate_with_name(&mut crate::Rng::seeded(3, 4), "Rookie".into());
+        let rookie_id = rookie.id;
+        sim.add_free_agent(rookie.clone()).unwrap();
+        assert_eq!(sim.free_agents().len(), 2);
+        assert_eq!(
+            sim.audit_log().last().unwrap().mutation,
+            Mutation::PlayerAdded { player: rookie_id }
+        );
+
+        // a player already in the database can't be added again, rostered or not
+        assert!(matches!(
+            sim.add_free_agent(rookie.clone()),
+            Err(DatabaseError::PlayerInUse {
+                kind: "free agents",
+                ..
+            })
+        ));
+        let rostered = sim.players().get(&batter).unwrap().clone();
+        assert!(matches!(
+            sim.add_free_agent(rostered),
+            Err(DatabaseError::PlayerInUse { kind: "team", .. })
+        ));
+        let loose = Player::generate_with_name(&mut crate::Rng::seeded(5, 6), "Loose".into());
+        let loose_id = loose.id;
+        sim.add_player(loose.clone()).unwrap();
+        assert!(matches!(
+            sim.add_free_agent(loose),
+            Err(DatabaseError::PlayerExists { player }) if player == loose_id
+        ));
+        assert_eq!(sim.free_agents().len(), 2);
+        assert_eq!(sim.teams().get(&team).unwrap().lineup[0], batter);
+
+        let slot = RosterSlot {
+            position: RosterPosition::Lineup,
+            index: 0,
+        };
+        let far = RosterSlot { index: 99, ..slot };
+        assert!(sim.sign_player(rookie_id, team, far).is_err());
+        assert!(matches!(
+            sim.sign_player(batter, team, slot),
+            Err(DatabaseError::NotFreeAgent { .. })
+        ));
+        sim.sign_player(rookie_id, team, slot).unwrap();
+        assert_eq!(sim.teams().get(&team).unwrap().lineup[0], rookie_id);
+        assert_eq!(sim.free_agents().len(), 1);
+        assert!(sim.validate().is_ok());
+
+        // a rostered free agent is inconsistent
+        let mut broken = sim.clone();
+        broken.database.free_agents.push(batter);
+        assert!(!broken.validate().is_ok());
+    }
+}
@@ -1,10 +1,16 @@
 use crate::database::{CheckEntity, Database};
-use crate::id::{GameId, PlayerId, TeamId};
-use crate::DatabaseError;
+use crate::id::{GameId, LeagueId, PlayerId, TeamId};
+use crate::{
+    Baserunner, BattedBall, DatabaseError, GameRules, Mutation, PitchDetail, Play, PlayEvent, Rng,
+    RngSource, Sim, Weather,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Write};
+use std::ops::Not;
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[non_exhaustive]
 pub struct Game {
     pub id: GameId,
@@ -13,22 +19,376 @@ pub struct Game {
     pub last_update: String,
 
     pub teams: AwayHome<GameTeam>,
+    /// The [league](crate::League) the game is played in (`None` for the main league), set from
+    /// its teams when its day starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub league: Option<LeagueId>,
     #[serde(flatten)]
     pub inning: Inning,
     pub at_bat: Option<PlayerId>,
     pub balls: u8,
     pub strikes: u8,
+    /// Pitches thrown to the batter so far. Reset with the count when the plate appearance ends.
+    #[serde(default)]
+    pub pitches: u16,
     pub outs: u8,
-    pub baserunners: Vec<(PlayerId, u8)>,
+    pub baserunners: Vec<Baserunner>,
+    #[serde(default)]
+    pub weather: Weather,
+    /// How many fans turned out, worked out when the game's day starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attendance: Option<u32>,
+
+    /// Every play in the game so far, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plays: Vec<Play>,
+
+    /// Why the game was suspended, if it was. Suspended games are not ticked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspended: Option<String>,
+
+    /// Why the game ended without being played out, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub called_off: Option<CalledOff>,
+
+    /// The rules the game is played by, set from its phase when its day starts.
+    #[serde(default)]
+    pub rules: GameRules,
+
+    /// How many times the game has been ticked, including ticks that only announce something.
+    #[serde(default)]
+    pub ticks: u32,
+
+    /// A summary of the game, filled in when someone wins it or it ends in a tie.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<GameOutcome>,
+
+    /// This game's own RNG stream, if the sim was configured with `Sim::set_game_streams` when
+    /// the game started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) rng: Option<Rng>,
+
+    /// How the ball left the bat on the play being ticked, until it's recorded on the play.
+    #[serde(skip)]
+    pub(crate) batted_ball: Option<BattedBall>,
+    /// Commentary for the pitch being ticked, until it's recorded on the play.
+    #[serde(skip)]
+    pub(crate) pitch: Option<PitchDetail>,
+}
+
+/// How a game ended without being played out.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[non_exhaustive]
+pub enum CalledOff {
+    /// A team forfeited, from [`Sim::forfeit_game`]. The other team won by
+    /// [`FORFEIT_RUNS`] to nothing.
+    Forfeit { team: TeamId, reason: String },
+    /// The game was cancelled, from [`Sim::cancel_game`]. Nobody won, and the game doesn't
+    /// count toward anyone's record.
+    Cancelled { reason: String },
+}
+
+/// A summary of a finished game, so it doesn't need to be worked out again from the plays.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct GameOutcome {
+    /// See [`Game::ticks`].
+    pub ticks: u32,
+    pub plate_appearances: u32,
+    /// The inning the game ended in, starting from 1. This is 0 for a game that ended before
+    /// the first pitch, like a forfeit.
+    pub innings: usize,
+    /// The winning team's pitcher when it took the lead for good, if it took the lead on a play.
+    pub winning_pitcher: Option<PlayerId>,
+    /// The pitcher who gave up the run that put the winning team ahead for good.
+    pub losing_pitcher: Option<PlayerId>,
+    /// Whether the home team won without batting in the bottom of the last inning, shaming the
+    /// away team.
+    pub shamed: bool,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+/// The runs the winning team is credited with in a forfeit. The forfeiting team gets none.
+pub const FORFEIT_RUNS: u16 = 9;
+
+impl<R: RngSource> Sim<R> {
+    /// Change the weather in one of today's games.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games.
+    pub fn set_weather(&mut self, game: GameId, weather: Weather) -> Result<(), DatabaseError> {
+        self.update_game(game, |game| game.weather = weather)
+    }
+
+    /// Give a team in one of today's games a run, recorded as a [`PlayEvent::AwardedRun`]. If
+    /// the run decides the game (say, between innings after the ninth), the game ends right away.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games, or is already over.
+    pub fn award_run(&mut self, game: GameId, team: TeamSelect) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        if self.database.games_today[i].is_finished() {
+            return Err(DatabaseError::GameOver { game });
+        }
+        // swap the game out of the database while it changes, as a tick does
+        let mut data = std::mem::take(&mut self.database.games_today[i]);
+        let mut update = String::new();
+        data.award_run(&mut self.database, team, &mut update);
+        data.last_update = update;
+        self.database.games_today[i] = data;
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Replace the runner on `base` in one of today's games with another player, like a pinch
+    /// runner. The replacement takes over how the runner reached base and the pitcher charged if
+    /// they score, as a pinch runner does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games, if nobody is on `base`, or if
+    /// `runner` is not in the database, is already batting or on base, or is on the roster of
+    /// the team in the field.
+    pub fn replace_baserunner(
+        &mut self,
+        game: GameId,
+        base: u8,
+        runner: PlayerId,
+    ) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        let game_data = &self.database.games_today[i];
+        let Some(replaced) = game_data.baserunners.iter().find(|on| on.base == base) else {
+            return Err(DatabaseError::EmptyBase { game, base });
+        };
+        let replaced = replaced.player;
+        let invalid = |reason| Err(DatabaseError::InvalidGameState { game, reason });
+        if game_data.at_bat == Some(runner)
+            || game_data.baserunners.iter().any(|on| on.player == runner)
+        {
+            return invalid("player is batting or on base more than once");
+        }
+        let fielding = game_data.teams.select(game_data.inning.fielding()).id;
+        if let Some(team) = self.database.teams.get(&fielding) {
+            if team.roster().any(|player| *player == runner) {
+                return invalid("baserunner is on the team in the field");
+            }
+        }
+        runner.try_load(&self.database)?;
+        let game_data = &mut self.database.games_today[i];
+        for on in &mut game_data.baserunners {
+            if on.base == base {
+                on.player = runner;
+            }
+        }
+        game_data.update_forced();
+        self.database.record(Mutation::BaserunnerReplaced {
+            game,
+            base,
+            player: replaced,
+            replacement: runner,
+        });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Bring a new pitcher into one of today's games for the team in the field, or between
+    /// half-innings, the team about to take it. The new pitcher pitches for the rest of the game,
+    /// or until they're changed again. Runners already on base stay charged to the pitcher they
+    /// reached against (see [`Play::inherited_runners`](crate::Play::inherited_runners)).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games, if it's already over, or if
+    /// `pitcher` is not in the rotation or shadows of the team in the field.
+    pub fn change_pitcher(&mut self, game: GameId, pitcher: PlayerId) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        let database = &mut self.database;
+        let game_data = &database.games_today[i];
+        if game_data.is_finished() {
+            return Err(DatabaseError::GameOver { game });
+        }
+        let select = match game_data.inning {
+            Inning::Top(_) | Inning::Bottom(_) => game_data.inning.fielding(),
+            // the team that just batted fields next
+            Inning::Mid(_) | Inning::End(_) => game_data.inning.batting(),
+        };
+        let team = game_data.teams.select(select).id.load(database);
+        if !team.rotation.contains(&pitcher) && !team.shadows.contains(&pitcher) {
+            return Err(DatabaseError::CannotPitch {
+                game,
+                player: pitcher,
+            });
+        }
+        let data = database.games_today[i].teams.select_mut(select);
+        if data.pitcher == Some(pitcher) {
+            return Ok(());
+        }
+        data.pitcher = Some(pitcher);
+        let team = data.id;
+        database.record(Mutation::PitcherChanged {
+            game,
+            team,
+            pitcher,
+        });
+        database.check_after_mutation();
+        Ok(())
+    }
+
+    /// End one of today's games immediately, regardless of the score, with `winner` as the
+    /// winner.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games, or if it's already over.
+    pub fn force_end_game(
+        &mut self,
+        game: GameId,
+        winner: TeamSelect,
+    ) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        let database = &mut self.database;
+        if database.games_today[i].is_finished() {
+            return Err(DatabaseError::GameOver { game });
+        }
+        database.games_today[i].check(database)?;
+        let mut game = std::mem::take(&mut database.games_today[i]);
+        let mut update = String::new();
+        game.finish(database, Some(winner), &mut update);
+        game.last_update = update;
+        database.games_today[i] = game;
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Suspend one of today's games, so it isn't ticked. To pick it back up later, pass it to
+    /// [`Sim::start_day`] on a later day (its state is kept exactly), or resume it today with
+    /// [`Sim::resume_game`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games, or if it's already over or
+    /// suspended.
+    pub fn suspend_game(&mut self, game: GameId, reason: &str) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        let game_data = &mut self.database.games_today[i];
+        if game_data.is_finished() {
+            return Err(DatabaseError::GameOver { game });
+        }
+        if game_data.is_suspended() {
+            return Err(DatabaseError::InvalidGameState {
+                game,
+                reason: "already suspended",
+            });
+        }
+        game_data.suspended = Some(reason.to_owned());
+        self.database.record(Mutation::GameSuspended {
+            game,
+            reason: reason.to_owned(),
+        });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Resume one of today's suspended games.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games, or if it isn't suspended, or
+    /// if it still can't be ticked (for example, if it was suspended by [`Sim::try_tick`] for
+    /// referring to a player who has since been removed).
+    pub fn resume_game(&mut self, game: GameId) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        let game_data = &self.database.games_today[i];
+        if !game_data.is_suspended() {
+            return Err(DatabaseError::InvalidGameState {
+                game,
+                reason: "not suspended",
+            });
+        }
+        game_data.check_tickable(&self.database)?;
+        self.database.games_today[i].suspended = None;
+        self.database.record(Mutation::GameResumed { game });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Have a team forfeit one of today's games. The other team wins by [`FORFEIT_RUNS`] to
+    /// nothing, which is what goes into the standings; the plays made before the forfeit still
+    /// count toward players' stats.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games, or if it's already over.
+    pub fn forfeit_game(
+        &mut self,
+        game: GameId,
+        forfeiting: TeamSelect,
+        reason: &str,
+    ) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        let database = &mut self.database;
+        if database.games_today[i].is_finished() {
+            return Err(DatabaseError::GameOver { game });
+        }
+        database.games_today[i].check(database)?;
+        let mut game = std::mem::take(&mut database.games_today[i]);
+        let mut update = String::new();
+        game.forfeit(database, forfeiting, reason, &mut update);
+        game.last_update = update;
+        database.games_today[i] = game;
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Cancel one of today's games. It's over, with no winner, and isn't counted in the
+    /// standings or in anyone's stats. Neither team's rotation advances.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games, or if it's already over.
+    pub fn cancel_game(&mut self, game: GameId, reason: &str) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        let game_data = &mut self.database.games_today[i];
+        if game_data.is_finished() {
+            return Err(DatabaseError::GameOver { game });
+        }
+        game_data.called_off = Some(CalledOff::Cancelled {
+            reason: reason.to_owned(),
+        });
+        game_data.last_update = format!("Game cancelled: {reason}");
+        self.database.record(Mutation::GameCancelled {
+            game,
+            reason: reason.to_owned(),
+        });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct GameTeam {
     pub id: TeamId,
     pub runs: u16,
     pub runs_by_inning: Vec<u16>,
     pub pitcher: Option<PlayerId>,
     pub lineup_slot: usize,
+    /// Pitches thrown in this game by each of the team's pitchers.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pitch_counts: BTreeMap<PlayerId, u32>,
+}
+
+impl GameTeam {
+    /// Pitches thrown in this game by the team's current pitcher.
+    pub fn pitch_count(&self) -> u32 {
+        self.pitcher
+            .and_then(|pitcher| self.pitch_counts.get(&pitcher).copied())
+            .unwrap_or(0)
+    }
 }
 
 impl Game {
@@ -43,12 +403,156 @@ impl Game {
         }
     }
 
+    /// End the game with `forfeiting` losing by [`FORFEIT_RUNS`] to nothing.
+    pub(crate) fn forfeit(
+        &mut self,
+        database: &mut Database,
+        forfeiting: TeamSelect,
+        reason: &str,
+        out: &mut String,
+    ) {
+        let winner = forfeiting.other();
+        self.teams.select_mut(winner).runs = FORFEIT_RUNS;
+        self.teams.select_mut(forfeiting).runs = 0;
+        let team = self.teams.select(forfeiting).id;
+        self.called_off = Some(CalledOff::Forfeit {
+            team,
+            reason: reason.to_owned(),
+        });
+        write!(out, "The {} forfeit. ", team.load(database).nickname)
+            .expect("std::fmt::Write does not fail on String");
+        database.record(Mutation::GameForfeited {
+            game: self.id,
+            team,
+            reason: reason.to_owned(),
+        });
+        self.finish(database, Some(winner), out);
+    }
+
+    /// Work out the [`GameOutcome`] once the game has a winner.
+    pub(crate) fn outcome(&self, shamed: bool) -> GameOutcome {
+        let (winning_pitcher, losing_pitcher) = self.pitchers_of_record().unzip();
+        GameOutcome {
+            ticks: self.ticks,
+            plate_appearances: self
+                .plays
+                .iter()
+                .filter(|play| play.event.ends_plate_appearance())
+                .count()
+                .try_into()
+                .unwrap_or(u32::MAX),
+            innings: self.inning.number(),
+            winning_pitcher: winning_pitcher.flatten(),
+            losing_pitcher,
+            shamed,
+        }
+    }
+
+    /// The winning team's pitcher when it last took the lead (if they'd pitched yet), and the
+    /// pitcher who gave up the go-ahead run.
+    fn pitchers_of_record(&self) -> Option<(Option<PlayerId>, PlayerId)> {
+        let winner = if self.teams.away.id == self.winner? {
+            TeamSelect::Away
+        } else {
+            TeamSelect::Home
+        };
+        let lead = |runs: &AwayHome<u16>| runs.select(winner) > runs.select(winner.other());
+        let mut runs = AwayHome::<u16>::default();
+        let mut pitching = AwayHome::<Option<PlayerId>>::default();
+        let mut decision = None;
+        for play in &self.plays {
+            if !matches!(play.event, PlayEvent::AwardedRun { .. }) {
+                *pitching.select_mut(play.inning.fielding()) = Some(play.pitcher);
+            }
+            let scoring = play.scoring_team();
+            let was_ahead = lead(&runs);
+            *runs.select_mut(scoring) += play.runs;
+            if scoring == winner && !was_ahead && lead(&runs) {
+                // an awarded run is charged to whoever was pitching against the team, if anyone
+                decision = pitching
+                    .select(winner.other())
+                    .map(|losing| (*pitching.select(winner), losing));
+            }
+        }
+        // a team that scores before it takes the field gets the decision for its first pitcher
+        let first = self
+            .plays
+            .iter()
+            .find(|play| {
+                play.inning.fielding() == winner
+                    && !matches!(play.event, PlayEvent::AwardedRun { .. })
+            })
+            .map(|play| play.pitcher);
+        decision.map(|(pitcher, losing)| (pitcher.or(first), losing))
+    }
+
+    /// Whether the game is over: someone won, it [tied](Game::is_tied), or it was
+    /// [cancelled](Game::is_cancelled).
     pub fn is_finished(&self) -> bool {
-        self.winner.is_some()
+        self.winner.is_some() || self.is_tied() || self.is_cancelled()
+    }
+
+    /// Whether the game ended in a tie, after [`GameRules::max_innings`].
+    pub fn is_tied(&self) -> bool {
+        self.winner.is_none() && self.outcome.is_some()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.called_off, Some(CalledOff::Cancelled { .. }))
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended.is_some()
+    }
+
+    /// Whether the game will be ticked: it's neither finished nor suspended.
+    pub fn is_active(&self) -> bool {
+        !self.is_finished() && !self.is_suspended()
+    }
+
+    /// The number of the base a runner scores by reaching. Bases are numbered from 1 (first base).
+    pub fn home_base(&self) -> u8 {
+        crate::sim::HOME_BASE
     }
 
     pub fn bases_occupied(&self) -> BTreeSet<u8> {
-        self.baserunners.iter().map(|(_, base)| *base).collect()
+        self.baserunners.iter().map(|runner| runner.base).collect()
+    }
+
+    /// Every player this game refers to: the batter, the baserunners and the pitchers responsible
+    /// for them, and the pitchers.
+    pub(crate) fn players(&self) -> impl Iterator<Item = &PlayerId> {
+        self.at_bat
+            .iter()
+            .chain(self.baserunners.iter().map(|runner| &runner.player))
+            .chain(
+                self.baserunners
+                    .iter()
+                    .filter_map(|runner| runner.pitcher.as_ref()),
+            )
+            .chain(self.teams.iter().filter_map(|data| data.pitcher.as_ref()))
+    }
+
+    /// Remove all references to a player. A removed batter's at-bat ends without an outcome, and
+    /// a removed pitcher is replaced from the rotation on the next tick.
+    pub(crate) fn remove_player(&mut self, player: PlayerId) {
+        if self.at_bat == Some(player) {
+            self.at_bat = None;
+            self.balls = 0;
+            self.strikes = 0;
+            self.pitches = 0;
+        }
+        self.baserunners.retain(|runner| runner.player != player);
+        for runner in &mut self.baserunners {
+            if runner.pitcher == Some(player) {
+                runner.pitcher = None;
+            }
+        }
+        for team in self.teams.iter_mut() {
+            if team.pitcher == Some(player) {
+                team.pitcher = None;
+            }
+        }
     }
 }
 
@@ -70,12 +574,7 @@ impl CheckEntity for Game {
                 });
             }
         }
-        for player in self
-            .at_bat
-            .iter()
-            .chain(self.baserunners.iter().map(|(p, _)| p))
-            .chain(self.teams.iter().filter_map(|data| data.pitcher.as_ref()))
-        {
+        for player in self.players() {
             if !database.players.contains_key(player) {
                 problems.push(DatabaseError::BadReference {
                     kind: "player",
@@ -83,11 +582,20 @@ impl CheckEntity for Game {
                 });
             }
         }
+        if let Some(league) = self.league {
+            if !database.leagues.contains_key(&league) {
+                problems.push(DatabaseError::BadReference {
+                    kind: "league",
+                    id: league.0,
+                });
+            }
+        }
         problems
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case", tag = "frame", content = "inning")]
 pub enum Inning {
     Top(usize),
@@ -129,10 +637,7 @@ impl Inning {
     }
 
     pub fn fielding(self) -> TeamSelect {
-        match self {
-            Inning::Top(_) | Inning::Mid(_) => TeamSelect::Home,
-            Inning::Bottom(_) | Inning::End(_) => TeamSelect::Away,
-        }
+        self.batting().other()
     }
 }
 
@@ -142,7 +647,8 @@ impl Default for Inning {
     }
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AwayHome<T> {
     pub away: T,
     pub home: T,
@@ -167,6 +673,46 @@ impl<T> AwayHome<T> {
         }
     }
 
+    /// Like `map`, but also tells `op` which team it's looking at.
+    pub fn map_with_select<U, F>(self, mut op: F) -> AwayHome<U>
+    where
+        F: FnMut(TeamSelect, T) -> U,
+    {
+        AwayHome {
+            away: op(TeamSelect::Away, self.away),
+            home: op(TeamSelect::Home, self.home),
+        }
+    }
+
+    /// Like `map`, but stops at the first error.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error `op` returns, trying the away team first.
+    pub fn try_map<U, E, F>(self, mut op: F) -> Result<AwayHome<U>, E>
+    where
+        F: FnMut(T) -> Result<U, E>,
+    {
+        Ok(AwayHome {
+            away: op(self.away)?,
+            home: op(self.home)?,
+        })
+    }
+
+    pub fn as_ref(&self) -> AwayHome<&T> {
+        AwayHome {
+            away: &self.away,
+            home: &self.home,
+        }
+    }
+
+    pub fn zip<U>(self, other: AwayHome<U>) -> AwayHome<(T, U)> {
+        AwayHome {
+            away: (self.away, other.away),
+            home: (self.home, other.home),
+        }
+    }
+
     pub fn select(&self, select: TeamSelect) -> &T {
         match select {
             TeamSelect::Away => &self.away,
@@ -191,8 +737,55 @@ impl<T> AwayHome<Option<T>> {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl<T> IntoIterator for AwayHome<T> {
+    type Item = T;
+    type IntoIter = std::array::IntoIter<T, 2>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        [self.away, self.home].into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a AwayHome<T> {
+    type Item = &'a T;
+    type IntoIter = std::array::IntoIter<&'a T, 2>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        [&self.away, &self.home].into_iter()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum TeamSelect {
     Away,
     Home,
 }
+
+impl TeamSelect {
+    #[must_use]
+    pub fn other(self) -> TeamSelect {
+        match self {
+            TeamSelect::Away => TeamSelect::Home,
+            TeamSelect::Home => TeamSelect::Away,
+        }
+    }
+}
+
+impl Not for TeamSelect {
+    type Output = TeamSelect;
+
+    fn not(self) -> TeamSelect {
+        self.other()
+    }
+}
+
+impl Display for TeamSelect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TeamSelect::Away => "away",
+            TeamSelect::Home => "home",
+        })
+    }
+}
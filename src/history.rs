@@ -0,0 +1,174 @@
+use crate::id::TeamId;
+use crate::{Database, Date, Game, Rebrand, RngSource, SeasonAwards, Sim};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeBounds;
+
+/// How many past days of finished games the sim keeps. See [`Sim::set_history_retention`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryRetention {
+    /// Don't keep any history. This is the default.
+    #[default]
+    Off,
+    /// Keep up to this many of the most recent days.
+    Days(usize),
+    /// Keep every day.
+    Unbounded,
+}
+
+/// The finished games from one past day.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct HistoryDay {
+    #[serde(flatten)]
+    pub date: Date,
+    pub games: Vec<Game>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct History {
+    #[serde(default)]
+    pub(crate) retention: HistoryRetention,
+    /// Oldest first.
+    #[serde(default)]
+    pub(crate) days: Vec<HistoryDay>,
+    /// Every season's awards, oldest first. These are kept regardless of the retention.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) awards: Vec<SeasonAwards>,
+    /// Every rebrand that has taken effect, oldest first. These are also kept regardless of the
+    /// retention.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) rebrands: Vec<Rebrand>,
+}
+
+impl History {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.retention == HistoryRetention::Off
+            && self.days.is_empty()
+            && self.awards.is_empty()
+            && self.rebrands.is_empty()
+    }
+
+    fn trim(&mut self) {
+        let keep = match self.retention {
+            HistoryRetention::Off => 0,
+            HistoryRetention::Days(days) => days,
+            HistoryRetention::Unbounded => return,
+        };
+        let excess = self.days.len().saturating_sub(keep);
+        self.days.drain(..excess);
+    }
+}
+
+impl Database {
+    /// Keep the finished games from a day that is ending, if history is on.
+    pub(crate) fn remember_day(&mut self, date: Date, games: &[Game]) {
+        if self.history.retention == HistoryRetention::Off {
+            return;
+        }
+        let games = games
+            .iter()
+            .filter(|game| game.is_finished())
+            .cloned()
+            .collect::<Vec<_>>();
+        if !games.is_empty() {
+            self.history.days.push(HistoryDay { date, games });
+            self.history.trim();
+        }
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    pub fn history_retention(&self) -> HistoryRetention {
+        self.database.history.retention
+    }
+
+    /// Set how many past days of finished games to keep. When [`Sim::start_day`] replaces
+    /// today's games, the finished ones are added to the history; unfinished ones are only
+    /// returned.
+    ///
+    /// Lowering the retention drops the oldest days right away.
+    pub fn set_history_retention(&mut self, retention: HistoryRetention) {
+        self.database.history.retention = retention;
+        self.database.history.trim();
+    }
+
+    /// Every day in the history, oldest first.
+    ///
+    /// Games in the history aren't checked for consistency, so they may refer to teams and
+    /// players that have since been removed.
+    pub fn history(&self) -> &[HistoryDay] {
+        &self.database.history.days
+    }
+
+    /// Games in the history played on a date in `dates`, oldest first.
+    pub fn history_between<D>(&self, dates: D) -> impl Iterator<Item = (Date, &Game)>
+    where
+        D: RangeBounds<Date>,
+    {
+        self.history()
+            .iter()
+            .filter(move |day| dates.contains(&day.date))
+            .flat_map(|day| day.games.iter().map(|game| (day.date, game)))
+    }
+
+    /// Games in the history that `team` played in, oldest first.
+    pub fn history_for_team(&self, team: TeamId) -> impl Iterator<Item = (Date, &Game)> {
+        self.history()
+            .iter()
+            .flat_map(|day| day.games.iter().map(|game| (day.date, game)))
+            .filter(move |(_, game)| game.teams.iter().any(|t| t.id == team))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Date, Game, HistoryRetention, Sim};
+
+    #[test]
+    fn history() {
+        let mut sim = crate::sim::tests::two_games();
+        let games = sim.games_today().to_vec();
+        let team = games[0].teams.away.id;
+        let fresh = |games: &[Game]| {
+            games
+                .iter()
+                .map(|game| Game::new(game.teams.as_ref().map(|team| team.id)))
+                .collect::<Vec<_>>()
+        };
+
+        // off by default
+        sim.finish_day();
+        sim.start_day(Date { season: 0, day: 1 }, fresh(&games))
+            .unwrap();
+        assert!(sim.history().is_empty());
+
+        sim.set_history_retention(HistoryRetention::Days(2));
+        for day in 2..5 {
+            sim.finish_day();
+            sim.start_day(Date { season: 0, day }, fresh(&games))
+                .unwrap();
+        }
+        let dates = sim
+            .history()
+            .iter()
+            .map(|day| day.date.day)
+            .collect::<Vec<_>>();
+        assert_eq!(dates, [2, 3]);
+        assert_eq!(sim.history_for_team(team).count(), 2);
+        let start = Date { season: 0, day: 3 };
+        assert_eq!(sim.history_between(start..).count(), 2);
+        assert!(sim.history_between(start..).all(|(date, _)| date == start));
+
+        let json = serde_json::to_string(&sim).unwrap();
+        let other: Sim = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.history_retention(), HistoryRetention::Days(2));
+        assert_eq!(other.history().len(), 2);
+
+        sim.set_history_retention(HistoryRetention::Off);
+        assert!(sim.history().is_empty());
+    }
+}
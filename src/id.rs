@@ -1,5 +1,5 @@
 macro_rules! id {
-    ($name:ident, $field:ident, $ty:ty) => {
+    ($name:ident, $field:ident, $ty:ty, $kind:expr) => {
         id!($name);
 
         // These methods call `Option::expect` because we treat a situation where an ID reference
@@ -21,6 +21,34 @@ macro_rules! id {
                     self
                 ))
             }
+
+            // These methods are for IDs that we haven't checked yet, like those passed into
+            // public methods on `Sim`.
+            pub(crate) fn try_load(
+                self,
+                database: &$crate::Database,
+            ) -> Result<&$ty, $crate::DatabaseError> {
+                database
+                    .$field
+                    .get(&self)
+                    .ok_or($crate::DatabaseError::BadReference {
+                        kind: $kind,
+                        id: self.0,
+                    })
+            }
+
+            pub(crate) fn try_load_mut(
+                self,
+                database: &mut $crate::Database,
+            ) -> Result<&mut $ty, $crate::DatabaseError> {
+                database
+                    .$field
+                    .get_mut(&self)
+                    .ok_or($crate::DatabaseError::BadReference {
+                        kind: $kind,
+                        id: self.0,
+                    })
+            }
         }
     };
 
@@ -37,13 +65,25 @@ macro_rules! id {
             ::serde::Deserialize,
             ::serde::Serialize,
         )]
+        #[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
         #[repr(transparent)]
         #[serde(transparent)]
         pub struct $name(pub ::uuid::Uuid);
 
         impl $name {
+            /// Generate a new random ID.
+            ///
+            /// Without the `os-entropy` feature, IDs come from a per-process counter instead:
+            /// they're unique within a process, but every process generates the same sequence
+            /// of IDs, so IDs generated in different processes collide.
             pub fn new() -> $name {
-                $name(::uuid::Uuid::new_v4())
+                $name($crate::id::new_uuid())
+            }
+
+            /// Roll an ID from the sim's RNG, so a seeded sim creates the same IDs every run.
+            #[allow(unused)]
+            pub(crate) fn rolled(rng: &mut impl $crate::RngSource) -> $name {
+                $name($crate::id::rolled_uuid(rng))
             }
         }
 
@@ -69,7 +109,44 @@ macro_rules! id {
     };
 }
 
-id!(BallparkId);
+id!(BallparkId, ballparks, crate::Ballpark, "ballpark");
 id!(GameId);
-id!(PlayerId, players, crate::Player);
-id!(TeamId, teams, crate::Team);
+id!(LeagueId, leagues, crate::League, "league");
+id!(PlayerId, players, crate::Player, "player");
+id!(TeamId, teams, crate::Team, "team");
+
+/// A version 4 UUID made from three of the RNG's values (53 random bits each).
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn rolled_uuid(rng: &mut impl crate::RngSource) -> uuid::Uuid {
+    let mut bits = 0_u128;
+    for _ in 0..3 {
+        let value = (rng.next_f64() * 9_007_199_254_740_992.0) as u128; // 2^53
+        bits = bits << 53 ^ value;
+    }
+    uuid::Builder::from_random_bytes(bits.to_be_bytes()).into_uuid()
+}
+
+#[cfg(feature = "os-entropy")]
+pub(crate) fn new_uuid() -> uuid::Uuid {
+    uuid::Uuid::new_v4()
+}
+
+/// Without OS entropy, IDs are version 4 UUIDs made from a process-wide counter run through
+/// SplitMix64. They're unique within the process, but not across processes: every process
+/// starts the counter at zero and generates the same IDs in the same order.
+#[cfg(not(feature = "os-entropy"))]
+pub(crate) fn new_uuid() -> uuid::Uuid {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn splitmix64(x: u64) -> u64 {
+        let mut z = x.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let n = COUNTER.fetch_add(2, Ordering::Relaxed);
+    let value = u128::from(splitmix64(n)) << 64 | u128::from(splitmix64(n + 1));
+    uuid::Builder::from_random_bytes(value.to_be_bytes()).into_uuid()
+}
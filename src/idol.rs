@@ -0,0 +1,152 @@
+use crate::id::PlayerId;
+use crate::{DatabaseError, RngSource, Sim};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Players ranked by the number of fans idolizing them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct IdolBoard {
+    votes: BTreeMap<PlayerId, u64>,
+}
+
+impl IdolBoard {
+    /// The number of votes for a player.
+    pub fn votes(&self, player: PlayerId) -> u64 {
+        self.votes.get(&player).copied().unwrap_or_default()
+    }
+
+    /// All players with at least one vote, from most to fewest votes. Ties are ordered by player
+    /// ID so the ranking is stable.
+    pub fn ranking(&self) -> Vec<(PlayerId, u64)> {
+        let mut ranking = self
+            .votes
+            .iter()
+            .filter(|(_, votes)| **votes > 0)
+            .map(|(player, votes)| (*player, *votes))
+            .collect::<Vec<_>>();
+        ranking.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranking
+    }
+
+    /// The top `n` players on the board.
+    pub fn top(&self, n: usize) -> Vec<PlayerId> {
+        self.ranking()
+            .into_iter()
+            .take(n)
+            .map(|(player, _)| player)
+            .collect()
+    }
+
+    /// A player's position on the board, starting from 0, if they have any votes.
+    pub fn rank(&self, player: PlayerId) -> Option<usize> {
+        self.ranking().iter().position(|(p, _)| *p == player)
+    }
+
+    pub(crate) fn players(&self) -> impl Iterator<Item = &PlayerId> {
+        self.votes.keys()
+    }
+
+    pub(crate) fn remove(&mut self, player: PlayerId) {
+        self.votes.remove(&player);
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// The idol board, with votes from [`Sim::add_idol_votes`] and [`Sim::set_idol_votes`].
+    pub fn idol_board(&self) -> &IdolBoard {
+        &self.database.idols
+    }
+
+    /// Add votes to the idol board.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any player ID is not in the database. No votes are added in this case.
+    pub fn add_idol_votes<I>(&mut self, votes: I) -> Result<(), DatabaseError>
+    where
+        I: IntoIterator<Item = (PlayerId, u64)>,
+    {
+        let votes = votes.into_iter().collect::<Vec<_>>();
+        self.check_idol_players(&votes)?;
+        for (player, count) in votes {
+            let entry = self.database.idols.votes.entry(player).or_default();
+            *entry = entry.saturating_add(count);
+        }
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Replace the idol board with new vote totals.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any player ID is not in the database. The board is unchanged in this
+    /// case.
+    pub fn set_idol_votes<I>(&mut self, votes: I) -> Result<(), DatabaseError>
+    where
+        I: IntoIterator<Item = (PlayerId, u64)>,
+    {
+        let votes = votes.into_iter().collect::<Vec<_>>();
+        self.check_idol_players(&votes)?;
+        self.database.idols.votes = votes.into_iter().collect();
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    fn check_idol_players(&self, votes: &[(PlayerId, u64)]) -> Result<(), DatabaseError> {
+        for (player, _) in votes {
+            player.try_load(&self.database)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::id::PlayerId;
+    use crate::DatabaseError;
+
+    #[test]
+    fn ranking() {
+        let mut sim = crate::sim::tests::two_games();
+        let mut players = sim.players().keys().copied().take(4).collect::<Vec<_>>();
+        players.sort();
+        let [a, b, c, d] = players[..] else {
+            unreachable!()
+        };
+        sim.set_idol_votes([(d, 5), (b, 3), (c, 5), (a, 0)])
+            .unwrap();
+
+        // ties go to the lower player ID, and players without votes aren't ranked
+        let board = sim.idol_board();
+        assert_eq!(board.ranking(), [(c, 5), (d, 5), (b, 3)]);
+        assert_eq!(board.top(2), [c, d]);
+        assert_eq!(board.rank(b), Some(2));
+        assert_eq!(board.rank(a), None);
+    }
+
+    #[test]
+    fn votes() {
+        let mut sim = crate::sim::tests::two_games();
+        let player = *sim.players().keys().next().unwrap();
+        sim.add_idol_votes([(player, u64::MAX - 1)]).unwrap();
+        sim.add_idol_votes([(player, 2)]).unwrap();
+        assert_eq!(sim.idol_board().votes(player), u64::MAX);
+
+        // an unknown player fails the whole update
+        let before = sim.idol_board().clone();
+        let unknown = PlayerId::new();
+        for result in [
+            sim.set_idol_votes([(player, 1), (unknown, 1)]),
+            sim.add_idol_votes([(player, 1), (unknown, 1)]),
+        ] {
+            assert!(matches!(
+                result,
+                Err(DatabaseError::BadReference { kind: "player", .. })
+            ));
+        }
+        assert_eq!(*sim.idol_board(), before);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::id::{GameId, PlayerId, TeamId};
+use crate::{DatabaseError, Mutation, RngSource, Sim, TeamSelect, Weather};
+use serde::{Deserialize, Serialize};
+
+/// Something that happens to the sim from outside of it, like a ruling from the commissioner.
+/// See [`Sim::inject`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[non_exhaustive]
+pub enum Injection {
+    /// Give a team in one of today's games a run.
+    AwardRun { game: GameId, team: TeamSelect },
+    /// Change the weather in one of today's games.
+    SetWeather { game: GameId, weather: Weather },
+    /// Add a modification to a player.
+    AddPlayerMod { player: PlayerId, name: String },
+    /// Remove a modification from a player.
+    RemovePlayerMod { player: PlayerId, name: String },
+    /// Add a modification to a team.
+    AddTeamMod { team: TeamId, name: String },
+    /// Remove a modification from a team.
+    RemoveTeamMod { team: TeamId, name: String },
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Make something happen in the sim between ticks, on behalf of something outside of it.
+    /// Unlike editing the sim directly, the injection is checked first, and is recorded in the
+    /// [audit log](Sim::audit_log) as a [`Mutation::Injected`], followed by any other mutations
+    /// it makes (like [`Mutation::ModAdded`]).
+    ///
+    /// Returns an event describing what happened.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the sim unchanged, if:
+    /// - the injection refers to a game that is not one of today's games, or is already over
+    /// - it refers to a player or team that is not in the database
+    /// - a mod's name is empty, or a mod being added is already there, or one being removed isn't
+    pub fn inject(&mut self, injection: Injection) -> Result<String, DatabaseError> {
+        let (event, mutation) = match &injection {
+            Injection::AwardRun { game, team } => {
+                let (game, team) = (*game, *team);
+                self.active_game_team(game, team)?;
+                // recorded first, since ending the game makes changes of its own
+                self.database.record(Mutation::Injected { injection });
+                self.award_run(game, team)?;
+                let i = self.game_index(game)?;
+                return Ok(self.database.games_today[i].last_update.clone());
+            }
+            Injection::SetWeather { game, weather } => {
+                self.active_game_team(*game, TeamSelect::Home)?;
+                let i = self.game_index(*game)?;
+                self.database.games_today[i].weather = *weather;
+                (format!("The weather changes to {weather}."), None)
+            }
+            Injection::AddPlayerMod { player, name } => {
+                let data = player.try_load_mut(&mut self.database)?;
+                check_mod(name, data.has_mod(name), true, "player", player.0)?;
+                data.mods.push(name.clone());
+                let mutation = Mutation::PlayerModAdded {
+                    player: *player,
+                    name: name.clone(),
+                    source: None,
+                };
+                (format!("{} gained {name}.", data.name), Some(mutation))
+            }
+            Injection::RemovePlayerMod { player, name } => {
+                let data = player.try_load_mut(&mut self.database)?;
+                check_mod(name, data.has_mod(name), false, "player", player.0)?;
+                data.mods.retain(|m| m != name);
+                let mutation = Mutation::PlayerModRemoved {
+                    player: *player,
+                    name: name.clone(),
+                };
+                (format!("{} lost {name}.", data.name), Some(mutation))
+            }
+            Injection::AddTeamMod { team, name } => {
+                let data = team.try_load_mut(&mut self.database)?;
+                check_mod(name, data.has_mod(name), true, "team", team.0)?;
+                data.mods.push(name.clone());
+                let mutation = Mutation::ModAdded {
+                    team: *team,
+                    name: name.clone(),
+                };
+                (
+                    format!("The {} gained {name}.", data.nickname),
+                    Some(mutation),
+                )
+            }
+            Injection::RemoveTeamMod { team, name } => {
+                let data = team.try_load_mut(&mut self.database)?;
+                check_mod(name, data.has_mod(name), false, "team", team.0)?;
+                data.mods.retain(|m| m != name);
+                let mutation = Mutation::ModRemoved {
+                    team: *team,
+                    name: name.clone(),
+                };
+                (
+                    format!("The {} lost {name}.", data.nickname),
+                    Some(mutation),
+                )
+            }
+        };
+        self.database.record(Mutation::Injected { injection });
+        if let Some(mutation) = mutation {
+            self.database.record(mutation);
+        }
+        self.database.check_after_mutation();
+        Ok(event)
+    }
+
+    /// The nickname of a team in one of today's games, if the game isn't over.
+    fn active_game_team(&self, game: GameId, team: TeamSelect) -> Result<String, DatabaseError> {
+        let data = &self.database.games_today[self.game_index(game)?];
+        if data.is_finished() {
+            return Err(DatabaseError::GameOver { game });
+        }
+        let team = data.teams.select(team).id.try_load(&self.database)?;
+        Ok(team.nickname.clone())
+    }
+}
+
+/// Check that a mod can be added (if `adding`) or removed.
+fn check_mod(
+    name: &str,
+    has_mod: bool,
+    adding: bool,
+    kind: &'static str,
+    id: uuid::Uuid,
+) -> Result<(), DatabaseError> {
+    if name.is_empty() {
+        Err(DatabaseError::EmptyName)
+    } else if has_mod == adding {
+        Err(DatabaseError::ModConflict {
+            kind,
+            id,
+            name: name.to_owned(),
+            present: has_mod,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Injection;
+    use crate::id::PlayerId;
+    use crate::{DatabaseError, Inning, Mutation, PlayEvent, TeamSelect, Weather};
+
+    #[test]
+    fn injections() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.tick();
+        sim.take_audit_log();
+        let game = sim.games_today()[0].clone();
+        let award = Injection::AwardRun {
+            game: game.id,
+            team: TeamSelect::Away,
+        };
+        assert!(sim
+            .inject(award.clone())
+            .unwrap()
+            .ends_with("are awarded a run."));
+        assert_eq!(
+            sim.games_today()[0].teams.away.runs,
+            game.teams.away.runs + 1
+        );
+        sim.inject(Injection::SetWeather {
+            game: game.id,
+            weather: Weather::Eclipse,
+        })
+        .unwrap();
+        assert_eq!(sim.games_today()[0].weather, Weather::Eclipse);
+
+        let player = sim.teams().get(&game.teams.home.id).unwrap().lineup[0];
+        let add = Injection::AddPlayerMod {
+            player,
+            name: "SHELLED".into(),
+        };
+        sim.inject(add.clone()).unwrap();
+        assert!(sim.players().get(&player).unwrap().has_mod("SHELLED"));
+        assert!(matches!(
+            sim.inject(add.clone()),
+            Err(DatabaseError::ModConflict { present: true, .. })
+        ));
+        let remove_team_mod = Injection::RemoveTeamMod {
+            team: game.teams.home.id,
+            name: "SHELLED".into(),
+        };
+        assert!(matches!(
+            sim.inject(remove_team_mod),
+            Err(DatabaseError::ModConflict { present: false, .. })
+        ));
+
+        // each injection is recorded, followed by what it changed
+        let log = sim
+            .take_audit_log()
+            .into_iter()
+            .map(|entry| entry.mutation)
+            .collect::<Vec<_>>();
+        assert_eq!(log.len(), 4);
+        assert_eq!(log[0], Mutation::Injected { injection: award });
+        assert_eq!(log[2], Mutation::Injected { injection: add });
+        assert_eq!(
+            log[3],
+            Mutation::PlayerModAdded {
+                player,
+                name: "SHELLED".into(),
+                source: None,
+            }
+        );
+
+        sim.finish_day();
+        assert!(matches!(
+            sim.inject(Injection::AwardRun {
+                game: game.id,
+                team: TeamSelect::Home,
+            }),
+            Err(DatabaseError::GameOver { .. })
+        ));
+    }
+
+    #[test]
+    fn awarded_runs() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.tick_until(|games| games[0].plays.len() >= 20);
+        let game = sim.games_today()[0].id;
+        let fielding = sim.games_today()[0].inning.fielding();
+        sim.inject(Injection::AwardRun {
+            game,
+            team: fielding,
+        })
+        .unwrap();
+
+        // the run is a play, so the plays still add up to the score
+        let data = sim.game(game).unwrap();
+        let play = data.plays.last().unwrap();
+        assert_eq!(play.event, PlayEvent::AwardedRun { team: fielding });
+        assert_eq!((play.scoring_team(), play.runs), (fielding, 1));
+        for select in [TeamSelect::Away, TeamSelect::Home] {
+            let runs = data
+                .plays
+                .iter()
+                .filter(|play| play.scoring_team() == select)
+                .map(|play| play.runs)
+                .sum::<u16>();
+            assert_eq!(runs, data.teams.select(select).runs);
+        }
+
+        // a run that decides the game ends it right away
+        sim.update_game(game, |game| {
+            game.inning = Inning::End(9);
+            game.teams.away.runs = game.teams.home.runs;
+        })
+        .unwrap();
+        let update = sim
+            .inject(Injection::AwardRun {
+                game,
+                team: TeamSelect::Away,
+            })
+            .unwrap();
+        assert!(update.contains("Game over."), "{update}");
+        let data = sim.game(game).unwrap();
+        assert_eq!(data.winner, Some(data.teams.away.id));
+        assert_eq!(data.last_update, update);
+
+        // nobody batted or pitched on it
+        sim.finish_day();
+        let stats = sim.stats(0).unwrap();
+        assert!(!stats.batting.contains_key(&PlayerId::default()));
+        assert!(!stats.pitching.contains_key(&PlayerId::default()));
+    }
+}
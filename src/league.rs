@@ -0,0 +1,201 @@
+use crate::database::{CheckEntity, Database};
+use crate::id::{LeagueId, TeamId};
+use crate::{DatabaseError, RngSource, Sim, Standing, Table};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A league hosted alongside the sim's main league, like a side league of short circuits.
+///
+/// Every team is in the main league unless its [`Team::league`](crate::Team::league) names
+/// another one. Teams only play teams in their own league, and each league has its own schedule
+/// (see [`Sim::schedule`]), standings (see [`Sim::league_standings`]), postseason (see
+/// [`Sim::playoff_odds`]), and elections (see [`Election::league`](crate::Election::league)).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct League {
+    pub id: LeagueId,
+    pub name: String,
+}
+
+impl CheckEntity for League {
+    fn problems(&self, _database: &Database) -> Vec<DatabaseError> {
+        let mut problems = Vec::new();
+        if self.id.0.is_nil() {
+            problems.push(DatabaseError::NilId);
+        }
+        if self.name.is_empty() {
+            problems.push(DatabaseError::EmptyName);
+        }
+        problems
+    }
+}
+
+impl Database {
+    /// The teams in a league (`None` for the main league), in ID order.
+    pub(crate) fn league_teams(&self, league: Option<LeagueId>) -> Vec<TeamId> {
+        self.teams
+            .values()
+            .filter(|team| team.league == league)
+            .map(|team| team.id)
+            .collect()
+    }
+
+    /// Every team, grouped by league: the main league first, then the rest in ID order.
+    pub(crate) fn teams_by_league(&self) -> BTreeMap<Option<LeagueId>, Vec<TeamId>> {
+        let mut leagues = BTreeMap::<_, Vec<_>>::new();
+        for team in self.teams.values() {
+            leagues.entry(team.league).or_default().push(team.id);
+        }
+        leagues
+    }
+
+    /// Whether two teams are in the same league.
+    pub(crate) fn same_league(&self, a: TeamId, b: TeamId) -> bool {
+        a.load(self).league == b.load(self).league
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// The leagues hosted alongside the main league.
+    pub fn leagues(&self) -> &Table<LeagueId, League> {
+        &self.database.leagues
+    }
+
+    /// Add a league to the database, or replace the league with the same ID. Teams join it by
+    /// setting their [`Team::league`](crate::Team::league).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the league's ID is nil (an all-zero UUID) or its name is empty.
+    pub fn add_league(&mut self, league: League) -> Result<(), DatabaseError> {
+        league.check(&self.database)?;
+        self.database.leagues.insert(league.id, league);
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// The teams in a league, or in the main league for `None`, in ID order.
+    pub fn league_teams(&self, league: Option<LeagueId>) -> Vec<TeamId> {
+        self.database.league_teams(league)
+    }
+
+    /// A season's [standings](Sim::standings), for only the teams now in a league (or in the
+    /// main league, for `None`).
+    pub fn league_standings(&self, league: Option<LeagueId>, season: u16) -> Vec<Standing<'_>> {
+        self.standings(season)
+            .into_iter()
+            .filter(|standing| {
+                self.teams()
+                    .get(&standing.team)
+                    .is_some_and(|team| team.league == league)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::League;
+    use crate::id::LeagueId;
+    use crate::{
+        AwayHome, DatabaseError, Date, Effect, Election, Game, PlayoffOddsConfig, Proposal, Tally,
+    };
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn leagues() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let league = League {
+            id: LeagueId::new(),
+            name: "Short Circuits".into(),
+        };
+        sim.add_league(league.clone()).unwrap();
+        let teams = sim.teams().keys().copied().collect::<Vec<_>>();
+        for team in &teams[2..] {
+            sim.update_team(*team, |team| team.league = Some(league.id))
+                .unwrap();
+        }
+        assert!(sim
+            .update_team(teams[0], |team| team.league = Some(LeagueId::new()))
+            .is_err());
+        assert_eq!(sim.league_teams(None), teams[..2]);
+        assert_eq!(sim.league_teams(Some(league.id)), teams[2..]);
+
+        // each league plays its own schedule, and teams from different leagues can't meet
+        for round in 0..3 {
+            for game in sim.round_robin(round) {
+                let [away, home] = [game.teams.away.id, game.teams.home.id];
+                assert_eq!(teams[2..].contains(&away), teams[2..].contains(&home));
+            }
+        }
+        let mixed = Game::new(AwayHome {
+            away: teams[0],
+            home: teams[2],
+        });
+        assert!(matches!(
+            sim.start_day(Date { season: 0, day: 1 }, vec![mixed]),
+            Err(DatabaseError::CrossLeague { .. })
+        ));
+        let games = sim.round_robin(1);
+        sim.start_day(Date { season: 0, day: 1 }, games).unwrap();
+        assert_eq!(sim.games_today()[1].league, Some(league.id));
+        sim.finish_day();
+
+        // standings and the postseason are kept apart
+        let standings = sim.league_standings(Some(league.id), 0);
+        assert_eq!(standings.len(), 2);
+        assert!(standings
+            .iter()
+            .all(|standing| teams[2..].contains(&standing.team)));
+        let odds = sim.playoff_odds(&PlayoffOddsConfig {
+            trials: 10,
+            ..PlayoffOddsConfig::default()
+        });
+        for league_teams in [&teams[..2], &teams[2..]] {
+            let championships = league_teams
+                .iter()
+                .map(|team| odds[team].championship)
+                .sum::<f64>();
+            assert!((championships - 1.0).abs() < 1e-9);
+        }
+
+        // elections only reach the league holding them
+        let election = Election {
+            league: Some(league.id),
+            decrees: vec![Proposal {
+                title: "Short Circuit".into(),
+                effect: Effect::AddMod {
+                    name: "SHORT_CIRCUIT".into(),
+                },
+            }],
+            decrees_passed: 1,
+            ..Election::default()
+        };
+        let outsider = Tally {
+            wills: BTreeMap::from([(teams[0], vec![1])]),
+            ..Tally::default()
+        };
+        assert!(matches!(
+            sim.run_election(&election, &outsider),
+            Err(DatabaseError::NotInLeague { .. })
+        ));
+        sim.run_election(&election, &Tally::default()).unwrap();
+        for team in sim.teams().values() {
+            assert_eq!(
+                team.has_mod("SHORT_CIRCUIT"),
+                team.league == Some(league.id)
+            );
+        }
+
+        // leagues are saved with the sim
+        let json = serde_json::to_string(&sim).unwrap();
+        let other: crate::Sim = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.leagues().get(&league.id), Some(&league));
+        assert_eq!(
+            other.teams().get(&teams[2]).unwrap().league,
+            Some(league.id)
+        );
+    }
+}
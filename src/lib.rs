@@ -5,53 +5,324 @@
     clippy::uninlined_format_args
 )]
 
+#[cfg(feature = "api")]
+pub mod api;
+mod attendance;
+mod audit;
+mod awards;
 mod ballpark;
+mod baserunner;
+#[cfg(feature = "binary")]
+mod binary;
+mod builder;
+#[cfg(feature = "checkpoint")]
+mod checkpoint;
+#[cfg(feature = "chronicler")]
+pub mod chronicler;
+mod commentary;
 mod database;
+mod datablase;
+mod diff;
+#[cfg(feature = "json")]
+mod divergence;
+mod editing;
+mod election;
+mod expansion;
+mod fielding;
+mod formulas;
+mod free_agents;
 mod game;
+mod history;
 pub mod id;
+mod idol;
+mod injection;
+mod league;
+mod matchup;
+pub mod mods;
+mod odds;
+mod play;
 mod player;
+mod playoffs;
+mod rating;
+mod rebrand;
+mod replay;
+mod retrosheet;
 mod rng;
+mod roster;
+mod rotation;
+mod rules;
+mod schedule;
+mod schema;
+mod scoreboard;
+mod sensitivity;
 mod sim;
+mod snacks;
+mod snapshot;
+mod stats;
+mod substitution;
+mod table;
 mod team;
-mod util;
+pub mod util;
+mod weather;
 
 use crate::database::{CheckEntity, Database};
-use crate::id::{PlayerId, TeamId};
+use crate::id::{BallparkId, GameId, PlayerId, TeamId};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::borrow::Cow;
 
+pub use crate::audit::{AuditEntry, Mutation};
+pub use crate::awards::{AwardCategory, SeasonAwards};
 pub use crate::ballpark::Ballpark;
-pub use crate::database::DatabaseError;
-pub use crate::game::{AwayHome, Game, GameTeam, Inning, TeamSelect};
-pub use crate::player::Player;
-pub use crate::rng::Rng;
-pub use crate::team::Team;
-pub use crate::util::Date;
-
-#[derive(Debug, Default, Deserialize, Serialize)]
-pub struct Sim {
-    rng: Rng,
-    #[serde(flatten, deserialize_with = "deserialize_database")]
+pub use crate::baserunner::{Baserunner, Reached};
+#[cfg(feature = "binary")]
+pub use crate::binary::BinaryError;
+pub use crate::builder::{GameBuilder, PlayerBuilder, TeamBuilder};
+#[cfg(feature = "checkpoint")]
+pub use crate::checkpoint::{CheckpointError, Checkpoints};
+pub use crate::commentary::{PitchDetail, PitchLocation, PitchType};
+pub use crate::database::{CheckPolicy, DatabaseError, Problem, ValidationReport};
+pub use crate::datablase::{GameEvent, GameEventBaseRunner};
+pub use crate::diff::{Change, SimDiff};
+#[cfg(feature = "json")]
+pub use crate::divergence::Divergence;
+pub use crate::editing::ATTRIBUTE_RANGE;
+pub use crate::election::{Effect, Election, ElectionResults, Proposal, Tally, Votes};
+pub use crate::expansion::Expansion;
+pub use crate::fielding::{BattedBall, Position};
+pub use crate::formulas::{DefaultFormulas, Formulas, Pitch, Term};
+pub use crate::game::{
+    AwayHome, CalledOff, Game, GameOutcome, GameTeam, Inning, TeamSelect, FORFEIT_RUNS,
+};
+pub use crate::history::{HistoryDay, HistoryRetention};
+pub use crate::idol::IdolBoard;
+pub use crate::injection::Injection;
+pub use crate::league::League;
+pub use crate::matchup::{MatchupRates, MatchupReport};
+pub use crate::play::{Play, PlayEvent, RunnerMove, RunnerResult};
+pub use crate::player::{Attribute, Player, UnknownValue};
+pub use crate::playoffs::{PlayoffOdds, PlayoffOddsConfig};
+pub use crate::rating::{RatingConfig, Standing, INITIAL_RATING};
+pub use crate::rebrand::{Rebrand, TeamIdentity};
+pub use crate::replay::ReplayMismatch;
+pub use crate::rng::{Rng, RngSource, Roll};
+pub use crate::rotation::{EmptyRotation, MachinePolicy};
+pub use crate::rules::GameRules;
+pub use crate::schedule::ScheduleConfig;
+pub use crate::schema::SCHEMA_VERSION;
+pub use crate::scoreboard::Scoreboard;
+pub use crate::sensitivity::{OutcomeRates, SensitivityPoint};
+pub use crate::sim::SimError;
+pub use crate::snacks::{Bet, Fan, Payout, SnackKind};
+pub use crate::snapshot::{GameSnapshot, SimSnapshot};
+pub use crate::stats::{
+    BattingStats, HeadToHead, PitchingStats, SeasonStats, Split, SplitStats, TeamRecord,
+};
+pub use crate::table::Table;
+pub use crate::team::{RosterPosition, RosterSize, RosterSlot, Team};
+pub use crate::util::{Date, Phase, SeasonConfig, SeasonPart};
+pub use crate::weather::{Forecast, Weather};
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(try_from = "schema::SimRepr<R>")]
+pub struct Sim<R = Rng> {
+    schema_version: schema::CurrentVersion,
+    rng: R,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    game_streams: Option<(u64, u64)>,
+    #[serde(flatten)]
     database: Database,
 }
 
 impl Sim {
+    #[cfg(feature = "os-entropy")]
     pub fn new() -> Sim {
         Sim::default()
     }
 
-    pub fn players(&self) -> &BTreeMap<PlayerId, Player> {
+    /// Create a sim with an RNG seeded from `s0` and `s1`, without touching the operating
+    /// system's random number generator.
+    pub fn seeded(s0: u64, s1: u64) -> Sim {
+        Sim::with_rng(Rng::seeded(s0, s1))
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Create a sim that rolls using `rng` instead of the default [`Rng`].
+    pub fn with_rng(rng: R) -> Sim<R> {
+        Sim {
+            schema_version: schema::CurrentVersion,
+            rng,
+            game_streams: None,
+            database: Database::default(),
+        }
+    }
+
+    pub fn date(&self) -> Date {
+        self.database.date
+    }
+
+    pub fn season_config(&self) -> &SeasonConfig {
+        &self.database.season_config
+    }
+
+    pub fn set_season_config(&mut self, config: SeasonConfig) {
+        self.database.season_config = config;
+    }
+
+    /// The phase of the season the current date falls in, according to the sim's
+    /// [`SeasonConfig`].
+    pub fn phase(&self) -> Phase {
+        self.database.date.phase(&self.database.season_config)
+    }
+
+    pub fn players(&self) -> &Table<PlayerId, Player> {
         &self.database.players
     }
 
-    pub fn teams(&self) -> &BTreeMap<TeamId, Team> {
+    pub fn teams(&self) -> &Table<TeamId, Team> {
         &self.database.teams
     }
 
+    /// Every player on a team's roster: the lineup, then the rotation, then the shadows. Returns
+    /// an empty list if the team is not in the database.
+    pub fn players_on_team(&self, team: TeamId) -> Vec<&Player> {
+        self.database
+            .teams
+            .get(&team)
+            .map_or_else(Vec::new, |team| {
+                team.roster()
+                    .map(|player| player.load(&self.database))
+                    .collect()
+            })
+    }
+
+    /// The team a player is rostered on, if any.
+    pub fn team_of(&self, player: PlayerId) -> Option<&Team> {
+        self.database
+            .roster_slot_of(player)
+            .ok()
+            .map(|(team, _)| team.load(&self.database))
+    }
+
+    /// A player's attributes as they would play today, after any of their team's mods that only
+    /// apply during part of the season (like [Earlbirds](mods::EARLBIRDS)). Free agents play as
+    /// they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not in the database.
+    pub fn effective_player(&self, player: PlayerId) -> Result<Cow<'_, Player>, DatabaseError> {
+        let data = player.try_load(&self.database)?;
+        Ok(match self.team_of(player) {
+            Some(team) => {
+                mods::effective(data, team, self.database.date, self.database.season_config)
+            }
+            None => Cow::Borrowed(data),
+        })
+    }
+
+    /// The chain of pitchers whose debts led to a player's mark, starting with the pitcher who
+    /// marked them (see [`Player::marked_by`]). The chain stops at a player with no mark, a
+    /// player who is no longer in the database, or a player already in the chain.
+    pub fn mark_chain(&self, player: PlayerId) -> Vec<PlayerId> {
+        let mut chain = Vec::new();
+        let mut next = self.players().get(&player).and_then(|p| p.marked_by);
+        while let Some(id) = next {
+            if id == player || chain.contains(&id) {
+                break;
+            }
+            chain.push(id);
+            next = self.players().get(&id).and_then(|p| p.marked_by);
+        }
+        chain
+    }
+
+    /// Players in the free agent pool: not on any team, and available to sign with
+    /// [`Sim::sign_player`]. They're in the order they became free agents.
+    pub fn free_agents(&self) -> Vec<&Player> {
+        self.database
+            .free_agents
+            .iter()
+            .map(|player| player.load(&self.database))
+            .collect()
+    }
+
+    pub fn ballparks(&self) -> &Table<BallparkId, Ballpark> {
+        &self.database.ballparks
+    }
+
     pub fn games_today(&self) -> &[Game] {
         &self.database.games_today
     }
 
+    /// One of today's games.
+    pub fn game(&self, id: GameId) -> Option<&Game> {
+        self.database.games_today.iter().find(|game| game.id == id)
+    }
+
+    /// Today's games that are still being played.
+    pub fn active_games(&self) -> impl Iterator<Item = &Game> {
+        self.database
+            .games_today
+            .iter()
+            .filter(|game| game.is_active())
+    }
+
+    /// Today's games that are over.
+    pub fn finished_games(&self) -> impl Iterator<Item = &Game> {
+        self.database
+            .games_today
+            .iter()
+            .filter(|game| game.is_finished())
+    }
+
+    /// Start or stop recording the values drawn from the sim's RNG. Each roll against a formula's
+    /// threshold records the terms that went into it (see [`Pitch::term`]); values drawn without
+    /// a label, like those behind [`RngSource::next_gaussian`], are recorded with no label.
+    /// Games with their own streams (see [`Sim::set_game_streams`]) don't draw from the sim's
+    /// RNG, so their rolls aren't recorded.
+    pub fn set_tracing(&mut self, enabled: bool) {
+        self.rng.set_tracing(enabled);
+    }
+
+    /// Choose whether each [`Play`] keeps a copy of its tick's update as its
+    /// [description](Play::description). Each copy is an allocation per pitch, so batch
+    /// simulations that never read descriptions can turn them off; plays then have empty
+    /// descriptions. [`Game::last_update`] is written either way, into a buffer reused from tick
+    /// to tick.
+    ///
+    /// This is a setting rather than part of the sim's state, so it isn't saved.
+    pub fn set_play_descriptions(&mut self, enabled: bool) {
+        self.database.skip_descriptions = !enabled;
+    }
+
+    /// The values drawn from the sim's RNG since the start of the last tick, or `None` if tracing
+    /// is disabled. Every kind of tick clears the trace first, so anything drawn between ticks,
+    /// like when starting a day, is kept only until the next tick.
+    pub fn trace(&self) -> Option<&[Roll]> {
+        self.rng.trace()
+    }
+
+    /// Check the database for every problem that would make it inconsistent.
+    ///
+    /// A `Sim` keeps its database consistent, so this is mostly useful for reporting everything
+    /// wrong with imported data at once (the `Deserialize` implementation fails with the same
+    /// report).
+    pub fn validate(&self) -> ValidationReport {
+        self.database.validate()
+    }
+
+    pub fn check_policy(&self) -> CheckPolicy {
+        self.database.check_policy
+    }
+
+    /// Choose when the sim checks its own consistency. Each check scans the whole database, which
+    /// can cost more than the tick itself; see [`CheckPolicy`] for the defaults.
+    pub fn set_check_policy(&mut self, policy: CheckPolicy) {
+        self.database.check_policy = policy;
+    }
+
     /// Add a player to the database.
     ///
     /// # Errors
@@ -59,11 +330,86 @@ impl Sim {
     /// Returns an error if the player's ID is nil (an all-zero UUID).
     pub fn add_player(&mut self, player: Player) -> Result<(), DatabaseError> {
         player.check(&self.database)?;
-        self.database.players.insert(player.id, player);
-        self.database.debug_check();
+        let id = player.id;
+        self.database.players.insert(id, player);
+        self.database.record(Mutation::PlayerAdded { player: id });
+        self.database.check_after_mutation();
         Ok(())
     }
 
+    /// Edit a player in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not in the database, or if the edited player is invalid
+    /// (including if `f` changes the player's ID). The player is unchanged in this case.
+    pub fn update_player<F>(&mut self, id: PlayerId, f: F) -> Result<(), DatabaseError>
+    where
+        F: FnOnce(&mut Player),
+    {
+        let player = id.try_load_mut(&mut self.database)?;
+        let backup = player.clone();
+        f(player);
+        let player = id.load(&self.database);
+        let result = if player.id == id {
+            player.check(&self.database)
+        } else {
+            Err(DatabaseError::KeyMismatch { key: id.0 })
+        };
+        if let Err(err) = result {
+            *id.load_mut(&mut self.database) = backup;
+            return Err(err);
+        }
+        self.database.record(Mutation::PlayerChanged { player: id });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Remove a player from the database, returning the removed player.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - the player is not in the database
+    /// - the player is on a team's roster, in one of today's games, on the idol board, or a
+    ///   free agent (use [`Sim::remove_player_force`] to remove those references too)
+    pub fn remove_player(&mut self, player: PlayerId) -> Result<Player, DatabaseError> {
+        if let Some((kind, id)) = self.database.player_references(player).into_iter().next() {
+            return Err(DatabaseError::PlayerInUse { player, kind, id });
+        }
+        self.remove_player_force(player)
+    }
+
+    /// Remove a player from the database, along with every reference to them from team rosters,
+    /// today's games, the idol board, and the free agent pool. Returns the removed player.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not in the database.
+    pub fn remove_player_force(&mut self, player: PlayerId) -> Result<Player, DatabaseError> {
+        let removed = self
+            .database
+            .players
+            .remove(&player)
+            .ok_or(DatabaseError::BadReference {
+                kind: "player",
+                id: player.0,
+            })?;
+        while let Ok((team, slot)) = self.database.roster_slot_of(player) {
+            self.database.roster_remove(team, slot);
+            self.database
+                .record(Mutation::RosterRemoved { team, player, slot });
+        }
+        for game in &mut self.database.games_today {
+            game.remove_player(player);
+        }
+        self.database.idols.remove(player);
+        self.database.free_agents.retain(|p| *p != player);
+        self.database.record(Mutation::PlayerRemoved { player });
+        self.database.check_after_mutation();
+        Ok(removed)
+    }
+
     /// Add a team to the database.
     ///
     /// # Errors
@@ -73,41 +419,228 @@ impl Sim {
     /// - the team references player IDs that are not in the database
     pub fn add_team(&mut self, team: Team) -> Result<(), DatabaseError> {
         team.check(&self.database)?;
-        self.database.teams.insert(team.id, team);
-        self.database.debug_check();
+        let id = team.id;
+        self.database.teams.insert(id, team);
+        self.database.record(Mutation::TeamAdded { team: id });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Give each game started after this call its own RNG stream, derived from the game's ID and
+    /// a seed drawn from the sim's RNG, instead of sharing the sim's RNG. Games with their own
+    /// streams have the same outcomes regardless of what other games are scheduled alongside
+    /// them, and two sims seeded the same way give the same game the same stream.
+    ///
+    /// The seed is drawn when streams are turned on; turning them on again keeps it. Pass `false`
+    /// to go back to sharing the sim's RNG for new games. Rolls from per-game streams are not
+    /// recorded in [`Sim::trace`].
+    pub fn set_game_streams(&mut self, enabled: bool) {
+        if !enabled {
+            self.game_streams = None;
+        } else if self.game_streams.is_none() {
+            self.game_streams = Some((
+                self.rng.next_labeled("game streams seed").to_bits(),
+                self.rng.next_labeled("game streams seed").to_bits(),
+            ));
+        }
+    }
+
+    /// Add a ballpark to the database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - the ballpark's ID is nil (an all-zero UUID)
+    /// - the ballpark references a team ID that is not in the database
+    pub fn add_ballpark(&mut self, ballpark: Ballpark) -> Result<(), DatabaseError> {
+        ballpark.check(&self.database)?;
+        let id = ballpark.id;
+        self.database.ballparks.insert(id, ballpark);
+        self.database
+            .record(Mutation::BallparkAdded { ballpark: id });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Edit a team in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the team is not in the database, or if the edited team is invalid
+    /// (including if `f` changes the team's ID). The team is unchanged in this case.
+    pub fn update_team<F>(&mut self, id: TeamId, f: F) -> Result<(), DatabaseError>
+    where
+        F: FnOnce(&mut Team),
+    {
+        let team = id.try_load_mut(&mut self.database)?;
+        let backup = team.clone();
+        f(team);
+        let team = id.load(&self.database);
+        let result = if team.id == id {
+            team.check(&self.database)
+        } else {
+            Err(DatabaseError::KeyMismatch { key: id.0 })
+        };
+        if let Err(err) = result {
+            *id.load_mut(&mut self.database) = backup;
+            return Err(err);
+        }
+        self.database.record(Mutation::TeamChanged { team: id });
+        self.database.check_after_mutation();
         Ok(())
     }
 
+    /// Edit one of today's games in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games, or if the edited game is invalid
+    /// (including if `f` changes the game's ID). The game is unchanged in this case.
+    pub fn update_game<F>(&mut self, id: GameId, f: F) -> Result<(), DatabaseError>
+    where
+        F: FnOnce(&mut Game),
+    {
+        let i = self.game_index(id)?;
+        let game = &mut self.database.games_today[i];
+        let backup = game.clone();
+        f(game);
+        game.update_forced();
+        let game = &self.database.games_today[i];
+        let result = if game.id == id {
+            game.check(&self.database)
+        } else {
+            Err(DatabaseError::KeyMismatch { key: id.0 })
+        };
+        if let Err(err) = result {
+            self.database.games_today[i] = backup;
+            return Err(err);
+        }
+        self.database.record(Mutation::GameChanged { game: id });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    fn game_index(&self, id: GameId) -> Result<usize, DatabaseError> {
+        self.database
+            .games_today
+            .iter()
+            .position(|game| game.id == id)
+            .ok_or(DatabaseError::BadReference {
+                kind: "game",
+                id: id.0,
+            })
+    }
+
     /// Start a new day of games.
     ///
+    /// Any [suspended](Game::suspended) game in `games`, like one carried over from the previous
+    /// day, is resumed exactly where it left off: same inning, count, baserunners, and pitchers.
+    ///
+    /// Teams don't all have to play, and a team can play more than once, as in a doubleheader.
+    /// A team's games are played one after another, in the order they appear in `games`: a game
+    /// doesn't start until every earlier game involving either team is finished or suspended.
+    /// Each game takes its starting pitchers from the rotations as they stand when it starts.
+    ///
     /// Returns the previous day of games.
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - any game's ID is nil (an all-zero UUID)
+    /// - the same game appears more than once
     /// - any game references team or player IDs that are not in the database
+    /// - any team in a game has a roster too small to be scheduled (see
+    ///   [`Team::check_schedulable`])
+    /// - any suspended game still can't be ticked, as for [`Sim::resume_game`]
+    /// - any game is between teams in different [leagues](League)
     pub fn start_day(
         &mut self,
         date: Date,
         games: Vec<Game>,
     ) -> Result<(Date, Vec<Game>), DatabaseError> {
-        for game in &games {
-            game.check(&self.database)?;
+        for (i, game) in games.iter().enumerate() {
+            if games[..i].iter().any(|other| other.id == game.id) {
+                return Err(DatabaseError::DuplicateGame { game: game.id });
+            }
+            if game.is_suspended() {
+                game.check_tickable(&self.database)?;
+            } else {
+                game.check(&self.database)?;
+            }
+            for team in game.teams.iter() {
+                team.id.load(&self.database).check_schedulable()?;
+            }
+            if !self
+                .database
+                .same_league(game.teams.away.id, game.teams.home.id)
+            {
+                return Err(DatabaseError::CrossLeague { game: game.id });
+            }
+        }
+        let mut games = games;
+        let forecast = self.database.apply_forecast(date, &mut games);
+        let rules = self.database.rules_for(date).clone();
+        let mut resumed_games = Vec::new();
+        for game in &mut games {
+            game.league = game.teams.home.id.load(&self.database).league;
+            game.update_forced();
+            let resumed = game.suspended.take().is_some();
+            if resumed {
+                resumed_games.push(game.id);
+            } else {
+                game.rules = rules.clone();
+                // with no weather to draw from, don't roll at all, so default rules don't
+                // change what the rest of the day rolls
+                if !forecast.contains(&game.id) && !rules.weather.is_empty() {
+                    if let Some(weather) = self.rng.choose_labeled("weather", &rules.weather) {
+                        game.weather = *weather;
+                    }
+                }
+            }
+            if let Some(seed) = self.game_streams {
+                // a resumed game keeps its stream where it left off
+                if !resumed || game.rng.is_none() {
+                    game.rng = Some(Rng::derived(seed, game.id.0.as_u128()));
+                }
+            }
         }
+        self.database.tick = 0;
         let old_date = std::mem::replace(&mut self.database.date, date);
         let old_games = std::mem::replace(&mut self.database.games_today, games);
+        for game in resumed_games {
+            self.database.record(Mutation::GameResumed { game });
+        }
+        self.database.remember_day(old_date, &old_games);
+        if date.season != old_date.season {
+            self.database.regress_ratings();
+            self.database.apply_rebrands(date.season);
+        }
+        self.database.draw_crowds();
+        self.update_party_time(old_date);
         Ok((old_date, old_games))
     }
 }
 
-fn deserialize_database<'de, D>(deserializer: D) -> Result<Database, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    let database = Database::deserialize(deserializer)?;
-    database
-        .check_consistency()
-        .map_err(serde::de::Error::custom)?;
-    Ok(database)
+impl<R: RngSource + Clone> Sim<R> {
+    /// Make several changes to the sim at once. `f` is run against a copy of the sim, which
+    /// replaces this sim only if `f` succeeds and the copy's database is consistent. Otherwise,
+    /// this sim is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `f` fails, or [`DatabaseError::Inconsistent`] if `f` left the database
+    /// in an inconsistent state.
+    pub fn transaction<T, F>(&mut self, f: F) -> Result<T, DatabaseError>
+    where
+        F: FnOnce(&mut Sim<R>) -> Result<T, DatabaseError>,
+    {
+        let mut staging = self.clone();
+        let value = f(&mut staging)?;
+        let report = staging.validate();
+        if !report.is_ok() {
+            return Err(DatabaseError::Inconsistent(report));
+        }
+        *self = staging;
+        Ok(value)
+    }
 }
@@ -0,0 +1,321 @@
+#![warn(clippy::pedantic)]
+
+use simx::{Date, Expansion, Phase, RosterSize, Sim, Team};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "usage: simx <command> [options]
+
+commands:
+  generate-league --teams N [--seed S0,S1] [-o OUT]
+      create a new league of N teams with generated rosters
+  run-day SIM [-o OUT]
+      play the next day of round-robin games
+  run-season SIM [-o OUT]
+      play the rest of the current regular season
+  export-stats SIM [--season N] [-o OUT]
+      write a season's stats as JSON (defaults to the current season)
+
+Sims are read and written as JSON. Output goes to standard output unless -o is given.";
+
+// The original twenty teams, used to name generated leagues.
+const TEAMS: [(&str, &str, &str); 20] = [
+    ("Baltimore", "Crabs", "BAL"),
+    ("Boston", "Flowers", "BOS"),
+    ("Canada", "Moist Talkers", "CAN"),
+    ("Charleston", "Shoe Thieves", "CHST"),
+    ("Chicago", "Firefighters", "CHI"),
+    ("Dallas", "Steaks", "DAL"),
+    ("Hades", "Tigers", "HDS"),
+    ("Hawaii", "Fridays", "HAW"),
+    ("Hellmouth", "Sunbeams", "HELL"),
+    ("Houston", "Spies", "HOU"),
+    ("Kansas City", "Breath Mints", "KCBM"),
+    ("LA Unlimited", "Tacos", "LAUT"),
+    ("Mexico City", "Wild Wings", "MXC"),
+    ("Miami", "Dale", "MIA"),
+    ("New York", "Millennials", "NYM"),
+    ("Philly", "Pies", "PHIL"),
+    ("San Francisco", "Lovers", "SFL"),
+    ("Seattle", "Garages", "SEA"),
+    ("Tokyo", "Lift", "TOKY"),
+    ("Yellowstone", "Magic", "YELL"),
+];
+
+fn main() {
+    if let Err(err) = run(&std::env::args().skip(1).collect::<Vec<_>>()) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+struct Args {
+    positional: Vec<String>,
+    output: Option<PathBuf>,
+    teams: Option<usize>,
+    seed: Option<(u64, u64)>,
+    season: Option<u16>,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, Box<dyn Error>> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            output: None,
+            teams: None,
+            seed: None,
+            season: None,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+            match arg.as_str() {
+                "-o" | "--output" => parsed.output = Some(value()?.into()),
+                "--teams" => parsed.teams = Some(value()?.parse()?),
+                "--season" => parsed.season = Some(value()?.parse()?),
+                "--seed" => {
+                    let value = value()?;
+                    let (s0, s1) = value.split_once(',').ok_or("--seed must be S0,S1")?;
+                    parsed.seed = Some((s0.parse()?, s1.parse()?));
+                }
+                _ if arg.starts_with('-') => return Err(format!("unknown option {arg}").into()),
+                _ => parsed.positional.push(arg),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn sim(&self) -> Result<Sim, Box<dyn Error>> {
+        let [path] = self.positional.as_slice() else {
+            return Err(format!("expected one sim file\n\n{USAGE}").into());
+        };
+        read_sim(Path::new(path))
+    }
+
+    fn write<T: serde::Serialize>(&self, value: &T) -> Result<(), Box<dyn Error>> {
+        if let Some(path) = &self.output {
+            let mut writer = BufWriter::new(File::create(path)?);
+            serde_json::to_writer(&mut writer, value)?;
+            writer.flush()?;
+        } else {
+            let mut stdout = std::io::stdout().lock();
+            serde_json::to_writer(&mut stdout, value)?;
+            writeln!(stdout)?;
+        }
+        Ok(())
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let Some((command, args)) = args.split_first() else {
+        return Err(USAGE.into());
+    };
+    let args = Args::parse(args.iter().cloned())?;
+    match command.as_str() {
+        "generate-league" => {
+            let teams = args.teams.ok_or("generate-league needs --teams")?;
+            let mut sim = match args.seed {
+                Some((s0, s1)) => Sim::seeded(s0, s1),
+                None => Sim::new(),
+            };
+            for i in 0..teams {
+                let (location, nickname, shorthand) = TEAMS.get(i).map_or_else(
+                    || {
+                        (
+                            String::new(),
+                            format!("Team {}", i + 1),
+                            format!("T{}", i + 1),
+                        )
+                    },
+                    |(l, n, s)| ((*l).to_owned(), (*n).to_owned(), (*s).to_owned()),
+                );
+                let team = Team::builder()
+                    .location(location)
+                    .nickname(nickname)
+                    .shorthand(shorthand)
+                    .build();
+                let mut expansion = Expansion::default();
+                expansion.roster = Some(RosterSize::STANDARD);
+                sim.add_expansion_team(team, expansion)?;
+            }
+            args.write(&sim)
+        }
+        "run-day" => {
+            let mut sim = args.sim()?;
+            run_day(&mut sim)?;
+            args.write(&sim)
+        }
+        "run-season" => {
+            let mut sim = args.sim()?;
+            let season = next_date(&sim).season;
+            while next_date(&sim).season == season
+                && next_date(&sim).phase(sim.season_config()) == Phase::RegularSeason
+            {
+                run_day(&mut sim)?;
+            }
+            args.write(&sim)
+        }
+        "export-stats" => {
+            let sim = args.sim()?;
+            let season = args.season.unwrap_or(sim.date().season);
+            let stats = sim
+                .stats(season)
+                .ok_or(format!("no stats for season {season}"))?;
+            args.write(stats)
+        }
+        _ => Err(format!("unknown command {command}\n\n{USAGE}").into()),
+    }
+}
+
+fn read_sim(path: &Path) -> Result<Sim, Box<dyn Error>> {
+    let reader =
+        BufReader::new(File::open(path).map_err(|err| format!("{}: {err}", path.display()))?);
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// The date of the next day to play: today, if no games have been scheduled yet, or tomorrow.
+fn next_date(sim: &Sim) -> Date {
+    if sim.games_today().is_empty() {
+        sim.date()
+    } else {
+        sim.date().next_day(sim.season_config())
+    }
+}
+
+fn run_day(sim: &mut Sim) -> Result<(), Box<dyn Error>> {
+    let date = next_date(sim);
+    let games = sim.round_robin(date.day);
+    if games.is_empty() {
+        return Err("the league needs at least two teams".into());
+    }
+    sim.start_day(date, games)?;
+    sim.finish_day();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use simx::{SeasonConfig, SeasonStats, Sim};
+    use std::path::PathBuf;
+
+    /// A file in a fresh temporary directory for one test.
+    fn temp_file(test: &str, name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("simx-{}-{test}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    fn simx(args: &[&str]) {
+        let args = args.iter().map(|arg| (*arg).to_owned()).collect::<Vec<_>>();
+        run(&args).unwrap();
+    }
+
+    fn read<T: serde::de::DeserializeOwned>(path: &PathBuf) -> T {
+        serde_json::from_reader(std::fs::File::open(path).unwrap()).unwrap()
+    }
+
+    fn write(path: &PathBuf, sim: &Sim) {
+        serde_json::to_writer(std::fs::File::create(path).unwrap(), sim).unwrap();
+    }
+
+    /// A league of four teams, written to a file by `generate-league`.
+    fn league(test: &str) -> (PathBuf, Sim) {
+        let path = temp_file(test, "league.json");
+        let out = path.to_str().unwrap();
+        simx(&[
+            "generate-league",
+            "--teams",
+            "4",
+            "--seed",
+            "1,2",
+            "-o",
+            out,
+        ]);
+        let sim = read(&path);
+        (path, sim)
+    }
+
+    #[test]
+    fn generate_league() {
+        let (path, sim) = league("generate-league");
+        assert_eq!(sim.teams().len(), 4);
+        assert!(sim.teams().values().any(|team| team.nickname == "Crabs"));
+        // the same seed generates the same players, except for their IDs
+        let (_, other) = league("generate-league-again");
+        let names = |sim: &Sim| {
+            let mut names = sim
+                .players()
+                .values()
+                .map(|p| p.name.clone())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        assert_eq!(names(&sim), names(&other));
+        let round_trip: Sim = serde_json::from_value(serde_json::to_value(&sim).unwrap()).unwrap();
+        assert_eq!(round_trip, sim);
+        assert!(run(&["generate-league".to_owned()]).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn run_day() {
+        let (path, _) = league("run-day");
+        let out = temp_file("run-day", "day.json");
+        simx(&[
+            "run-day",
+            path.to_str().unwrap(),
+            "-o",
+            out.to_str().unwrap(),
+        ]);
+        let sim: Sim = read(&out);
+        assert_eq!(sim.games_today().len(), 2);
+        assert!(sim.games_today().iter().all(simx::Game::is_finished));
+
+        // the next run picks up on the next day
+        simx(&[
+            "run-day",
+            out.to_str().unwrap(),
+            "-o",
+            out.to_str().unwrap(),
+        ]);
+        let next: Sim = read(&out);
+        assert_eq!(next.date(), sim.date().next_day(sim.season_config()));
+    }
+
+    #[test]
+    fn run_season_and_export_stats() {
+        let (path, mut sim) = league("run-season");
+        sim.set_season_config(SeasonConfig::new(3, 1));
+        write(&path, &sim);
+        let out = temp_file("run-season", "season.json");
+        simx(&[
+            "run-season",
+            path.to_str().unwrap(),
+            "-o",
+            out.to_str().unwrap(),
+        ]);
+        let sim: Sim = read(&out);
+        assert_eq!(sim.date().day, 2);
+
+        let stats = temp_file("run-season", "stats.json");
+        simx(&[
+            "export-stats",
+            out.to_str().unwrap(),
+            "--season",
+            "0",
+            "-o",
+            stats.to_str().unwrap(),
+        ]);
+        let exported: SeasonStats = read(&stats);
+        assert_eq!(&exported, sim.stats(0).unwrap());
+        assert!(exported
+            .teams
+            .values()
+            .all(|record| record.wins + record.losses + record.ties == 3));
+        assert!(run(&["export-stats".to_owned(), path.to_str().unwrap().to_owned()]).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use crate::id::{PlayerId, TeamId};
+use crate::{
+    AwayHome, BattingStats, DatabaseError, Date, Game, HistoryRetention, Rng, RngSource,
+    SeasonStats, Sim,
+};
+use std::collections::BTreeMap;
+
+/// The results of simulating one matchup many times, from [`Sim::matchup_report`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct MatchupReport {
+    pub trials: u32,
+    /// How many games each team won.
+    pub wins: AwayHome<u32>,
+    /// How many games each team scored each number of runs in, indexed by runs.
+    pub runs: AwayHome<Vec<u32>>,
+    /// Each team's batting rates across every game.
+    pub rates: AwayHome<MatchupRates>,
+    /// Every player who was the hero of at least one game, with the chance of being the hero,
+    /// most likely first. A game's hero is the player on the winning team with the most runs
+    /// batted in, then the most hits.
+    pub heroes: Vec<(PlayerId, f64)>,
+}
+
+/// A team's batting rates, per plate appearance.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[non_exhaustive]
+pub struct MatchupRates {
+    pub strikeouts: f64,
+    pub walks: f64,
+    pub home_runs: f64,
+}
+
+impl MatchupReport {
+    /// The average runs each team scored.
+    pub fn mean_runs(&self) -> AwayHome<f64> {
+        self.runs.as_ref().map(|runs| {
+            let total = runs
+                .iter()
+                .zip(0_u32..)
+                .map(|(games, runs)| f64::from(runs) * f64::from(*games))
+                .sum::<f64>();
+            total / f64::from(self.trials.max(1))
+        })
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Simulate a game between two teams `trials` times, as if it were played on `date`, for
+    /// previewing the matchup.
+    ///
+    /// Each game is played on a copy of the sim with everything as it is now, so the sim itself
+    /// isn't changed and its RNG isn't used: the games are seeded from `seed`, and the same sim
+    /// with the same arguments always gives the same report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either team isn't in the database or can't be scheduled (see
+    /// [`Team::check_schedulable`](crate::Team::check_schedulable)).
+    pub fn matchup_report(
+        &self,
+        teams: AwayHome<TeamId>,
+        date: Date,
+        trials: u32,
+        seed: u64,
+    ) -> Result<MatchupReport, DatabaseError> {
+        let mut base = Sim::with_rng(Rng::seeded_from_str(&format!("matchup-{seed}")));
+        base.database = self.database.clone();
+        base.database.games_today.clear();
+        base.set_history_retention(HistoryRetention::Off);
+        // check the matchup once, up front
+        base.clone().start_day(date, vec![Game::new(teams)])?;
+
+        let rosters = teams.map(|team| {
+            team.load(&self.database)
+                .roster()
+                .copied()
+                .collect::<Vec<_>>()
+        });
+        let mut report = MatchupReport {
+            trials,
+            wins: AwayHome::default(),
+            runs: AwayHome::default(),
+            rates: AwayHome::default(),
+            heroes: Vec::new(),
+        };
+        let mut total = SeasonStats::default();
+        let mut heroes = BTreeMap::new();
+        let mut sim = base.clone();
+        for _ in 0..trials {
+            sim.database.clone_from(&base.database);
+            sim.start_day(date, vec![Game::new(teams)])?;
+            sim.finish_day();
+            let game = &sim.games_today()[0];
+
+            for (data, runs) in game.teams.iter().zip(report.runs.iter_mut()) {
+                let i = usize::from(data.runs);
+                if runs.len() <= i {
+                    runs.resize(i + 1, 0);
+                }
+                runs[i] += 1;
+            }
+            let mut stats = SeasonStats::default();
+            stats.record_game(game);
+            total.record_game(game);
+            if let Some(winner) = game.winner {
+                let (wins, roster) = if winner == teams.away {
+                    (&mut report.wins.away, &rosters.away)
+                } else {
+                    (&mut report.wins.home, &rosters.home)
+                };
+                *wins += 1;
+                let hero = stats
+                    .batting
+                    .iter()
+                    .filter(|(player, _)| roster.contains(player))
+                    .max_by_key(|(player, batting)| {
+                        (
+                            batting.runs_batted_in,
+                            batting.hits,
+                            std::cmp::Reverse(**player),
+                        )
+                    });
+                if let Some((player, _)) = hero {
+                    *heroes.entry(*player).or_insert(0) += 1;
+                }
+            }
+        }
+
+        report.rates = rosters.map(|roster| {
+            let mut sum = BattingStats::default();
+            for batting in roster.iter().filter_map(|player| total.batting.get(player)) {
+                sum.plate_appearances += batting.plate_appearances;
+                sum.strikeouts += batting.strikeouts;
+                sum.walks += batting.walks;
+                sum.home_runs += batting.home_runs;
+            }
+            let rate = |count: u32| f64::from(count) / f64::from(sum.plate_appearances.max(1));
+            MatchupRates {
+                strikeouts: rate(sum.strikeouts),
+                walks: rate(sum.walks),
+                home_runs: rate(sum.home_runs),
+            }
+        });
+        report.heroes = heroes
+            .into_iter()
+            .map(|(player, games)| (player, f64::from(games) / f64::from(trials)))
+            .collect();
+        report
+            .heroes
+            .sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::id::TeamId;
+    use crate::{AwayHome, Date};
+
+    #[test]
+    fn matchup_report() {
+        let sim = crate::sim::tests::two_games();
+        let before = sim.clone();
+        let teams = sim.games_today()[0].teams.as_ref().map(|team| team.id);
+        let date = Date { season: 0, day: 1 };
+        let report = sim.matchup_report(teams, date, 20, 1).unwrap();
+        assert_eq!(sim, before);
+        assert_eq!(report.wins.away + report.wins.home, 20);
+        assert!(report
+            .runs
+            .iter()
+            .all(|runs| runs.iter().sum::<u32>() == 20));
+        let mean = report.mean_runs();
+        assert!(mean.away > 0.0 || mean.home > 0.0);
+        assert!(report.rates.iter().all(|rates| rates.strikeouts > 0.0));
+        assert!((report.heroes.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(report.heroes.windows(2).all(|w| w[0].1 >= w[1].1));
+
+        assert_eq!(sim.matchup_report(teams, date, 20, 1).unwrap(), report);
+        let missing = AwayHome {
+            away: TeamId::new(),
+            home: teams.home,
+        };
+        assert!(sim.matchup_report(missing, date, 20, 1).is_err());
+    }
+}
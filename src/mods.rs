@@ -0,0 +1,81 @@
+//! Names of the modifications (mods) the sim knows how to apply, as they appear in
+//! [`Player::mods`](crate::Player::mods) and [`Team::mods`](crate::Team::mods).
+//!
+//! Mods are stored as strings so that mods the sim doesn't implement survive a round trip. Any
+//! mod not listed here has no effect.
+
+use crate::{Date, Player, SeasonConfig, SeasonPart, Team};
+use std::borrow::Cow;
+
+/// A pitcher who hits batters with pitches, making them [`UNSTABLE`].
+pub const DEBT: &str = "DEBT";
+
+/// A pitcher who hits batters with pitches, making them [`OBSERVED`].
+pub const REFINANCED: &str = "REFINANCED";
+
+/// A player marked by a [`DEBT`] pitcher. Eclipse weather doesn't do anything yet, but the mark
+/// is kept for when it does; see [`Player::marked_by`](crate::Player::marked_by).
+pub const UNSTABLE: &str = "UNSTABLE";
+
+/// A player marked by a [`REFINANCED`] pitcher.
+pub const OBSERVED: &str = "OBSERVED";
+
+/// A player who may bat again right after their plate appearance ends.
+pub const REVERBERATING: &str = "REVERBERATING";
+
+/// A player who may bat again right after their plate appearance ends, but only in
+/// [`Weather::Reverb`](crate::Weather::Reverb).
+pub const REPEATING: &str = "REPEATING";
+
+/// A player trapped in a Peanut shell. Shelled pitchers are skipped in the rotation.
+pub const SHELLED: &str = "SHELLED";
+
+/// A player who is Elsewhere. Elsewhere pitchers are skipped in the rotation.
+pub const ELSEWHERE: &str = "ELSEWHERE";
+
+/// A team whose players overperform in the [earlseason](SeasonPart::Earlseason).
+pub const EARLBIRDS: &str = "EARLBIRDS";
+
+/// A team whose players overperform in the [lateseason](SeasonPart::Lateseason).
+pub const LATE_TO_PARTY: &str = "LATE_TO_PARTY";
+
+/// A team that has been eliminated from the postseason (see
+/// [`Sim::is_eliminated`](crate::Sim::is_eliminated)). During their games, a player may party,
+/// permanently boosting their attributes. Teams stop partying when a new season starts.
+pub const PARTY_TIME: &str = "PARTY_TIME";
+
+/// A team whose ballpark has a Fax Machine: when their pitcher gives up 10 or more runs in a home
+/// game, the pitcher is swapped mid-game with a random player from the team's shadows, who
+/// takes their place in the rotation.
+pub const FAX_MACHINE: &str = "FAX_MACHINE";
+
+/// Team mods that make players overperform during part of the season, and the parts they apply
+/// in.
+const SEASONAL: &[(&str, &[SeasonPart])] = &[
+    (EARLBIRDS, &[SeasonPart::Earlseason]),
+    (LATE_TO_PARTY, &[SeasonPart::Lateseason]),
+];
+
+/// How much an overperforming player's attributes are multiplied by.
+const OVERPERFORMING: f64 = 1.2;
+
+/// A player's attributes as they play on `date` for `team`, after any of the team's mods that
+/// apply on that date.
+pub(crate) fn effective<'a>(
+    player: &'a Player,
+    team: &Team,
+    date: Date,
+    config: SeasonConfig,
+) -> Cow<'a, Player> {
+    let part = date.season_part(&config);
+    let overperforming = SEASONAL
+        .iter()
+        .any(|(name, parts)| team.has_mod(name) && parts.contains(&part));
+    if overperforming {
+        let mut player = player.clone();
+        player.scale(OVERPERFORMING);
+        Cow::Owned(player)
+    } else {
+        Cow::Borrowed(player)
+    }
+}
@@ -0,0 +1,342 @@
+use crate::id::PlayerId;
+use crate::rating;
+use crate::{
+    mods, AwayHome, Ballpark, Database, DatabaseError, Date, Formulas, Game, Pitch, Player,
+    RngSource, Sim,
+};
+
+// The exponent of the "Pythagorean" expectation, which turns runs scored and allowed into a
+// winning percentage.
+const PYTHAGOREAN_EXPONENT: f64 = 1.83;
+
+// Rough run values of each way a plate appearance can end, relative to an out.
+const WALK_RUNS: f64 = 0.3;
+const HIT_RUNS: f64 = 0.5;
+const HOME_RUN_RUNS: f64 = 1.4;
+
+/// How a plate appearance is expected to end.
+#[derive(Debug, Default, Clone, Copy)]
+struct PlateAppearance {
+    walk: f64,
+    strikeout: f64,
+    in_play: f64,
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Estimate each team's chance of winning a game before its first pitch, like the odds the
+    /// real site showed next to each game on the schedule. The two chances add up to 1.
+    ///
+    /// The estimate comes from the same [formulas](crate::Formulas) that decide each pitch,
+    /// applied to every batter in each team's lineup against the opposing pitcher (the one
+    /// already in the game, or else the next one in the rotation), with everyone's vibes and
+    /// seasonal mods on the day the game is played and the home team's ballpark. Rarer outcomes
+    /// (stolen bases, bunts, foul outs, and anything decided by mods or weather) are left out, so
+    /// the odds are only a guide. The estimate is blended with the teams' [ratings](Sim::rating)
+    /// as much as the [rating config](crate::RatingConfig::odds_weight) says to, which by default
+    /// is not at all.
+    ///
+    /// This is for one of today's games, played on the sim's [current date](Sim::date). For a
+    /// game scheduled for another day, use [`Sim::odds_on`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either team in the game isn't in the database.
+    pub fn odds(&self, game: &Game) -> Result<AwayHome<f64>, DatabaseError> {
+        self.odds_on(game, self.database.date)
+    }
+
+    /// Like [`Sim::odds`], for a game scheduled on `date`: vibes and seasonal mods are as they
+    /// will be that day.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either team in the game isn't in the database.
+    pub fn odds_on(&self, game: &Game, date: Date) -> Result<AwayHome<f64>, DatabaseError> {
+        let database = &self.database;
+        let teams = game.teams.as_ref().try_map(|data| {
+            database
+                .teams
+                .get(&data.id)
+                .ok_or(DatabaseError::BadReference {
+                    kind: "team",
+                    id: data.id.0,
+                })
+        })?;
+        let ballpark = database
+            .ballpark_of(teams.home.id)
+            .cloned()
+            .unwrap_or_default();
+        let effective = |team, player: PlayerId| {
+            database
+                .players
+                .get(&player)
+                .map(|player| mods::effective(player, team, date, database.season_config))
+        };
+        let lineups = teams.map(|team| {
+            team.lineup
+                .iter()
+                .filter_map(|player| effective(team, *player))
+                .collect::<Vec<_>>()
+        });
+        let lineups = lineups
+            .as_ref()
+            .map(|lineup| lineup.iter().map(|player| &**player).collect::<Vec<_>>());
+        let pitchers = game.teams.as_ref().zip(teams).map(|(data, team)| {
+            data.pitcher
+                .or_else(|| team.probable_pitcher(&database.players, 0))
+                .and_then(|player| effective(team, player))
+        });
+
+        let runs = AwayHome {
+            away: expected_runs(
+                database,
+                date,
+                &ballpark,
+                &lineups.away,
+                pitchers.home.as_deref(),
+                &lineups.home,
+                -database.home_advantage,
+            ),
+            home: expected_runs(
+                database,
+                date,
+                &ballpark,
+                &lineups.home,
+                pitchers.away.as_deref(),
+                &lineups.away,
+                database.home_advantage,
+            ),
+        };
+        let strength = runs.map(|runs| runs.powf(PYTHAGOREAN_EXPONENT));
+        let total = strength.away + strength.home;
+        let odds = if total > 0.0 && total.is_finite() {
+            strength.map(|strength| strength / total)
+        } else {
+            AwayHome {
+                away: 0.5,
+                home: 0.5,
+            }
+        };
+
+        // lean toward the teams' ratings as a prior, as far as the rating config says to
+        let weight = database.rating_config.odds_weight.clamp(0.0, 1.0);
+        let ratings = game.teams.as_ref().map(|team| database.rating(team.id));
+        let away = (1.0 - weight) * odds.away
+            + weight * rating::expected_score(ratings.away, ratings.home);
+        Ok(AwayHome {
+            away,
+            home: 1.0 - away,
+        })
+    }
+}
+
+/// The runs a lineup is expected to score in nine innings against a pitcher and fielders.
+/// `home_advantage` is from the lineup's side, as in [`Pitch::home_advantage`].
+fn expected_runs(
+    database: &Database,
+    date: Date,
+    ballpark: &Ballpark,
+    lineup: &[&Player],
+    pitcher: Option<&Player>,
+    fielders: &[&Player],
+    home_advantage: f64,
+) -> f64 {
+    let default = Player::default();
+    let pitcher = pitcher.unwrap_or(&default);
+    let mut fielders = fielders.to_vec();
+    if fielders.is_empty() {
+        fielders.push(&default);
+    }
+
+    let (mut runs, mut outs) = (0.0, 0.0);
+    for batter in lineup {
+        let pitch = Pitch {
+            date,
+            pitcher,
+            batter,
+            ballpark,
+            balls: 0,
+            strikes: 0,
+            outs: 0,
+            baserunners: &[],
+            home_advantage,
+            batted_ball: None,
+            terms: None,
+        };
+        let formulas = &*database.formulas.0;
+        let pa = plate_appearance(formulas, pitch);
+        let out = mean(fielders.iter().map(|f| chance(formulas.out(&pitch, f))));
+        let home_run = chance(formulas.home_run(&pitch));
+        let hit = pa.in_play * (1.0 - out);
+        runs += pa.walk * WALK_RUNS
+            + hit * (1.0 - home_run) * HIT_RUNS
+            + hit * home_run * HOME_RUN_RUNS;
+        outs += pa.strikeout + pa.in_play * out;
+    }
+    if outs > 0.0 {
+        // each batter in the lineup comes up equally often, until 27 outs are made
+        runs * 27.0 / outs
+    } else if runs > 0.0 {
+        f64::INFINITY
+    } else {
+        0.0
+    }
+}
+
+/// The chance of each way a plate appearance ends, starting from an 0-0 count.
+fn plate_appearance(formulas: &dyn Formulas, pitch: Pitch<'_>) -> PlateAppearance {
+    // work backwards from full counts, so each count's outcomes only depend on counts already
+    // worked out
+    let mut counts = [[PlateAppearance::default(); 3]; 4];
+    for balls in (0..4).rev() {
+        for strikes in (0..3).rev() {
+            let pitch = Pitch {
+                balls,
+                strikes,
+                ..pitch
+            };
+            let strike = chance(formulas.strike(&pitch));
+            // chance of a ball, a strike, a foul, and a ball in play
+            let (mut b, mut s, mut f, mut p) = (0.0, 0.0, 0.0, 0.0);
+            for (zone, in_zone) in [(strike, true), (1.0 - strike, false)] {
+                let swing = chance(formulas.swing(&pitch, in_zone));
+                let contact = chance(formulas.contact(&pitch, in_zone));
+                let foul = chance(formulas.foul(&pitch));
+                if in_zone {
+                    s += zone * (1.0 - swing);
+                } else {
+                    b += zone * (1.0 - swing);
+                }
+                s += zone * swing * (1.0 - contact);
+                f += zone * swing * contact * foul;
+                p += zone * swing * contact * (1.0 - foul);
+            }
+
+            let next = |balls: u8, strikes: u8| {
+                if balls == 4 {
+                    PlateAppearance {
+                        walk: 1.0,
+                        ..PlateAppearance::default()
+                    }
+                } else if strikes == 3 {
+                    PlateAppearance {
+                        strikeout: 1.0,
+                        ..PlateAppearance::default()
+                    }
+                } else {
+                    counts[usize::from(balls)][usize::from(strikes)]
+                }
+            };
+            let ball = next(balls + 1, strikes);
+            let strike = next(balls, strikes + 1);
+            // a foul with two strikes leaves the count where it is
+            let repeat = if strikes == 2 {
+                f
+            } else {
+                s += f;
+                0.0
+            };
+            let scale = 1.0 / (1.0 - repeat).max(f64::EPSILON);
+            counts[usize::from(balls)][usize::from(strikes)] = PlateAppearance {
+                walk: (b * ball.walk + s * strike.walk) * scale,
+                strikeout: (b * ball.strikeout + s * strike.strikeout) * scale,
+                in_play: (b * ball.in_play + s * strike.in_play + p) * scale,
+            };
+        }
+    }
+    counts[0][0]
+}
+
+/// A threshold as the chance that a roll succeeds. Rolls never succeed against a NaN threshold.
+fn chance(threshold: f64) -> f64 {
+    if threshold.is_nan() {
+        0.0
+    } else {
+        threshold.clamp(0.0, 1.0)
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0.0), |(sum, count), value| (sum + value, count + 1.0));
+    if count > 0.0 {
+        sum / count
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Date, Formulas, Game, Pitch, Player};
+
+    #[derive(Debug)]
+    struct NoHits;
+
+    impl Formulas for NoHits {
+        fn out(&self, _pitch: &Pitch<'_>, _fielder: &Player) -> f64 {
+            1.0
+        }
+
+        fn swing(&self, _pitch: &Pitch<'_>, _strike: bool) -> f64 {
+            1.0
+        }
+    }
+
+    #[test]
+    fn odds() {
+        let sim = crate::sim::tests::two_games();
+        for game in sim.games_today() {
+            let odds = sim.odds(game).unwrap();
+            assert!((odds.away + odds.home - 1.0).abs() < 1e-9);
+            assert!(odds.away > 0.0 && odds.home > 0.0);
+        }
+
+        // better hitters make for better odds
+        let mut sim = sim;
+        let game = sim.games_today()[0].clone();
+        let before = sim.odds(&game).unwrap();
+        let lineup = sim.teams().get(&game.teams.away.id).unwrap().lineup.clone();
+        for player in lineup {
+            sim.update_player(player, |player| {
+                player.thwackability = 1.0;
+                player.divinity = 1.0;
+                player.patheticism = 0.0;
+            })
+            .unwrap();
+        }
+        let odds = sim.odds(&game).unwrap();
+        assert!(odds.away > before.away);
+
+        // playing at home helps, and playing away hurts
+        sim.set_home_advantage(0.2);
+        assert!(sim.odds(&game).unwrap().home > odds.home);
+        sim.set_home_advantage(-0.2);
+        assert!(sim.odds(&game).unwrap().home < odds.home);
+        sim.set_home_advantage(0.0);
+
+        // if nobody can get on base, it's a coin flip
+        sim.set_formulas(NoHits);
+        let game = &sim.games_today()[0];
+        let odds = sim.odds(game).unwrap();
+        assert!((odds.away - 0.5).abs() < 1e-9);
+
+        let missing = Game::new(game.teams.as_ref().map(|_| crate::id::TeamId::new()));
+        assert!(sim.odds(&missing).is_err());
+    }
+
+    #[test]
+    fn odds_on_scheduled_day() {
+        let sim = crate::sim::tests::two_games();
+        let game = &sim.games_today()[0];
+        let today = sim.odds(game).unwrap();
+        assert_eq!(sim.odds_on(game, sim.date()).unwrap(), today);
+
+        // vibes change from day to day, and so do the odds
+        let later = (1..10)
+            .map(|day| sim.odds_on(game, Date { season: 0, day }).unwrap())
+            .collect::<Vec<_>>();
+        assert!(later
+            .iter()
+            .any(|odds| (odds.away - today.away).abs() > 1e-6));
+    }
+}
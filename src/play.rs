@@ -0,0 +1,378 @@
+use crate::id::PlayerId;
+use crate::sim::OUTS_NEEDED;
+use crate::{BattedBall, Game, Inning, PitchDetail, TeamSelect};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A single play in a game: a pitch, a baserunner stealing, or an [awarded
+/// run](PlayEvent::AwardedRun).
+///
+/// Ticks that don't involve a pitch (announcing the inning, a new batter, the end of the game) are
+/// not plays.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Play {
+    #[serde(flatten)]
+    pub inning: Inning,
+    pub batter: PlayerId,
+    pub pitcher: PlayerId,
+    pub event: PlayEvent,
+    /// The count before the play.
+    pub balls: u8,
+    pub strikes: u8,
+    pub outs_before: u8,
+    pub outs_on_play: u8,
+    /// Baserunners and the base they are on before the play.
+    pub baserunners_before: Vec<(PlayerId, u8)>,
+    /// Baserunners and the base they are on after the play. This is always empty if the play ended
+    /// the half-inning.
+    pub baserunners_after: Vec<(PlayerId, u8)>,
+    /// Runs scored by the batting team on the play.
+    pub runs: u16,
+    /// Runners on base before the play who reached against a different pitcher than this play's,
+    /// like after a [pitching change](crate::Sim::change_pitcher), with the pitcher responsible
+    /// for each of them. Runs they score are charged to that pitcher.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub inherited_runners: BTreeMap<PlayerId, PlayerId>,
+    /// The play's update, as in [`Game::last_update`]. Empty if the sim isn't keeping
+    /// descriptions (see [`Sim::set_play_descriptions`](crate::Sim::set_play_descriptions)).
+    pub description: String,
+    /// How the ball left the bat, for balls in play other than bunts. Foul outs are popups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batted_ball: Option<BattedBall>,
+    /// Play-by-play flavor for the pitch, for every play but steal attempts. This is chosen
+    /// without touching the sim's RNG.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pitch: Option<PitchDetail>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[non_exhaustive]
+pub enum PlayEvent {
+    Ball,
+    Strike {
+        swinging: bool,
+    },
+    Foul,
+    Walk,
+    /// The batter was hit by a pitch and takes first base.
+    HitByPitch,
+    Strikeout {
+        swinging: bool,
+    },
+    Flyout {
+        fielder: PlayerId,
+    },
+    GroundOut {
+        fielder: PlayerId,
+    },
+    LineOut {
+        fielder: PlayerId,
+    },
+    /// A popup caught in fair territory.
+    PopOut {
+        fielder: PlayerId,
+    },
+    /// A foul ball caught for an out.
+    FoulOut {
+        fielder: PlayerId,
+    },
+    HomeRun,
+    /// A single, double, or triple.
+    Hit {
+        bases: u8,
+    },
+    StolenBase {
+        runner: PlayerId,
+        base: u8,
+    },
+    CaughtStealing {
+        runner: PlayerId,
+        base: u8,
+    },
+    /// A bunt fielded for an out, moving each runner up a base.
+    SacrificeBunt {
+        fielder: PlayerId,
+    },
+    /// A bunt popped up and caught. Runners hold.
+    BuntPopout {
+        fielder: PlayerId,
+    },
+    /// A bunt the batter beat out for a single, moving each runner up a base.
+    BuntHit,
+    /// A run given to `team` from outside the sim, with [`Sim::award_run`](crate::Sim::award_run)
+    /// or [`Injection::AwardRun`](crate::Injection::AwardRun). Nobody bats or pitches on it, so
+    /// the play's batter and pitcher are nil, and its run counts for `team` whether or not
+    /// they're batting.
+    AwardedRun {
+        team: TeamSelect,
+    },
+}
+
+impl PlayEvent {
+    /// Whether this event is a pitch, rather than a baserunner stealing or an awarded run.
+    pub fn is_pitch(&self) -> bool {
+        !matches!(
+            self,
+            PlayEvent::StolenBase { .. }
+                | PlayEvent::CaughtStealing { .. }
+                | PlayEvent::AwardedRun { .. }
+        )
+    }
+
+    /// Whether this event ends the batter's plate appearance.
+    pub fn ends_plate_appearance(&self) -> bool {
+        matches!(
+            self,
+            PlayEvent::Walk
+                | PlayEvent::HitByPitch
+                | PlayEvent::Strikeout { .. }
+                | PlayEvent::Flyout { .. }
+                | PlayEvent::GroundOut { .. }
+                | PlayEvent::LineOut { .. }
+                | PlayEvent::PopOut { .. }
+                | PlayEvent::FoulOut { .. }
+                | PlayEvent::HomeRun
+                | PlayEvent::Hit { .. }
+                | PlayEvent::SacrificeBunt { .. }
+                | PlayEvent::BuntPopout { .. }
+                | PlayEvent::BuntHit
+        )
+    }
+}
+
+impl Play {
+    /// The team the play's runs count for: the batting team, unless the run was
+    /// [awarded](PlayEvent::AwardedRun) to the other one.
+    pub fn scoring_team(&self) -> TeamSelect {
+        match self.event {
+            PlayEvent::AwardedRun { team } => team,
+            _ => self.inning.batting(),
+        }
+    }
+
+    /// Where each runner on the play went, working from [`Play::baserunners_before`] and
+    /// [`Play::baserunners_after`]: every runner who started the play on base, in the order they
+    /// were listed, then the batter if the play ended their plate appearance. Runners stranded by
+    /// the play ending the half-inning stay where they were.
+    pub fn runner_moves(&self) -> Vec<RunnerMove> {
+        let inning_over = self.outs_before + self.outs_on_play >= OUTS_NEEDED;
+        let caught = match self.event {
+            PlayEvent::CaughtStealing { runner, .. } => Some(runner),
+            _ => None,
+        };
+        let after = |runner: PlayerId| {
+            self.baserunners_after
+                .iter()
+                .find(|(r, _)| *r == runner)
+                .map(|(_, base)| RunnerResult::Base(*base))
+        };
+        let mut moves = Vec::new();
+        for (runner, from) in &self.baserunners_before {
+            let to = if Some(*runner) == caught {
+                RunnerResult::Out
+            } else if let Some(to) = after(*runner) {
+                to
+            } else if inning_over {
+                RunnerResult::Base(*from)
+            } else {
+                RunnerResult::Scored
+            };
+            moves.push(RunnerMove {
+                runner: *runner,
+                from: *from,
+                to,
+            });
+        }
+        if self.event.ends_plate_appearance() {
+            let to = match (after(self.batter), &self.event) {
+                (Some(to), _) => to,
+                (None, PlayEvent::HomeRun) => RunnerResult::Scored,
+                (None, _) => RunnerResult::Out,
+            };
+            moves.push(RunnerMove {
+                runner: self.batter,
+                from: 0,
+                to,
+            });
+        }
+        moves
+    }
+}
+
+/// Where a runner went on a play. See [`Play::runner_moves`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct RunnerMove {
+    pub runner: PlayerId,
+    /// The base the runner started the play on, or 0 for the batter.
+    pub from: u8,
+    pub to: RunnerResult,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RunnerResult {
+    /// The runner ended the play on this base.
+    Base(u8),
+    Scored,
+    Out,
+}
+
+/// The parts of a game's state that a play changes, captured before the play.
+pub(crate) struct Before {
+    inning: Inning,
+    balls: u8,
+    strikes: u8,
+    outs: u8,
+    baserunners: Vec<(PlayerId, u8)>,
+    responsible: BTreeMap<PlayerId, PlayerId>,
+    runs: u16,
+}
+
+impl Before {
+    pub(crate) fn new(game: &Game) -> Before {
+        Before {
+            inning: game.inning,
+            balls: game.balls,
+            strikes: game.strikes,
+            outs: game.outs,
+            baserunners: game.runner_positions(),
+            responsible: game
+                .baserunners
+                .iter()
+                .filter_map(|runner| Some((runner.player, runner.pitcher?)))
+                .collect(),
+            runs: game.teams.select(game.inning.batting()).runs,
+        }
+    }
+
+    pub(crate) fn into_play(
+        self,
+        game: &Game,
+        batter: PlayerId,
+        pitcher: PlayerId,
+        event: PlayEvent,
+        description: String,
+    ) -> Play {
+        let (outs_on_play, baserunners_after) = if game.inning == self.inning {
+            (game.outs - self.outs, game.runner_positions())
+        } else {
+            (OUTS_NEEDED - self.outs, Vec::new())
+        };
+        Play {
+            inning: self.inning,
+            batter,
+            pitcher,
+            event,
+            balls: self.balls,
+            strikes: self.strikes,
+            outs_before: self.outs,
+            outs_on_play,
+            baserunners_before: self.baserunners,
+            baserunners_after,
+            runs: game.teams.select(self.inning.batting()).runs - self.runs,
+            inherited_runners: self
+                .responsible
+                .into_iter()
+                .filter(|(_, responsible)| *responsible != pitcher)
+                .collect(),
+            description,
+            batted_ball: None,
+            pitch: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Play, PlayEvent, RunnerMove, RunnerResult};
+    use crate::id::PlayerId;
+    use crate::Inning;
+    use std::collections::BTreeMap;
+
+    fn play(event: PlayEvent, outs_on_play: u8, before: &[(PlayerId, u8)]) -> Play {
+        Play {
+            inning: Inning::Top(1),
+            batter: PlayerId::new(),
+            pitcher: PlayerId::new(),
+            event,
+            balls: 0,
+            strikes: 0,
+            outs_before: 1,
+            outs_on_play,
+            baserunners_before: before.to_vec(),
+            baserunners_after: Vec::new(),
+            runs: 0,
+            inherited_runners: BTreeMap::new(),
+            description: String::new(),
+            batted_ball: None,
+            pitch: None,
+        }
+    }
+
+    fn moved(runner: PlayerId, from: u8, to: RunnerResult) -> RunnerMove {
+        RunnerMove { runner, from, to }
+    }
+
+    #[test]
+    fn caught_stealing() {
+        let (first, third) = (PlayerId::new(), PlayerId::new());
+        let mut play = play(
+            PlayEvent::CaughtStealing {
+                runner: first,
+                base: 2,
+            },
+            1,
+            &[(third, 3), (first, 1)],
+        );
+        play.baserunners_after = vec![(third, 3)];
+        // the batter is still up
+        assert_eq!(
+            play.runner_moves(),
+            [
+                moved(third, 3, RunnerResult::Base(3)),
+                moved(first, 1, RunnerResult::Out),
+            ]
+        );
+    }
+
+    #[test]
+    fn stranded() {
+        let (second, third) = (PlayerId::new(), PlayerId::new());
+        let play = play(
+            PlayEvent::GroundOut {
+                fielder: PlayerId::new(),
+            },
+            2,
+            &[(third, 3), (second, 2)],
+        );
+        assert_eq!(
+            play.runner_moves(),
+            [
+                moved(third, 3, RunnerResult::Base(3)),
+                moved(second, 2, RunnerResult::Base(2)),
+                moved(play.batter, 0, RunnerResult::Out),
+            ]
+        );
+    }
+
+    #[test]
+    fn home_run() {
+        let first = PlayerId::new();
+        let mut play = play(PlayEvent::HomeRun, 0, &[(first, 1)]);
+        play.runs = 2;
+        assert_eq!(
+            play.runner_moves(),
+            [
+                moved(first, 1, RunnerResult::Scored),
+                moved(play.batter, 0, RunnerResult::Scored),
+            ]
+        );
+    }
+}
@@ -1,54 +1,184 @@
 use crate::database::CheckEntity;
 use crate::id::PlayerId;
-use crate::{Database, DatabaseError, Date, Rng};
+use crate::{Database, DatabaseError, Date, RngSource};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+/// One of a player's attributes, for editing with
+/// [`Sim::set_attribute`](crate::Sim::set_attribute).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Attribute {
+    Thwackability,
+    Moxie,
+    Divinity,
+    Musclitude,
+    Patheticism,
+    Buoyancy,
+    BaseThirst,
+    Laserlikeness,
+    GroundFriction,
+    Continuation,
+    Indulgence,
+    Martyrdom,
+    Tragicness,
+    Shakespearianism,
+    Suppression,
+    Unthwackability,
+    Coldness,
+    Overpowerment,
+    Ruthlessness,
+    Omniscience,
+    Tenaciousness,
+    Watchfulness,
+    Anticapitalism,
+    Chasiness,
+    Pressurization,
+    Cinnamon,
+}
+
+impl Attribute {
+    pub const ALL: [Attribute; 26] = [
+        Attribute::Thwackability,
+        Attribute::Moxie,
+        Attribute::Divinity,
+        Attribute::Musclitude,
+        Attribute::Patheticism,
+        Attribute::Buoyancy,
+        Attribute::BaseThirst,
+        Attribute::Laserlikeness,
+        Attribute::GroundFriction,
+        Attribute::Continuation,
+        Attribute::Indulgence,
+        Attribute::Martyrdom,
+        Attribute::Tragicness,
+        Attribute::Shakespearianism,
+        Attribute::Suppression,
+        Attribute::Unthwackability,
+        Attribute::Coldness,
+        Attribute::Overpowerment,
+        Attribute::Ruthlessness,
+        Attribute::Omniscience,
+        Attribute::Tenaciousness,
+        Attribute::Watchfulness,
+        Attribute::Anticapitalism,
+        Attribute::Chasiness,
+        Attribute::Pressurization,
+        Attribute::Cinnamon,
+    ];
+}
+
+/// A player.
+///
+/// Deserializing is lenient, so that player JSON from other eras and tools loads: every missing
+/// field but `id` defaults to zero or empty, and fields simx doesn't know about are kept in
+/// [`Player::extra`] and written back out when serializing.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[non_exhaustive]
 pub struct Player {
     pub id: PlayerId,
+    #[serde(default)]
     pub name: String,
 
+    #[serde(default)]
     pub thwackability: f64,
+    #[serde(default)]
     pub moxie: f64,
+    #[serde(default)]
     pub divinity: f64,
+    #[serde(default)]
     pub musclitude: f64,
+    #[serde(default)]
     pub patheticism: f64,
+    #[serde(default)]
     pub buoyancy: f64,
-    #[serde(alias = "baseThirst")]
+    #[serde(default, alias = "baseThirst")]
     pub base_thirst: f64,
+    #[serde(default)]
     pub laserlikeness: f64,
-    #[serde(alias = "groundFriction")]
+    #[serde(default, alias = "groundFriction")]
     pub ground_friction: f64,
+    #[serde(default)]
     pub continuation: f64,
+    #[serde(default)]
     pub indulgence: f64,
+    #[serde(default)]
     pub martyrdom: f64,
+    #[serde(default)]
     pub tragicness: f64,
+    #[serde(default)]
     pub shakespearianism: f64,
+    #[serde(default)]
     pub suppression: f64,
+    #[serde(default)]
     pub unthwackability: f64,
+    #[serde(default)]
     pub coldness: f64,
+    #[serde(default)]
     pub overpowerment: f64,
+    #[serde(default)]
     pub ruthlessness: f64,
+    #[serde(default)]
     pub omniscience: f64,
+    #[serde(default)]
     pub tenaciousness: f64,
+    #[serde(default)]
     pub watchfulness: f64,
+    #[serde(default)]
     pub anticapitalism: f64,
+    #[serde(default)]
     pub chasiness: f64,
+    #[serde(default)]
     pub pressurization: f64,
+    #[serde(default)]
     pub cinnamon: f64,
 
+    #[serde(default)]
     pub soul: u16,
-    #[serde(alias = "peanutAllergy")]
+    #[serde(default, alias = "peanutAllergy")]
     pub peanut_allergy: bool,
+    #[serde(default)]
     pub fate: u8,
+    #[serde(default)]
     pub blood: u8,
+    #[serde(default)]
     pub coffee: u8,
+
+    #[serde(default)]
+    pub mods: Vec<String>,
+    /// The pitcher who gave this player their [Unstable](crate::mods::UNSTABLE) or
+    /// [Observed](crate::mods::OBSERVED) mark by hitting them with a pitch. Following this from
+    /// player to player traces a chain of debts; see [`Sim::mark_chain`](crate::Sim::mark_chain).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marked_by: Option<PlayerId>,
+
+    /// Fields simx doesn't model, like `hittingRating` or `items`.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, UnknownValue>,
+}
+
+/// The value of a player field simx doesn't model, kept as it was read so that it's written back
+/// out unchanged. See [`Player::extra`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum UnknownValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Unsigned(u64),
+    Float(f64),
+    String(String),
+    List(Vec<UnknownValue>),
+    Map(BTreeMap<String, UnknownValue>),
 }
 
 impl Player {
     // TODO: figure out the interface around name/ritual pools and make `pub`
-    pub(crate) fn generate_with_name(rng: &mut Rng, name: String) -> Player {
+    pub(crate) fn generate_with_name(rng: &mut impl RngSource, name: String) -> Player {
         Player {
             id: PlayerId::new(),
             name,
@@ -79,14 +209,137 @@ impl Player {
             pressurization: rng.next_f64(),
             cinnamon: rng.next_f64(),
             soul: rng.choose(2..10).unwrap_or_default(),
-            peanut_allergy: rng.choose([true, false]).unwrap_or_default(),
+            peanut_allergy: rng.next_bool(0.5),
             // ritual gets rolled after fate but we don't pick that yet, so throw away a roll.
             fate: (rng.choose(0..100).unwrap_or_default(), rng.next_f64()).0,
             blood: rng.choose(0..13).unwrap_or_default(),
             coffee: rng.choose(0..13).unwrap_or_default(),
+            mods: Vec::new(),
+            marked_by: None,
+            extra: BTreeMap::new(),
+        }
+    }
+
+    /// Increase each of this player's attributes by `amount`.
+    ///
+    /// Patheticism and tragicness are inverted (lower is better), so they are decreased instead.
+    /// Attributes are not clamped.
+    pub fn boost(&mut self, amount: f64) {
+        for attr in self.attributes_mut() {
+            *attr += amount;
+        }
+        self.patheticism -= amount;
+        self.tragicness -= amount;
+    }
+
+    /// Multiply each of this player's attributes by `factor`, dividing patheticism and
+    /// tragicness instead.
+    pub(crate) fn scale(&mut self, factor: f64) {
+        for attr in self.attributes_mut() {
+            *attr *= factor;
+        }
+        self.patheticism /= factor;
+        self.tragicness /= factor;
+    }
+
+    /// Every attribute where higher is better.
+    fn attributes_mut(&mut self) -> [&mut f64; 24] {
+        [
+            &mut self.thwackability,
+            &mut self.moxie,
+            &mut self.divinity,
+            &mut self.musclitude,
+            &mut self.buoyancy,
+            &mut self.base_thirst,
+            &mut self.laserlikeness,
+            &mut self.ground_friction,
+            &mut self.continuation,
+            &mut self.indulgence,
+            &mut self.martyrdom,
+            &mut self.shakespearianism,
+            &mut self.suppression,
+            &mut self.unthwackability,
+            &mut self.coldness,
+            &mut self.overpowerment,
+            &mut self.ruthlessness,
+            &mut self.omniscience,
+            &mut self.tenaciousness,
+            &mut self.watchfulness,
+            &mut self.anticapitalism,
+            &mut self.chasiness,
+            &mut self.pressurization,
+            &mut self.cinnamon,
+        ]
+    }
+
+    /// The value of one of this player's attributes.
+    pub fn attribute(&self, attribute: Attribute) -> f64 {
+        match attribute {
+            Attribute::Thwackability => self.thwackability,
+            Attribute::Moxie => self.moxie,
+            Attribute::Divinity => self.divinity,
+            Attribute::Musclitude => self.musclitude,
+            Attribute::Patheticism => self.patheticism,
+            Attribute::Buoyancy => self.buoyancy,
+            Attribute::BaseThirst => self.base_thirst,
+            Attribute::Laserlikeness => self.laserlikeness,
+            Attribute::GroundFriction => self.ground_friction,
+            Attribute::Continuation => self.continuation,
+            Attribute::Indulgence => self.indulgence,
+            Attribute::Martyrdom => self.martyrdom,
+            Attribute::Tragicness => self.tragicness,
+            Attribute::Shakespearianism => self.shakespearianism,
+            Attribute::Suppression => self.suppression,
+            Attribute::Unthwackability => self.unthwackability,
+            Attribute::Coldness => self.coldness,
+            Attribute::Overpowerment => self.overpowerment,
+            Attribute::Ruthlessness => self.ruthlessness,
+            Attribute::Omniscience => self.omniscience,
+            Attribute::Tenaciousness => self.tenaciousness,
+            Attribute::Watchfulness => self.watchfulness,
+            Attribute::Anticapitalism => self.anticapitalism,
+            Attribute::Chasiness => self.chasiness,
+            Attribute::Pressurization => self.pressurization,
+            Attribute::Cinnamon => self.cinnamon,
         }
     }
 
+    pub(crate) fn attribute_mut(&mut self, attribute: Attribute) -> &mut f64 {
+        match attribute {
+            Attribute::Thwackability => &mut self.thwackability,
+            Attribute::Moxie => &mut self.moxie,
+            Attribute::Divinity => &mut self.divinity,
+            Attribute::Musclitude => &mut self.musclitude,
+            Attribute::Patheticism => &mut self.patheticism,
+            Attribute::Buoyancy => &mut self.buoyancy,
+            Attribute::BaseThirst => &mut self.base_thirst,
+            Attribute::Laserlikeness => &mut self.laserlikeness,
+            Attribute::GroundFriction => &mut self.ground_friction,
+            Attribute::Continuation => &mut self.continuation,
+            Attribute::Indulgence => &mut self.indulgence,
+            Attribute::Martyrdom => &mut self.martyrdom,
+            Attribute::Tragicness => &mut self.tragicness,
+            Attribute::Shakespearianism => &mut self.shakespearianism,
+            Attribute::Suppression => &mut self.suppression,
+            Attribute::Unthwackability => &mut self.unthwackability,
+            Attribute::Coldness => &mut self.coldness,
+            Attribute::Overpowerment => &mut self.overpowerment,
+            Attribute::Ruthlessness => &mut self.ruthlessness,
+            Attribute::Omniscience => &mut self.omniscience,
+            Attribute::Tenaciousness => &mut self.tenaciousness,
+            Attribute::Watchfulness => &mut self.watchfulness,
+            Attribute::Anticapitalism => &mut self.anticapitalism,
+            Attribute::Chasiness => &mut self.chasiness,
+            Attribute::Pressurization => &mut self.pressurization,
+            Attribute::Cinnamon => &mut self.cinnamon,
+        }
+    }
+
+    /// Whether the player has a mod (see [`mods`](crate::mods)).
+    pub fn has_mod(&self, name: &str) -> bool {
+        self.mods.iter().any(|m| m == name)
+    }
+
     pub fn vibes(&self, date: Date) -> f64 {
         let frequency = 6.0 + (10.0 * self.buoyancy).round();
         (std::f64::consts::PI * ((2.0 / frequency) * f64::from(date.day) + 0.5)).sin()
@@ -102,3 +355,37 @@ impl CheckEntity for Player {
         problems
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Player, UnknownValue};
+    use serde_json::json;
+
+    #[test]
+    fn unknown_fields() {
+        let json = json!({
+            "id": "b7adbbcc-0679-43f3-a939-07f009a393db",
+            "name": "Nagomi Mcdaniel",
+            "baseThirst": 0.5,
+            "hittingRating": 0.25,
+            "items": [{"name": "Bat", "durability": 3}],
+            "permAttr": null,
+        });
+        let player: Player = serde_json::from_value(json).unwrap();
+        assert_eq!(player.name, "Nagomi Mcdaniel");
+        assert!((player.base_thirst - 0.5).abs() < f64::EPSILON);
+        assert_eq!(player.extra["hittingRating"], UnknownValue::Float(0.25));
+        assert_eq!(player.extra["permAttr"], UnknownValue::Null);
+
+        let value = serde_json::to_value(&player).unwrap();
+        assert_eq!(value["items"], json!([{"name": "Bat", "durability": 3}]));
+        assert_eq!(value["permAttr"], json!(null));
+        assert_eq!(serde_json::from_value::<Player>(value).unwrap(), player);
+    }
+
+    #[test]
+    fn id_required() {
+        let json = json!({"name": "Nagomi Mcdaniel", "moxie": 0.5});
+        assert!(serde_json::from_value::<Player>(json).is_err());
+    }
+}
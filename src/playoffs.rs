@@ -0,0 +1,388 @@
+use crate::id::TeamId;
+use crate::{mods, AwayHome, Date, Game, Mutation, Phase, Rng, RngSource, Sim};
+use std::collections::{BTreeMap, HashMap};
+
+/// Options for [`Sim::playoff_odds`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PlayoffOddsConfig {
+    /// How many times to simulate the rest of the season.
+    pub trials: u32,
+    /// How many games a team needs to win to take a postseason series.
+    pub series_wins: u32,
+    /// Seeds the simulation. The same sim with the same options always gives the same odds.
+    pub seed: u64,
+}
+
+impl Default for PlayoffOddsConfig {
+    /// 1,000 trials and best-of-five series.
+    fn default() -> PlayoffOddsConfig {
+        PlayoffOddsConfig {
+            trials: 1000,
+            series_wins: 3,
+            seed: 0,
+        }
+    }
+}
+
+/// A team's chances from [`Sim::playoff_odds`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[non_exhaustive]
+pub struct PlayoffOdds {
+    /// The chance of making the postseason.
+    pub postseason: f64,
+    /// The chance of winning the postseason.
+    pub championship: f64,
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Simulate the rest of the current season many times, and report how often each team made
+    /// the postseason and won it all.
+    ///
+    /// Each trial starts from the current season's standings. The rest of the regular season
+    /// (any of today's games that haven't finished, then each remaining day of
+    /// [`Sim::round_robin`]) is decided game by game using [`Sim::odds`]. The best teams by
+    /// record, with ties broken by fewest losses and then by team ID, then play a bracket: the
+    /// best remaining seed plays the worst, at home, and with an odd number of teams left the
+    /// best seed sits out a round. Each [league](crate::League) has its own postseason, with
+    /// [`SeasonConfig::postseason_teams`](crate::SeasonConfig::postseason_teams) teams, and
+    /// champion.
+    ///
+    /// The sim itself isn't changed, and its RNG isn't used: the trials are seeded from
+    /// [`PlayoffOddsConfig::seed`].
+    pub fn playoff_odds(&self, config: &PlayoffOddsConfig) -> BTreeMap<TeamId, PlayoffOdds> {
+        let mut chances = HashMap::new();
+        let mut home_chance = |away: TeamId, home: TeamId| {
+            *chances.entry((away, home)).or_insert_with(|| {
+                self.odds(&Game::new(AwayHome { away, home }))
+                    .map_or(0.5, |odds| odds.home)
+            })
+        };
+
+        let mut records = self
+            .teams()
+            .keys()
+            .map(|team| (*team, (0, 0)))
+            .collect::<BTreeMap<TeamId, (u32, u32)>>();
+        if let Some(stats) = self.stats(self.date().season) {
+            for (team, record) in &stats.teams {
+                if let Some(entry) = records.get_mut(team) {
+                    *entry = (record.wins, record.losses);
+                }
+            }
+        }
+        let schedule = self.rest_of_regular_season();
+        let leagues = self.database.teams_by_league();
+        let spots = usize::from(self.season_config().postseason_teams);
+
+        let mut odds = records
+            .keys()
+            .map(|team| (*team, PlayoffOdds::default()))
+            .collect::<BTreeMap<_, _>>();
+        let mut rng = Rng::seeded_from_str(&format!("playoff-odds-{}", config.seed));
+        for _ in 0..config.trials {
+            let mut records = records.clone();
+            for teams in &schedule {
+                let (winner, loser) = if rng.next_bool(home_chance(teams.away, teams.home)) {
+                    (teams.home, teams.away)
+                } else {
+                    (teams.away, teams.home)
+                };
+                if let Some(record) = records.get_mut(&winner) {
+                    record.0 += 1;
+                }
+                if let Some(record) = records.get_mut(&loser) {
+                    record.1 += 1;
+                }
+            }
+
+            for teams in leagues.values() {
+                let seeds = seed(teams, &records, spots);
+                for team in &seeds {
+                    odds.entry(*team).or_default().postseason += 1.0;
+                }
+                let champion = bracket(seeds, config.series_wins, &mut rng, &mut home_chance);
+                if let Some(champion) = champion {
+                    odds.entry(champion).or_default().championship += 1.0;
+                }
+            }
+        }
+
+        if config.trials > 0 {
+            let trials = f64::from(config.trials);
+            for odds in odds.values_mut() {
+                odds.postseason /= trials;
+                odds.championship /= trials;
+            }
+        }
+        odds
+    }
+
+    /// Whether a team can no longer make the postseason, even by winning every game left in the
+    /// current regular season: at least
+    /// [`SeasonConfig::postseason_teams`](crate::SeasonConfig::postseason_teams) other teams in
+    /// its [league](crate::League) already have more wins than it could finish with. Ties are
+    /// assumed to go the team's way, so a team isn't eliminated until it truly can't catch up.
+    ///
+    /// The rest of the regular season is assumed to follow [`Sim::round_robin`], as in
+    /// [`Sim::playoff_odds`].
+    pub fn is_eliminated(&self, team: TeamId) -> bool {
+        self.eliminated(&self.rest_of_regular_season())
+            .contains(&team)
+    }
+
+    /// Every eliminated team (see [`Sim::is_eliminated`]), given what's left of the schedule.
+    fn eliminated(&self, schedule: &[AwayHome<TeamId>]) -> Vec<TeamId> {
+        let stats = self.stats(self.date().season);
+        let wins = |team: &TeamId| {
+            stats
+                .and_then(|stats| stats.teams.get(team))
+                .map_or(0, |record| record.wins)
+        };
+        let spots = usize::from(self.season_config().postseason_teams);
+        self.teams()
+            .keys()
+            .filter(|team| {
+                let remaining = schedule
+                    .iter()
+                    .filter(|teams| teams.away == **team || teams.home == **team)
+                    .count();
+                let best = u64::from(wins(team)) + remaining as u64;
+                let ahead = self
+                    .teams()
+                    .keys()
+                    .filter(|other| {
+                        self.database.same_league(**team, **other) && u64::from(wins(other)) > best
+                    })
+                    .count();
+                ahead >= spots
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Flag newly eliminated teams with [Party Time](mods::PARTY_TIME), and clear the flag from
+    /// every team when a new season starts.
+    pub(crate) fn update_party_time(&mut self, old_date: Date) {
+        if self.date().season != old_date.season {
+            let partying = self
+                .teams()
+                .values()
+                .filter(|team| team.has_mod(mods::PARTY_TIME))
+                .map(|team| team.id)
+                .collect::<Vec<_>>();
+            for team in partying {
+                team.load_mut(&mut self.database)
+                    .mods
+                    .retain(|name| name != mods::PARTY_TIME);
+                self.database.record(Mutation::ModRemoved {
+                    team,
+                    name: mods::PARTY_TIME.into(),
+                });
+            }
+        }
+        if self.date().phase(self.season_config()) != Phase::RegularSeason {
+            return;
+        }
+        for team in self.eliminated(&self.rest_of_regular_season()) {
+            let data = team.load_mut(&mut self.database);
+            if !data.has_mod(mods::PARTY_TIME) {
+                data.mods.push(mods::PARTY_TIME.into());
+                self.database.record(Mutation::ModAdded {
+                    team,
+                    name: mods::PARTY_TIME.into(),
+                });
+            }
+        }
+    }
+
+    /// The matchups left in the current regular season, in the order they'll be played.
+    fn rest_of_regular_season(&self) -> Vec<AwayHome<TeamId>> {
+        let config = self.season_config();
+        let mut schedule = self
+            .games_today()
+            .iter()
+            .filter(|game| !game.is_finished())
+            .map(|game| game.teams.as_ref().map(|team| team.id))
+            .collect::<Vec<_>>();
+        let mut date = self.date();
+        if !self.games_today().is_empty() {
+            date = date.next_day(config);
+        }
+        while date.season == self.date().season && date.phase(config) == Phase::RegularSeason {
+            schedule.extend(
+                self.round_robin(date.day)
+                    .into_iter()
+                    .map(|game| game.teams.map(|team| team.id)),
+            );
+            date = date.next_day(config);
+        }
+        schedule
+    }
+}
+
+/// The best `n` of `teams` by record (wins, then losses), with ties broken by team ID.
+fn seed(teams: &[TeamId], records: &BTreeMap<TeamId, (u32, u32)>, n: usize) -> Vec<TeamId> {
+    let mut seeds = teams
+        .iter()
+        .filter_map(|team| Some((*team, *records.get(team)?)))
+        .collect::<Vec<_>>();
+    seeds.sort_by(|a, b| {
+        (b.1 .0.cmp(&a.1 .0))
+            .then(a.1 .1.cmp(&b.1 .1))
+            .then(a.0.cmp(&b.0))
+    });
+    seeds.into_iter().map(|(team, _)| team).take(n).collect()
+}
+
+/// Play out a postseason bracket between `seeds`, best first, and return the champion. Series
+/// go to the first team to `series_wins` wins, and `home_chance` gives the home team's chance of
+/// winning each game.
+fn bracket(
+    mut seeds: Vec<TeamId>,
+    series_wins: u32,
+    rng: &mut impl RngSource,
+    home_chance: &mut impl FnMut(TeamId, TeamId) -> f64,
+) -> Option<TeamId> {
+    while seeds.len() > 1 {
+        let bye = seeds.len() % 2;
+        let playing = &seeds[bye..];
+        let winners = (0..playing.len() / 2)
+            .map(|i| {
+                let (home, away) = (playing[i], playing[playing.len() - 1 - i]);
+                let chance = home_chance(away, home);
+                let (mut home_wins, mut away_wins) = (0, 0);
+                while home_wins < series_wins.max(1) && away_wins < series_wins.max(1) {
+                    if rng.next_bool(chance) {
+                        home_wins += 1;
+                    } else {
+                        away_wins += 1;
+                    }
+                }
+                if home_wins > away_wins {
+                    home
+                } else {
+                    away
+                }
+            })
+            .collect::<Vec<_>>();
+        seeds.truncate(bye);
+        seeds.extend(winners);
+    }
+    seeds.first().copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PlayoffOddsConfig;
+    use crate::{Date, Formulas, Game, Mutation, SeasonConfig, SeasonStats, Team, TeamRecord};
+
+    #[derive(Debug)]
+    struct AlwaysParty;
+
+    impl Formulas for AlwaysParty {
+        fn party(&self, _team: &Team) -> f64 {
+            1.0
+        }
+    }
+
+    #[test]
+    fn playoff_odds() {
+        let mut sim = crate::sim::tests::two_games();
+        let mut season_config = SeasonConfig::new(6, 2);
+        season_config.postseason_teams = 2;
+        sim.set_season_config(season_config);
+        let config = PlayoffOddsConfig {
+            trials: 200,
+            seed: 7,
+            ..PlayoffOddsConfig::default()
+        };
+        let odds = sim.playoff_odds(&config);
+        assert_eq!(odds.len(), 4);
+        let postseason = odds.values().map(|odds| odds.postseason).sum::<f64>();
+        let championship = odds.values().map(|odds| odds.championship).sum::<f64>();
+        assert!((postseason - 2.0).abs() < 1e-9);
+        assert!((championship - 1.0).abs() < 1e-9);
+        assert!(odds
+            .values()
+            .all(|odds| odds.championship <= odds.postseason));
+
+        // the same seed gives the same odds, without touching the sim
+        let before = sim.clone();
+        assert_eq!(sim.playoff_odds(&config), odds);
+        assert_eq!(sim, before);
+        let other = PlayoffOddsConfig { seed: 8, ..config };
+        assert_ne!(sim.playoff_odds(&other), odds);
+    }
+
+    #[test]
+    fn party_time() {
+        let mut sim = crate::sim::tests::two_games();
+        let mut config = SeasonConfig::new(3, 2);
+        config.postseason_teams = 1;
+        sim.set_season_config(config);
+        let teams = sim.teams().keys().copied().collect::<Vec<_>>();
+        let leader = teams[0];
+        assert!(!teams.iter().any(|team| sim.is_eliminated(*team)));
+
+        // three games left each, so nobody can catch five wins
+        let mut stats = SeasonStats {
+            season: 0,
+            ..SeasonStats::default()
+        };
+        stats.teams.insert(
+            leader,
+            TeamRecord {
+                wins: 5,
+                ..TeamRecord::default()
+            },
+        );
+        sim.database.stats.push(stats);
+        assert!(!sim.is_eliminated(leader));
+        assert!(teams[1..].iter().all(|team| sim.is_eliminated(*team)));
+        // the odds agree with who's eliminated
+        let odds = sim.playoff_odds(&PlayoffOddsConfig::default());
+        assert!((odds[&leader].postseason - 1.0).abs() < 1e-9);
+        assert!(teams[1..].iter().all(|team| odds[team].postseason == 0.0));
+
+        let fresh = |sim: &crate::Sim| {
+            sim.games_today()
+                .iter()
+                .map(|game| Game::new(game.teams.as_ref().map(|team| team.id)))
+                .collect::<Vec<_>>()
+        };
+        sim.take_audit_log();
+        let games = fresh(&sim);
+        sim.start_day(Date { season: 0, day: 1 }, games).unwrap();
+        for team in &teams {
+            let partying = sim
+                .teams()
+                .get(team)
+                .unwrap()
+                .has_mod(crate::mods::PARTY_TIME);
+            assert_eq!(partying, *team != leader);
+        }
+        assert_eq!(
+            sim.audit_log()
+                .iter()
+                .filter(|entry| matches!(entry.mutation, Mutation::ModAdded { .. }))
+                .count(),
+            3
+        );
+
+        sim.set_formulas(AlwaysParty);
+        sim.finish_day();
+        assert!(sim
+            .games_today()
+            .iter()
+            .flat_map(|game| &game.plays)
+            .any(|play| play.description.ends_with("is Partying!")));
+
+        let games = fresh(&sim);
+        sim.start_day(Date { season: 1, day: 0 }, games).unwrap();
+        assert!(sim
+            .teams()
+            .values()
+            .all(|team| !team.has_mod(crate::mods::PARTY_TIME)));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::id::TeamId;
+use crate::{AwayHome, Database, Game, RngSource, Sim, TeamRecord};
+use serde::{Deserialize, Serialize};
+
+/// The rating every team starts at, and the average rating ratings regress toward.
+pub const INITIAL_RATING: f64 = 1500.0;
+
+/// How team ratings move. Each team has an Elo-style rating that goes up when it wins and down
+/// when it loses, by more when the result was less expected. Set with
+/// [`Sim::set_rating_config`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+#[non_exhaustive]
+pub struct RatingConfig {
+    /// The most a team's rating can move in one game.
+    pub k_factor: f64,
+    /// How much of the way back to [`INITIAL_RATING`] each team's rating goes when a new season
+    /// starts, from 0 (not at all) to 1 (all the way).
+    pub season_regression: f64,
+    /// How much [`Sim::odds`] goes by the teams' ratings, from 0 (not at all) to 1 (only by
+    /// the ratings) instead of by the formulas.
+    pub odds_weight: f64,
+}
+
+impl Default for RatingConfig {
+    /// Ratings move by up to 20 points a game and regress a third of the way each season, and
+    /// don't affect the odds.
+    fn default() -> RatingConfig {
+        RatingConfig {
+            k_factor: 20.0,
+            season_regression: 1.0 / 3.0,
+            odds_weight: 0.0,
+        }
+    }
+}
+
+/// A team's place in the standings. See [`Sim::standings`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Standing<'a> {
+    pub team: TeamId,
+    pub record: &'a TeamRecord,
+    /// The team's current [rating](Sim::rating).
+    pub rating: f64,
+}
+
+/// The chance a team rated `rating` beats a team rated `opponent`.
+pub(crate) fn expected_score(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10_f64.powf((opponent - rating) / 400.0))
+}
+
+impl Database {
+    pub(crate) fn rating(&self, team: TeamId) -> f64 {
+        self.ratings.get(&team).copied().unwrap_or(INITIAL_RATING)
+    }
+
+    /// Move both teams' ratings after a game with a winner or a tie.
+    pub(crate) fn update_ratings(&mut self, game: &Game) {
+        if game.winner.is_none() && !game.is_tied() {
+            return;
+        }
+        let teams = game.teams.as_ref().map(|team| team.id);
+        let ratings = teams.map(|team| self.rating(team));
+        let scores = match game.winner {
+            Some(winner) if winner == teams.away => AwayHome {
+                away: 1.0,
+                home: 0.0,
+            },
+            Some(_) => AwayHome {
+                away: 0.0,
+                home: 1.0,
+            },
+            None => AwayHome {
+                away: 0.5,
+                home: 0.5,
+            },
+        };
+        let k = self.rating_config.k_factor;
+        let away = ratings.away + k * (scores.away - expected_score(ratings.away, ratings.home));
+        let home = ratings.home + k * (scores.home - expected_score(ratings.home, ratings.away));
+        self.ratings.insert(teams.away, away);
+        self.ratings.insert(teams.home, home);
+    }
+
+    /// Pull every rating part of the way back to the average for a new season.
+    pub(crate) fn regress_ratings(&mut self) {
+        let regression = self.rating_config.season_regression.clamp(0.0, 1.0);
+        for rating in self.ratings.values_mut() {
+            *rating += (INITIAL_RATING - *rating) * regression;
+        }
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// A team's rating, which is [`INITIAL_RATING`] until it has finished a game.
+    pub fn rating(&self, team: TeamId) -> f64 {
+        self.database.rating(team)
+    }
+
+    pub fn rating_config(&self) -> &RatingConfig {
+        &self.database.rating_config
+    }
+
+    /// Change how team ratings move, starting with the next game to finish.
+    pub fn set_rating_config(&mut self, config: RatingConfig) {
+        self.database.rating_config = config;
+    }
+
+    /// A season's standings (see [`SeasonStats::standings`](crate::SeasonStats::standings)),
+    /// with each team's current rating. Empty if no games have finished in the season.
+    pub fn standings(&self, season: u16) -> Vec<Standing<'_>> {
+        self.stats(season)
+            .map(|stats| stats.standings())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(team, record)| Standing {
+                team,
+                record,
+                rating: self.rating(team),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RatingConfig, INITIAL_RATING};
+    use crate::{Date, Game, Sim};
+
+    #[test]
+    fn ratings() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        let mut sim = crate::sim::tests::two_games();
+        let teams = sim.games_today()[0].teams.as_ref().map(|team| team.id);
+        assert!(close(sim.rating(teams.away), INITIAL_RATING));
+        sim.finish_day();
+        let game = &sim.games_today()[0];
+        let (winner, loser) = if game.winner == Some(teams.away) {
+            (teams.away, teams.home)
+        } else {
+            (teams.home, teams.away)
+        };
+        // evenly matched teams move by half the K-factor
+        assert!(close(sim.rating(winner), INITIAL_RATING + 10.0));
+        assert!(close(sim.rating(loser), INITIAL_RATING - 10.0));
+        let standings = sim.standings(0);
+        assert_eq!(standings.len(), 4);
+        assert!(close(standings[0].rating, INITIAL_RATING + 10.0));
+        assert!(sim.standings(1).is_empty());
+
+        // the odds lean toward the better-rated team as much as they're told to
+        let rematch = Game::new(teams);
+        let before = sim.odds(&rematch).unwrap();
+        sim.set_rating_config(RatingConfig {
+            odds_weight: 1.0,
+            ..RatingConfig::default()
+        });
+        let odds = sim.odds(&rematch).unwrap();
+        let favorite = if winner == teams.away {
+            odds.away
+        } else {
+            odds.home
+        };
+        let expected = super::expected_score(INITIAL_RATING + 10.0, INITIAL_RATING - 10.0);
+        assert!(close(favorite, expected));
+        assert_ne!(odds, before);
+
+        // ratings head back toward the average in a new season, and are saved with the sim
+        sim.set_rating_config(RatingConfig {
+            season_regression: 0.5,
+            ..RatingConfig::default()
+        });
+        let games = sim.round_robin(1);
+        sim.start_day(Date { season: 1, day: 0 }, games).unwrap();
+        assert!(close(sim.rating(winner), INITIAL_RATING + 5.0));
+        let json = serde_json::to_string(&sim).unwrap();
+        let other: Sim = serde_json::from_str(&json).unwrap();
+        assert!(close(other.rating(winner), sim.rating(winner)));
+        assert_eq!(other.rating_config(), sim.rating_config());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::database::Database;
+use crate::id::TeamId;
+use crate::{DatabaseError, Mutation, RngSource, Sim, Team};
+use serde::{Deserialize, Serialize};
+
+/// The names a team goes by. See [`Sim::rebrand_team`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct TeamIdentity {
+    pub location: String,
+    pub nickname: String,
+    pub shorthand: String,
+}
+
+impl TeamIdentity {
+    pub fn new(location: &str, nickname: &str, shorthand: &str) -> TeamIdentity {
+        TeamIdentity {
+            location: location.into(),
+            nickname: nickname.into(),
+            shorthand: shorthand.into(),
+        }
+    }
+
+    /// The identity a team goes by now.
+    pub fn of(team: &Team) -> TeamIdentity {
+        TeamIdentity::new(&team.location, &team.nickname, &team.shorthand)
+    }
+
+    /// The team's full name, like [`Team::name`].
+    pub fn name(&self) -> String {
+        format!("{} {}", self.location, self.nickname)
+    }
+
+    fn apply(&self, team: &mut Team) {
+        self.location.clone_into(&mut team.location);
+        self.nickname.clone_into(&mut team.nickname);
+        self.shorthand.clone_into(&mut team.shorthand);
+    }
+}
+
+/// A team's move to a new location or change of name, kept in the sim's history. See
+/// [`Sim::rebrands`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Rebrand {
+    pub team: TeamId,
+    /// The first season the team played under its new identity.
+    pub season: u16,
+    pub old: TeamIdentity,
+    pub new: TeamIdentity,
+}
+
+impl Database {
+    /// Apply every rebrand waiting for a new season. Rebrands for teams that aren't in the
+    /// database, as in an edited save, are dropped.
+    pub(crate) fn apply_rebrands(&mut self, season: u16) {
+        for (team, new) in std::mem::take(&mut self.rebrands) {
+            let Some(data) = self.teams.get_mut(&team) else {
+                continue;
+            };
+            let old = TeamIdentity::of(data);
+            new.apply(data);
+            let name = new.name();
+            self.history.rebrands.push(Rebrand {
+                team,
+                season,
+                old,
+                new,
+            });
+            self.record(Mutation::TeamRebranded { team, name });
+        }
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Move a team to a new location or give it new names, starting with the next season. The
+    /// change takes effect on the first [`Sim::start_day`] in a later season, and is then kept in
+    /// [`Sim::rebrands`] so past seasons can still be shown under the team's old identity (see
+    /// [`Sim::team_identity`]). Rebranding a team again before then replaces the waiting rebrand.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the team is not in the database, or if any of the new names are empty.
+    pub fn rebrand_team(
+        &mut self,
+        team: TeamId,
+        identity: TeamIdentity,
+    ) -> Result<(), DatabaseError> {
+        team.try_load(&self.database)?;
+        if [&identity.location, &identity.nickname, &identity.shorthand]
+            .iter()
+            .any(|name| name.trim().is_empty())
+        {
+            return Err(DatabaseError::EmptyName);
+        }
+        let name = identity.name();
+        self.database.rebrands.insert(team, identity);
+        self.database
+            .record(Mutation::RebrandScheduled { team, name });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// The rebrand waiting for the next season, if any.
+    pub fn pending_rebrand(&self, team: TeamId) -> Option<&TeamIdentity> {
+        self.database.rebrands.get(&team)
+    }
+
+    /// Call off the rebrand waiting for the next season, returning it.
+    pub fn cancel_rebrand(&mut self, team: TeamId) -> Option<TeamIdentity> {
+        let identity = self.database.rebrands.remove(&team)?;
+        self.database.record(Mutation::RebrandCancelled { team });
+        self.database.check_after_mutation();
+        Some(identity)
+    }
+
+    /// Every rebrand that has taken effect, oldest first. These are kept regardless of the
+    /// [history retention](Sim::set_history_retention).
+    pub fn rebrands(&self) -> &[Rebrand] {
+        &self.database.history.rebrands
+    }
+
+    /// The identity a team went by during a season, for showing that season's stats and games.
+    /// Returns `None` if the team is not in the database.
+    pub fn team_identity(&self, team: TeamId, season: u16) -> Option<TeamIdentity> {
+        let mut identity = TeamIdentity::of(self.database.teams.get(&team)?);
+        for rebrand in self.rebrands().iter().rev() {
+            if rebrand.team == team && rebrand.season > season {
+                identity = rebrand.old.clone();
+            }
+        }
+        Some(identity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TeamIdentity;
+    use crate::id::TeamId;
+    use crate::{Date, HistoryRetention, Mutation, Sim};
+
+    #[test]
+    fn rebrands() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.set_history_retention(HistoryRetention::Unbounded);
+        sim.finish_day();
+        let team = sim.games_today()[0].teams.away.id;
+        let old = TeamIdentity::of(sim.teams().get(&team).unwrap());
+        assert!(sim
+            .rebrand_team(team, TeamIdentity::new("Boston", "", "BOS"))
+            .is_err());
+        let new = TeamIdentity::new("Boston", "Flowers", "BOS");
+        sim.rebrand_team(team, new.clone()).unwrap();
+        assert_eq!(sim.pending_rebrand(team), Some(&new));
+        assert_eq!(
+            sim.audit_log().last().unwrap().mutation,
+            Mutation::RebrandScheduled {
+                team,
+                name: "Boston Flowers".into(),
+            }
+        );
+
+        // nothing changes until the next season starts
+        let games = sim.round_robin(1);
+        sim.start_day(Date { season: 0, day: 1 }, games).unwrap();
+        assert_eq!(sim.teams().get(&team).unwrap().nickname, old.nickname);
+        assert!(sim.rebrands().is_empty());
+        sim.finish_day();
+        let games = sim.round_robin(2);
+        sim.start_day(Date { season: 1, day: 0 }, games).unwrap();
+        assert_eq!(sim.teams().get(&team).unwrap().name(), "Boston Flowers");
+        assert_eq!(sim.pending_rebrand(team), None);
+        assert!(sim.audit_log().iter().any(|entry| entry.mutation
+            == Mutation::TeamRebranded {
+                team,
+                name: "Boston Flowers".into(),
+            }));
+
+        // past seasons still go by the old identity, and the rebrand is saved with the sim
+        assert_eq!(sim.team_identity(team, 0), Some(old.clone()));
+        assert_eq!(sim.team_identity(team, 1), Some(new.clone()));
+        let json = serde_json::to_string(&sim).unwrap();
+        let other: Sim = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.rebrands(), sim.rebrands());
+        assert_eq!(other.rebrands()[0].season, 1);
+
+        // a second rebrand stacks on the first
+        sim.rebrand_team(team, TeamIdentity::new("Boston", "Bulbs", "BUL"))
+            .unwrap();
+        sim.finish_day();
+        let games = sim.round_robin(3);
+        sim.start_day(Date { season: 2, day: 0 }, games).unwrap();
+        assert_eq!(sim.team_identity(team, 0), Some(old));
+        assert_eq!(sim.team_identity(team, 1), Some(new));
+        assert_eq!(sim.team_identity(team, 2).unwrap().shorthand, "BUL");
+        assert_eq!(sim.rebrands().len(), 2);
+    }
+
+    #[test]
+    fn cancel_and_unknown_team() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let team = sim.games_today()[0].teams.away.id;
+        assert_eq!(sim.cancel_rebrand(team), None);
+        let identity = TeamIdentity::new("Boston", "Flowers", "BOS");
+        sim.rebrand_team(team, identity.clone()).unwrap();
+        sim.take_audit_log();
+        assert_eq!(sim.cancel_rebrand(team), Some(identity.clone()));
+        assert_eq!(
+            sim.take_audit_log()[0].mutation,
+            Mutation::RebrandCancelled { team }
+        );
+
+        // a save edited to rebrand a team that isn't there
+        let missing = TeamId::new();
+        sim.database.rebrands.insert(missing, identity);
+        let report = sim.validate();
+        assert_eq!(report.problems.len(), 1);
+        assert_eq!(report.problems[0].kind, "pending rebrand");
+        assert_eq!(report.problems[0].id, Some(missing.0));
+        let games = sim.round_robin(1);
+        sim.start_day(Date { season: 1, day: 0 }, games).unwrap();
+        assert!(sim.rebrands().is_empty());
+        assert_eq!(sim.pending_rebrand(missing), None);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Change, RngSource, Sim, SimDiff};
+
+/// The first tick where a replay didn't reproduce the recorded changes, from
+/// [`Sim::replay_check`].
+#[derive(Debug, thiserror::Error)]
+#[error("replay diverged on recorded tick {tick}: expected {expected:?}, got {actual:?}")]
+#[non_exhaustive]
+pub struct ReplayMismatch {
+    /// The index of the tick in the recording, starting from 0.
+    pub tick: usize,
+    /// The first recorded change that wasn't reproduced, or `None` if the replay produced
+    /// something extra.
+    pub expected: Option<Change>,
+    /// The change the replay produced instead, or `None` if it was missing.
+    pub actual: Option<Change>,
+}
+
+impl<R: RngSource + Clone> Sim<R> {
+    /// Tick `ticks` times, returning what changed on each tick. The result can be passed to
+    /// [`Sim::replay_check`] on a copy of the sim from before these ticks.
+    pub fn record_ticks(&mut self, ticks: usize) -> Vec<SimDiff> {
+        (0..ticks)
+            .map(|_| {
+                let before = self.clone();
+                self.tick();
+                Sim::diff(&before, self)
+            })
+            .collect()
+    }
+
+    /// Re-run ticks on a copy of this sim and check that each one reproduces the changes
+    /// previously recorded with [`Sim::record_ticks`] from the same state. This sim is unchanged.
+    ///
+    /// This is meant as a guard rail for changes to the simulation that shouldn't change its
+    /// results, such as how many random numbers are rolled and in what order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first tick whose changes differ from the recording.
+    pub fn replay_check(&self, saved_events: &[SimDiff]) -> Result<(), ReplayMismatch> {
+        let mut sim = self.clone();
+        for (tick, recorded) in saved_events.iter().enumerate() {
+            let replayed = sim.record_ticks(1).remove(0);
+            if replayed != *recorded {
+                let len = recorded.changes.len().max(replayed.changes.len());
+                let i = (0..len)
+                    .find(|i| recorded.changes.get(*i) != replayed.changes.get(*i))
+                    .unwrap_or_default();
+                return Err(ReplayMismatch {
+                    tick,
+                    expected: recorded.changes.get(i).cloned(),
+                    actual: replayed.changes.get(i).cloned(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Rng, SimDiff};
+
+    #[test]
+    fn replay_check() {
+        let mut sim = crate::sim::tests::two_games();
+        let start = sim.clone();
+        let recorded = sim.record_ticks(50);
+        let json = serde_json::to_string(&recorded).unwrap();
+        let recorded: Vec<SimDiff> = serde_json::from_str(&json).unwrap();
+        start.replay_check(&recorded).unwrap();
+
+        // a different roll sequence should be caught
+        let mut other = start.clone();
+        other.rng = Rng::seeded(3, 4);
+        let mismatch = other.replay_check(&recorded).unwrap_err();
+        assert_ne!(mismatch.expected, mismatch.actual);
+
+        let mut short = recorded;
+        short[10].changes.clear();
+        assert!(matches!(start.replay_check(&short[..10]), Ok(())));
+        let mismatch = start.replay_check(&short).unwrap_err();
+        assert_eq!(mismatch.tick, 10);
+        assert!(mismatch.expected.is_none());
+        assert!(mismatch.actual.is_some());
+    }
+}
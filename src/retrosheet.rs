@@ -0,0 +1,188 @@
+use crate::sim::{HOME_BASE, OUTS_NEEDED};
+use crate::{Game, Play, PlayEvent, RngSource, Sim, TeamSelect};
+use std::io::{self, Write};
+
+// Retrosheet's event file format is documented at https://www.retrosheet.org/eventfile.htm.
+//
+// simx doesn't track fielding positions, so fielders are written as `99` (unknown), and players
+// and teams are identified by their UUIDs instead of Retrosheet's 8-character IDs.
+
+impl<R: RngSource> Sim<R> {
+    /// Write today's finished games in the style of Retrosheet event files, one play per line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_retrosheet<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for game in self.finished_games() {
+            self.write_retrosheet_game(&mut writer, game)?;
+        }
+        Ok(())
+    }
+
+    fn write_retrosheet_game<W: Write>(&self, writer: &mut W, game: &Game) -> io::Result<()> {
+        let date = self.database.date;
+        writeln!(writer, "id,{}", game.id)?;
+        writeln!(writer, "version,2")?;
+        writeln!(writer, "info,visteam,{}", game.teams.away.id)?;
+        writeln!(writer, "info,hometeam,{}", game.teams.home.id)?;
+        writeln!(writer, "info,season,{}", date.season)?;
+        writeln!(writer, "info,day,{}", date.day)?;
+
+        self.write_retrosheet_starters(writer, game)?;
+
+        let mut pitches = String::new();
+        for play in &game.plays {
+            let (pitch, event) = match &play.event {
+                PlayEvent::Ball => (Some('B'), None),
+                PlayEvent::Strike { swinging: false } => (Some('C'), None),
+                PlayEvent::Strike { swinging: true } => (Some('S'), None),
+                PlayEvent::Foul => (Some('F'), None),
+                PlayEvent::Walk => (Some('B'), Some("W".to_owned())),
+                PlayEvent::HitByPitch => (Some('H'), Some("HP".to_owned())),
+                PlayEvent::Strikeout { swinging } => (
+                    Some(if *swinging { 'S' } else { 'C' }),
+                    Some("K".to_owned()),
+                ),
+                PlayEvent::Flyout { .. } => (Some('X'), Some("99/F".to_owned())),
+                PlayEvent::GroundOut { .. } => (Some('X'), Some("99/G".to_owned())),
+                PlayEvent::LineOut { .. } => (Some('X'), Some("99/L".to_owned())),
+                PlayEvent::PopOut { .. } => (Some('X'), Some("99/P".to_owned())),
+                PlayEvent::FoulOut { .. } => (Some('X'), Some("99/FL".to_owned())),
+                PlayEvent::SacrificeBunt { .. } => (Some('X'), Some("99/SH".to_owned())),
+                PlayEvent::BuntPopout { .. } => (Some('X'), Some("99/BP".to_owned())),
+                PlayEvent::BuntHit => (Some('X'), Some("S/BG".to_owned())),
+                PlayEvent::HomeRun => (Some('X'), Some("HR".to_owned())),
+                PlayEvent::Hit { bases } => (
+                    Some('X'),
+                    Some(
+                        match bases {
+                            1 => "S",
+                            2 => "D",
+                            _ => "T",
+                        }
+                        .to_owned(),
+                    ),
+                ),
+                PlayEvent::StolenBase { base, .. } => {
+                    (None, Some(format!("SB{}", base_code(*base))))
+                }
+                PlayEvent::CaughtStealing { base, .. } => {
+                    (None, Some(format!("CS{}", base_code(*base))))
+                }
+                PlayEvent::AwardedRun { team } => {
+                    // there's no event for a run nobody scored
+                    let team = game.teams.select(*team).id;
+                    writeln!(writer, "com,\"run awarded to {team}\"")?;
+                    continue;
+                }
+            };
+            pitches.extend(pitch);
+            if let Some(event) = event {
+                writeln!(
+                    writer,
+                    "play,{},{},{},{}{},{},{}{}",
+                    play.inning.number(),
+                    side_of(play),
+                    play.batter,
+                    play.balls,
+                    play.strikes,
+                    pitches,
+                    event,
+                    advances(play)
+                )?;
+            }
+            if play.event.ends_plate_appearance()
+                || play.outs_before + play.outs_on_play >= OUTS_NEEDED
+            {
+                pitches.clear();
+            }
+        }
+        Ok(())
+    }
+
+    fn write_retrosheet_starters<W: Write>(&self, writer: &mut W, game: &Game) -> io::Result<()> {
+        for (select, side) in [(TeamSelect::Away, 0), (TeamSelect::Home, 1)] {
+            let team = game.teams.select(select).id.load(&self.database);
+            for (i, player) in team.lineup.iter().enumerate() {
+                writeln!(
+                    writer,
+                    "start,{},\"{}\",{},{},0",
+                    player,
+                    quote(&player.load(&self.database).name),
+                    side,
+                    i + 1
+                )?;
+            }
+            // pitchers don't bat, so they are listed like a pitcher in a designated hitter game
+            let starter = game
+                .plays
+                .iter()
+                .find(|play| {
+                    side_of(play) != side && !matches!(play.event, PlayEvent::AwardedRun { .. })
+                })
+                .map(|play| play.pitcher);
+            if let Some(pitcher) = starter {
+                writeln!(
+                    writer,
+                    "start,{},\"{}\",{},0,1",
+                    pitcher,
+                    quote(&pitcher.load(&self.database).name),
+                    side
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn side_of(play: &Play) -> u8 {
+    match play.inning.batting() {
+        TeamSelect::Away => 0,
+        TeamSelect::Home => 1,
+    }
+}
+
+fn base_code(base: u8) -> String {
+    if base >= HOME_BASE {
+        "H".to_owned()
+    } else {
+        base.to_string()
+    }
+}
+
+/// Runner advances, like `.1-3;2-H`. The batter's own advance is implied by the event.
+fn advances(play: &Play) -> String {
+    if play.outs_before + play.outs_on_play >= OUTS_NEEDED {
+        // the half-inning ended; nobody advanced
+        return String::new();
+    }
+    let caught = match play.event {
+        PlayEvent::CaughtStealing { runner, .. } => Some(runner),
+        _ => None,
+    };
+    let mut moves = Vec::new();
+    for (runner, from) in &play.baserunners_before {
+        if Some(*runner) == caught {
+            continue;
+        }
+        let to = play
+            .baserunners_after
+            .iter()
+            .find(|(r, _)| r == runner)
+            .map_or(HOME_BASE, |(_, base)| *base);
+        if to != *from {
+            moves.push(format!("{}-{}", from, base_code(to)));
+        }
+    }
+    if matches!(play.event, PlayEvent::StolenBase { .. }) || moves.is_empty() {
+        // a stolen base is described by the event itself
+        String::new()
+    } else {
+        format!(".{}", moves.join(";"))
+    }
+}
+
+fn quote(name: &str) -> String {
+    name.replace('"', "")
+}
@@ -1,3 +1,4 @@
+use crate::Term;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::array::IntoIter;
 use std::num::Wrapping;
@@ -10,34 +11,228 @@ type Iter = IntoIter<u64, 64>;
 // - `State` has no invariants
 // - `Iter` has its own deserialize implementation
 #[allow(clippy::unsafe_derive_deserialize)]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Rng {
     state: State,
     #[serde(
         deserialize_with = "deserialize_iter",
         serialize_with = "serialize_iter"
     )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Vec<u64>"))]
     iter: Iter,
+    #[serde(skip)]
+    trace: Option<Vec<Roll>>,
+}
+
+/// A source of rolls for the sim.
+///
+/// [`Rng`] is the implementation used to match Blaseball. Other implementations can be used to
+/// script specific outcomes in tests or to swap in a faster generator.
+pub trait RngSource {
+    /// Produce the next value, in the range `[0, 1)`.
+    fn next_f64(&mut self) -> f64;
+
+    /// Like [`RngSource::next_f64`], with a label describing what the value is for.
+    fn next_labeled(&mut self, label: &'static str) -> f64 {
+        let _ = label;
+        self.next_f64()
+    }
+
+    /// Roll a value and return whether it is less than `threshold`.
+    fn roll(&mut self, label: &'static str, threshold: f64) -> bool {
+        self.next_labeled(label) < threshold
+    }
+
+    /// Return `true` with probability `p`. Consumes one value.
+    fn next_bool(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Produce a value uniformly distributed in the range `[lo, hi)`. Consumes one value.
+    fn next_range(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (hi - lo) * self.next_f64()
+    }
+
+    /// Produce a value from the standard normal distribution (mean 0, standard deviation 1),
+    /// using the Box-Muller transform. Consumes two values.
+    fn next_gaussian(&mut self) -> f64 {
+        // `1.0 - x` keeps the logarithm's argument in `(0, 1]`
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+    }
+
+    fn choose<I>(&mut self, choices: I) -> Option<I::Item>
+    where
+        Self: Sized,
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let value = self.next_f64();
+        choose_with(value, choices)
+    }
+
+    /// Like [`RngSource::choose`], with a label describing what the choice is for.
+    fn choose_labeled<I>(&mut self, label: &'static str, choices: I) -> Option<I::Item>
+    where
+        Self: Sized,
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let value = self.next_labeled(label);
+        choose_with(value, choices)
+    }
+
+    /// Start or stop recording every value consumed. Stopping discards the trace. Sources that
+    /// do not support tracing ignore this.
+    fn set_tracing(&mut self, enabled: bool) {
+        let _ = enabled;
+    }
+
+    /// The values consumed since tracing was enabled or the trace was last taken, or `None` if
+    /// tracing is disabled or unsupported.
+    fn trace(&self) -> Option<&[Roll]> {
+        None
+    }
+
+    /// Take the recorded trace, leaving an empty trace in its place if tracing is enabled.
+    fn take_trace(&mut self) -> Vec<Roll> {
+        Vec::new()
+    }
+
+    /// Record the terms a formula reported (see [`Pitch::term`](crate::Pitch::term)) on the last
+    /// roll in the trace. Sources that do not support tracing ignore this.
+    fn explain(&mut self, terms: Vec<Term>) {
+        let _ = terms;
+    }
+}
+
+/// A single value consumed from the RNG, recorded when tracing is enabled.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Roll {
+    /// What the roll was for, if the call site provided a label.
+    pub label: Option<&'static str>,
+    pub value: f64,
+    /// The threshold the value was compared against, for rolls made with [`RngSource::roll`].
+    pub threshold: Option<f64>,
+    /// Whether the value was less than the threshold, for rolls made with [`RngSource::roll`].
+    pub outcome: Option<bool>,
+    /// What went into the threshold, for rolls against a [formula](crate::Formulas) that
+    /// reports its terms.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub terms: Vec<Term>,
 }
 
 impl Rng {
+    /// Create a new RNG seeded from the operating system's random number generator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system's random number generator fails.
+    #[cfg(feature = "os-entropy")]
     pub fn new() -> Rng {
         let mut buf = [0; 16];
         getrandom::getrandom(&mut buf).expect("failed to get random seed");
         // SAFETY: integers are plain old datatypes so we can always transmute to them
-        Rng::from_state(unsafe { std::mem::transmute(buf) })
+        Rng::from_state(unsafe { std::mem::transmute::<[u8; 16], State>(buf) })
     }
 
     pub fn seeded(s0: u64, s1: u64) -> Rng {
         Rng::from_state([Wrapping(s0), Wrapping(s1)])
     }
 
+    /// Create an RNG seeded from an arbitrary phrase, like `"season-7-chaos"`.
+    ///
+    /// The phrase is hashed with 64-bit FNV-1a and expanded into the two state words with
+    /// SplitMix64. This mapping is stable across versions of simx.
+    pub fn seeded_from_str(phrase: &str) -> Rng {
+        let hash = phrase.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        let s0 = splitmix64(hash);
+        Rng::seeded(s0, splitmix64(s0))
+    }
+
+    /// Create an RNG positioned at a value, using the `(s0, s1)+offset` notation from
+    /// [rng.sibr.dev](https://rng.sibr.dev). The next value produced is the value at that
+    /// position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is not less than 64.
+    pub fn from_position(s0: u64, s1: u64, offset: u8) -> Rng {
+        assert!(offset < 64, "offset must be less than 64");
+        let mut state = [Wrapping(s0), Wrapping(s1)];
+        for _ in 0..=offset {
+            prev(&mut state);
+        }
+        let mut rng = Rng::from_state(state);
+        discard(&mut rng.iter, u64::from(63 - offset));
+        rng
+    }
+
+    /// The state of the next value produced, in the `(s0, s1)+offset` notation from
+    /// [rng.sibr.dev](https://rng.sibr.dev). See also [`Rng::offset`].
+    pub fn state(&self) -> (u64, u64) {
+        let mut state = self.state;
+        if self.iter.len() == 0 {
+            for _ in 0..64 {
+                next(&mut state);
+            }
+        } else {
+            for _ in self.iter.len()..64 {
+                prev(&mut state);
+            }
+        }
+        (state[0].0, state[1].0)
+    }
+
+    /// The offset of the next value produced within its 64-value block, in the
+    /// `(s0, s1)+offset` notation from [rng.sibr.dev](https://rng.sibr.dev). See also
+    /// [`Rng::state`].
+    pub fn offset(&self) -> u8 {
+        #[allow(clippy::cast_possible_truncation)]
+        let offset = self.iter.len().checked_sub(1).unwrap_or(63) as u8;
+        offset
+    }
+
+    /// Derive an independent stream from a seed and a key, such that the same seed and key
+    /// always produce the same stream.
+    pub(crate) fn derived(seed: (u64, u64), key: u128) -> Rng {
+        #[allow(clippy::cast_possible_truncation)]
+        let (hi, lo) = ((key >> 64) as u64, key as u64);
+        Rng::seeded(
+            splitmix64(seed.0 ^ splitmix64(hi)),
+            splitmix64(seed.1 ^ splitmix64(lo)),
+        )
+    }
+
     fn from_state(mut state: State) -> Rng {
         let iter = next_buf(&mut state);
-        Rng { state, iter }
+        Rng {
+            state,
+            iter,
+            trace: None,
+        }
     }
 
-    pub fn next_f64(&mut self) -> f64 {
+    fn record(&mut self, label: Option<&'static str>, value: f64, threshold: Option<f64>) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(Roll {
+                label,
+                value,
+                threshold,
+                outcome: threshold.map(|threshold| value < threshold),
+                terms: Vec::new(),
+            });
+        }
+    }
+
+    // clippy::missing_panics_doc: `next_buf` always refills the iterator
+    #[allow(clippy::missing_panics_doc)]
+    fn next_raw(&mut self) -> f64 {
         let s0_shifted = if let Some(n) = self.iter.next_back() {
             n
         } else {
@@ -49,38 +244,226 @@ impl Rng {
         f64::from_bits(s0_shifted | 0x3ff0_0000_0000_0000) - 1.0
     }
 
-    pub fn choose<I>(&mut self, choices: I) -> Option<I::Item>
-    where
-        I: IntoIterator,
-        I::IntoIter: ExactSizeIterator,
-    {
-        let mut choices = choices.into_iter();
-        #[allow(clippy::cast_precision_loss)]
-        let len = choices.len() as f64;
-        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-        let n = (self.next_f64() * len).floor() as usize;
-        choices.nth(n)
+    /// Advance the RNG by `n` values, as if `next_f64` were called `n` times.
+    pub fn jump(&mut self, n: u64) {
+        let remaining = self.iter.len() as u64;
+        if n <= remaining {
+            discard(&mut self.iter, n);
+            return;
+        }
+        let n = n - remaining;
+        self.iter = Iter::default();
+        self.state = Jump::step().pow(n / 64 * 64).apply(self.state);
+        if !n.is_multiple_of(64) {
+            self.iter = next_buf(&mut self.state);
+            discard(&mut self.iter, n % 64);
+        }
     }
-}
 
-fn next_buf(state: &mut State) -> Iter {
-    fn next(state: &mut State) -> u64 {
-        let [mut s1, s0] = *state;
-        state[0] = s0;
-        s1 ^= s1 << 23;
-        s1 ^= s1 >> 17;
-        s1 ^= s0;
-        s1 ^= s0 >> 26;
-        *state = [state[1], s1];
-        (s0 >> 12).0
+    /// Move the RNG backwards by `n` values, so that the last `n` values are produced again.
+    pub fn step_back(&mut self, n: u64) {
+        if n == 0 {
+            return;
+        }
+        let used = 64 - self.iter.len() as u64;
+        let block_back = Jump::step_back().pow(64);
+        let mut state = block_back.apply(self.state);
+        let used = if let Some(used) = used.checked_sub(n) {
+            used
+        } else {
+            let deficit = n - used;
+            state = block_back.pow(deficit.div_ceil(64)).apply(state);
+            (64 - deficit % 64) % 64
+        };
+        self.iter = next_buf(&mut state);
+        self.state = state;
+        discard(&mut self.iter, used);
     }
 
+    /// Advance the RNG by `n` whole 64-value blocks, keeping the position within the current
+    /// block the same.
+    ///
+    /// This is equivalent to `self.jump(64 * n)`, but does not overflow.
+    pub fn jump_blocks(&mut self, n: u64) {
+        if n == 0 {
+            return;
+        }
+        let used = 64 - self.iter.len() as u64;
+        let block = Jump::step().pow(64);
+        if used == 64 {
+            self.state = block.pow(n).apply(self.state);
+        } else {
+            self.state = block.pow(n - 1).apply(self.state);
+            self.iter = next_buf(&mut self.state);
+            discard(&mut self.iter, used);
+        }
+    }
+}
+
+/// The SplitMix64 output function, used to spread seed bits across the state.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn choose_with<I>(value: f64, choices: I) -> Option<I::Item>
+where
+    I: IntoIterator,
+    I::IntoIter: ExactSizeIterator,
+{
+    let mut choices = choices.into_iter();
+    #[allow(clippy::cast_precision_loss)]
+    let len = choices.len() as f64;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let n = (value * len).floor() as usize;
+    choices.nth(n)
+}
+
+fn next(state: &mut State) -> u64 {
+    let [mut s1, s0] = *state;
+    state[0] = s0;
+    s1 ^= s1 << 23;
+    s1 ^= s1 >> 17;
+    s1 ^= s0;
+    s1 ^= s0 >> 26;
+    *state = [state[1], s1];
+    (s0 >> 12).0
+}
+
+/// The inverse of `next`.
+fn prev(state: &mut State) {
+    let [s0, s1] = *state;
+    let mut x = s1 ^ s0 ^ (s0 >> 26);
+    // undo `x ^= x >> 17`
+    x ^= (x >> 17) ^ (x >> 34) ^ (x >> 51);
+    // undo `x ^= x << 23`
+    x ^= (x << 23) ^ (x << 46);
+    *state = [x, s0];
+}
+
+fn next_buf(state: &mut State) -> Iter {
     let iter = std::array::from_fn(|_| next(state)).into_iter();
     debug_assert!(iter.size_hint().0 == 64);
     debug_assert!(iter.size_hint().1 == Some(64));
     iter
 }
 
+fn discard(iter: &mut Iter, n: u64) {
+    if let Some(n) = n.checked_sub(1) {
+        #[allow(clippy::cast_possible_truncation)]
+        iter.nth_back(n as usize);
+    }
+}
+
+/// A linear transformation of the RNG state over GF(2), used to skip ahead without generating
+/// each intermediate value. Each element is the image of the corresponding bit of the state.
+struct Jump([u128; 128]);
+
+impl Jump {
+    fn identity() -> Jump {
+        Jump(std::array::from_fn(|i| 1 << i))
+    }
+
+    fn step() -> Jump {
+        Jump(std::array::from_fn(|i| {
+            let mut state = from_bits(1 << i);
+            next(&mut state);
+            to_bits(state)
+        }))
+    }
+
+    fn step_back() -> Jump {
+        Jump(std::array::from_fn(|i| {
+            let mut state = from_bits(1 << i);
+            prev(&mut state);
+            to_bits(state)
+        }))
+    }
+
+    fn apply_bits(&self, bits: u128) -> u128 {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| bits >> i & 1 == 1)
+            .fold(0, |acc, (_, column)| acc ^ column)
+    }
+
+    fn apply(&self, state: State) -> State {
+        from_bits(self.apply_bits(to_bits(state)))
+    }
+
+    fn then(&self, other: &Jump) -> Jump {
+        Jump(std::array::from_fn(|i| other.apply_bits(self.0[i])))
+    }
+
+    fn pow(&self, mut n: u64) -> Jump {
+        let mut result = Jump::identity();
+        let mut base = Jump(self.0);
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.then(&base);
+            }
+            base = base.then(&base);
+            n >>= 1;
+        }
+        result
+    }
+}
+
+fn to_bits(state: State) -> u128 {
+    u128::from(state[0].0) | u128::from(state[1].0) << 64
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn from_bits(bits: u128) -> State {
+    [Wrapping(bits as u64), Wrapping((bits >> 64) as u64)]
+}
+
+impl RngSource for Rng {
+    fn next_f64(&mut self) -> f64 {
+        let value = self.next_raw();
+        self.record(None, value, None);
+        value
+    }
+
+    fn next_labeled(&mut self, label: &'static str) -> f64 {
+        let value = self.next_raw();
+        self.record(Some(label), value, None);
+        value
+    }
+
+    fn roll(&mut self, label: &'static str, threshold: f64) -> bool {
+        let value = self.next_raw();
+        self.record(Some(label), value, Some(threshold));
+        value < threshold
+    }
+
+    fn set_tracing(&mut self, enabled: bool) {
+        if !enabled {
+            self.trace = None;
+        } else if self.trace.is_none() {
+            self.trace = Some(Vec::new());
+        }
+    }
+
+    fn trace(&self) -> Option<&[Roll]> {
+        self.trace.as_deref()
+    }
+
+    fn take_trace(&mut self) -> Vec<Roll> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    fn explain(&mut self, terms: Vec<Term>) {
+        if let Some(roll) = self.trace.as_mut().and_then(|trace| trace.last_mut()) {
+            roll.terms = terms;
+        }
+    }
+}
+
+#[cfg(feature = "os-entropy")]
 impl Default for Rng {
     fn default() -> Rng {
         Rng::new()
@@ -117,13 +500,28 @@ where
     iter.as_slice().serialize(serializer)
 }
 
+/// Two RNGs are equal if they will produce the same values. Tracing is ignored.
+impl PartialEq for Rng {
+    fn eq(&self, other: &Self) -> bool {
+        self.state.eq(&other.state) && self.iter.as_slice().eq(other.iter.as_slice())
+    }
+}
+
 #[cfg(test)]
-mod tests {
-    use super::Rng;
+pub(crate) mod tests {
+    use super::{Rng, RngSource};
+    use crate::{PlayEvent, Sim};
+
+    /// Answers each roll from a script keyed on its label, and 0.5 for unlabeled values.
+    pub(crate) struct Scripted(pub(crate) fn(&'static str) -> f64);
+
+    impl RngSource for Scripted {
+        fn next_f64(&mut self) -> f64 {
+            0.5
+        }
 
-    impl PartialEq for Rng {
-        fn eq(&self, other: &Self) -> bool {
-            self.state.eq(&other.state) && self.iter.as_slice().eq(other.iter.as_slice())
+        fn next_labeled(&mut self, label: &'static str) -> f64 {
+            (self.0)(label)
         }
     }
 
@@ -167,9 +565,98 @@ mod tests {
         );
     }
 
+    #[allow(clippy::pedantic)]
+    #[test]
+    fn position() {
+        // https://rng.sibr.dev/?state=(9168710189202541577,14545355385888695162)+17
+        let mut rng = Rng::seeded(2935246629125674131, 766864515362452477);
+        rng.nth(45);
+        assert_eq!(rng.state(), (9168710189202541577, 14545355385888695162));
+        assert_eq!(rng.offset(), 17);
+        assert_eq!(
+            Rng::from_position(9168710189202541577, 14545355385888695162, 17),
+            rng
+        );
+
+        rng.nth(17);
+        assert_eq!(rng.offset(), 63);
+        let mut rebuilt = Rng::from_position(rng.state().0, rng.state().1, 63);
+        assert_eq!(rebuilt.next(), rng.next());
+    }
+
+    #[test]
+    fn seeded_from_str() {
+        let a = Rng::seeded_from_str("season-7-chaos");
+        assert_eq!(a, Rng::seeded_from_str("season-7-chaos"));
+        assert_ne!(a, Rng::seeded_from_str("season-8-chaos"));
+    }
+
+    #[test]
+    fn helper_consumption() {
+        let mut rng = Rng::seeded(1, 2);
+        let mut expected = Rng::from_position(rng.state().0, rng.state().1, rng.offset());
+        rng.next_bool(0.5);
+        rng.next_range(-1.0, 1.0);
+        rng.next_gaussian();
+        expected.jump(4);
+        assert_eq!(rng.next(), expected.next());
+    }
+
+    #[allow(clippy::pedantic)]
+    #[test]
+    fn jump() {
+        for n in [0, 1, 5, 49, 50, 63, 64, 65, 128, 200, 1000] {
+            let mut expected = Rng::seeded(2935246629125674131, 766864515362452477);
+            let mut jumped = Rng::seeded(2935246629125674131, 766864515362452477);
+            expected.nth(14);
+            jumped.nth(14);
+
+            for _ in 0..n {
+                expected.next();
+            }
+            jumped.jump(n);
+            assert_eq!(jumped.next(), expected.next(), "jump({})", n);
+        }
+    }
+
+    #[test]
+    fn step_back() {
+        for skip in [0, 14, 63, 64, 100] {
+            for n in [0, 1, 14, 50, 64, 65, 130] {
+                let mut rng = Rng::seeded(3, 4);
+                rng.jump(skip);
+                let values = rng.by_ref().take(200).collect::<Vec<_>>();
+                rng.step_back(n);
+                let n = usize::try_from(n).unwrap();
+                assert_eq!(rng.take(n).collect::<Vec<_>>(), values[(200 - n)..]);
+            }
+        }
+    }
+
+    #[allow(clippy::pedantic)]
+    #[test]
+    fn jump_blocks() {
+        for skip in [0, 14, 63, 64] {
+            let mut expected = Rng::seeded(2935246629125674131, 766864515362452477);
+            let mut jumped = Rng::seeded(2935246629125674131, 766864515362452477);
+            expected.jump(skip);
+            jumped.jump(skip);
+            for _ in 0..(64 * 3) {
+                expected.next();
+            }
+            jumped.jump_blocks(3);
+            assert_eq!(
+                jumped.take(100).collect::<Vec<_>>(),
+                expected.take(100).collect::<Vec<_>>(),
+                "skip {}",
+                skip
+            );
+        }
+    }
+
     #[test]
     fn ser_and_de() -> Result<(), serde_json::Error> {
-        let mut rng = Rng::new();
+        let mut rng = Rng::seeded(5, 6);
         let rebuilt: Rng = serde_json::from_str(&serde_json::to_string(&rng)?)?;
         assert_eq!(rng, rebuilt);
 
@@ -190,4 +677,36 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn scripted() {
+        // every pitch is in the zone and taken
+        let mut sim = Sim::with_rng(Scripted(|label| match label {
+            "strike" => 0.0,
+            "swing" => 0.99,
+            _ => 0.5,
+        }));
+        sim.database = crate::sim::tests::two_games().database;
+        while sim.games_today()[0].plays.len() < 6 {
+            sim.tick();
+        }
+        let game = &sim.games_today()[0];
+        let events = game
+            .plays
+            .iter()
+            .map(|play| &play.event)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                &PlayEvent::Strike { swinging: false },
+                &PlayEvent::Strike { swinging: false },
+                &PlayEvent::Strikeout { swinging: false },
+                &PlayEvent::Strike { swinging: false },
+                &PlayEvent::Strike { swinging: false },
+                &PlayEvent::Strikeout { swinging: false },
+            ]
+        );
+        assert!(game.plays.iter().all(|play| play.balls == 0));
+    }
 }
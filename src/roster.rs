@@ -0,0 +1,474 @@
+use crate::database::CheckEntity;
+use crate::id::{PlayerId, TeamId};
+use crate::{Database, DatabaseError, Mutation, RngSource, RosterPosition, RosterSlot, Sim};
+
+impl<R: RngSource> Sim<R> {
+    /// Move a player to a different place on their team's roster, such as from the shadows to
+    /// the lineup. The player is inserted at `to.index` after being removed from their current
+    /// place, shifting later players back.
+    ///
+    /// The team's rotation slot and the lineup slots of today's games are adjusted so that the
+    /// same pitcher and batters are up next. A player in the middle of one of today's unfinished
+    /// games (batting, on base, or pitching) can be reordered within their part of the roster,
+    /// but can't be moved out of it until the game is over.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not on a team's roster, if `to.index` is past the end of
+    /// the target list, or if the move would take a player in an unfinished game out of the
+    /// lineup or rotation they're playing from.
+    pub fn move_player(&mut self, player: PlayerId, to: RosterSlot) -> Result<(), DatabaseError> {
+        let (team, from) = self.database.roster_slot_of(player)?;
+        if from.position != to.position {
+            self.database.check_not_playing(player)?;
+        }
+        let len = team.load(&self.database).players(to.position).len();
+        let max = if from.position == to.position {
+            len - 1
+        } else {
+            len
+        };
+        if to.index > max {
+            return Err(DatabaseError::SlotOutOfRange { team, slot: to });
+        }
+        self.database.roster_remove(team, from);
+        self.database.roster_insert(team, to, player);
+        self.database.record(Mutation::RosterRemoved {
+            team,
+            player,
+            slot: from,
+        });
+        self.database.record(Mutation::RosterAdded {
+            team,
+            player,
+            slot: to,
+        });
+        self.database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Swap two players' places on their teams' rosters. The players can be on the same team or
+    /// on different teams.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either player is not on a team's roster, for any of the reasons
+    /// [`Sim::trade`] would. The rosters are unchanged in this case.
+    pub fn swap_players(&mut self, a: PlayerId, b: PlayerId) -> Result<(), DatabaseError> {
+        let (team_a, slot_a) = self.database.roster_slot_of(a)?;
+        let (team_b, slot_b) = self.database.roster_slot_of(b)?;
+        self.trade(team_a, slot_a, team_b, slot_b)
+    }
+
+    /// Exchange the players in two roster slots. The slots can be on the same team or on
+    /// different teams.
+    ///
+    /// As with [`Sim::move_player`], a player in the middle of one of today's unfinished games
+    /// can only be exchanged with someone in the same part of their own team's roster.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either team is not in the database, either slot is empty, either
+    /// player is in an unfinished game and would change teams or leave the lineup or rotation
+    /// they're playing from, or the trade would leave a team with an invalid roster. The rosters
+    /// are unchanged in this case.
+    pub fn trade(
+        &mut self,
+        team_a: TeamId,
+        slot_a: RosterSlot,
+        team_b: TeamId,
+        slot_b: RosterSlot,
+    ) -> Result<(), DatabaseError> {
+        if slot_a.position != slot_b.position {
+            // moving between teams is checked in `Database::trade`
+            self.database
+                .check_not_playing(self.database.roster_get(team_a, slot_a)?)?;
+            self.database
+                .check_not_playing(self.database.roster_get(team_b, slot_b)?)?;
+        }
+        self.database.trade(team_a, slot_a, team_b, slot_b)?;
+        self.database.record_trade(team_a, slot_a, team_b, slot_b);
+        self.database.check_after_mutation();
+        Ok(())
+    }
+}
+
+impl Database {
+    /// Exchange the players in two roster slots. Players in today's unfinished games can't change
+    /// teams, but can change places on their own team's roster (see [`Sim::substitute`]).
+    pub(crate) fn trade(
+        &mut self,
+        team_a: TeamId,
+        slot_a: RosterSlot,
+        team_b: TeamId,
+        slot_b: RosterSlot,
+    ) -> Result<(), DatabaseError> {
+        let player_a = self.roster_get(team_a, slot_a)?;
+        let player_b = self.roster_get(team_b, slot_b)?;
+        if team_a != team_b {
+            self.check_not_playing(player_a)?;
+            self.check_not_playing(player_b)?;
+        }
+        let exchange = |database: &mut Database, a, b| {
+            team_a.load_mut(database).players_mut(slot_a.position)[slot_a.index] = b;
+            team_b.load_mut(database).players_mut(slot_b.position)[slot_b.index] = a;
+        };
+
+        exchange(self, player_a, player_b);
+        for team in [team_a, team_b] {
+            if let Err(err) = team.load(self).check(self) {
+                exchange(self, player_b, player_a);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Record a successful [`Database::trade`] in the audit log, as each slot's player leaving it
+    /// and the other slot's player taking their place.
+    pub(crate) fn record_trade(
+        &mut self,
+        team_a: TeamId,
+        slot_a: RosterSlot,
+        team_b: TeamId,
+        slot_b: RosterSlot,
+    ) {
+        // the players have already changed places
+        let Ok(player_a) = self.roster_get(team_b, slot_b) else {
+            return;
+        };
+        let Ok(player_b) = self.roster_get(team_a, slot_a) else {
+            return;
+        };
+        for (team, slot, left, arrived) in [
+            (team_a, slot_a, player_a, player_b),
+            (team_b, slot_b, player_b, player_a),
+        ] {
+            self.record(Mutation::RosterRemoved {
+                team,
+                player: left,
+                slot,
+            });
+            self.record(Mutation::RosterAdded {
+                team,
+                player: arrived,
+                slot,
+            });
+        }
+    }
+
+    /// Check that a player isn't batting, on base, or pitching (or charged with a runner) in one
+    /// of today's unfinished games.
+    pub(crate) fn check_not_playing(&self, player: PlayerId) -> Result<(), DatabaseError> {
+        let game = self
+            .games_today
+            .iter()
+            .find(|game| !game.is_finished() && game.players().any(|p| *p == player));
+        match game {
+            Some(game) => Err(DatabaseError::PlayerInUse {
+                player,
+                kind: "game",
+                id: game.id.0,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Remove the player in a roster slot, keeping slot counters pointed at the same upcoming
+    /// player.
+    pub(crate) fn roster_remove(&mut self, team: TeamId, slot: RosterSlot) -> PlayerId {
+        self.adjust_slots(team, slot, |n| if slot.index < n { n - 1 } else { n });
+        let data = team.load_mut(self);
+        let player = data.players_mut(slot.position).remove(slot.index);
+        // Removing the last pitcher in the rotation while they were up wraps around to the top.
+        data.rotation_slot %= data.rotation.len().max(1);
+        player
+    }
+
+    /// Insert a player into a roster slot, keeping slot counters pointed at the same upcoming
+    /// player.
+    pub(crate) fn roster_insert(&mut self, team: TeamId, slot: RosterSlot, player: PlayerId) {
+        self.adjust_slots(team, slot, |n| if slot.index <= n { n + 1 } else { n });
+        team.load_mut(self)
+            .players_mut(slot.position)
+            .insert(slot.index, player);
+    }
+
+    fn adjust_slots(&mut self, team: TeamId, slot: RosterSlot, f: impl Fn(usize) -> usize) {
+        match slot.position {
+            RosterPosition::Lineup => {
+                for game in &mut self.games_today {
+                    for data in game.teams.iter_mut() {
+                        if data.id == team {
+                            data.lineup_slot = f(data.lineup_slot);
+                        }
+                    }
+                }
+            }
+            RosterPosition::Rotation => {
+                // The slot can be past the end of the rotation (say, from a loaded save), in
+                // which case it effectively points at `rotation_slot % len`.
+                let team = team.load_mut(self);
+                let len = team.rotation.len().max(1);
+                team.rotation_slot = f(team.rotation_slot % len);
+            }
+            RosterPosition::Shadows => {}
+        }
+    }
+
+    pub(crate) fn roster_slot_of(
+        &self,
+        player: PlayerId,
+    ) -> Result<(TeamId, RosterSlot), DatabaseError> {
+        self.teams
+            .values()
+            .find_map(|team| Some((team.id, team.slot_of(player)?)))
+            .ok_or(DatabaseError::NotRostered { player })
+    }
+
+    pub(crate) fn roster_get(
+        &self,
+        team: TeamId,
+        slot: RosterSlot,
+    ) -> Result<PlayerId, DatabaseError> {
+        team.try_load(self)?
+            .get(slot)
+            .ok_or(DatabaseError::EmptySlot { team, slot })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DatabaseError, Mutation, Player, RosterPosition, RosterSize, RosterSlot};
+
+    #[test]
+    fn move_player() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.tick_until(|games| games[0].at_bat.is_some() && games[0].teams.home.pitcher.is_some());
+        let game = sim.games_today()[0].clone();
+        let pitcher = game.teams.home.pitcher.unwrap();
+        let batter = game.at_bat.unwrap();
+        let shadows = RosterSlot {
+            position: RosterPosition::Shadows,
+            index: 0,
+        };
+
+        // players in the game can't leave the part of the roster they're playing from
+        for player in [pitcher, batter] {
+            assert!(matches!(
+                sim.move_player(player, shadows),
+                Err(DatabaseError::PlayerInUse { kind: "game", id, .. }) if id == game.id.0
+            ));
+        }
+        let team = sim.teams().get(&game.teams.home.id).unwrap().clone();
+        assert!(team.rotation.contains(&pitcher));
+
+        // but they can be reordered
+        let last = RosterSlot {
+            position: RosterPosition::Rotation,
+            index: team.rotation.len() - 1,
+        };
+        sim.move_player(pitcher, last).unwrap();
+        let moved = sim.teams().get(&team.id).unwrap();
+        assert_eq!(*moved.rotation.last().unwrap(), pitcher);
+
+        sim.finish_day();
+        sim.move_player(pitcher, shadows).unwrap();
+        assert_eq!(sim.teams().get(&team.id).unwrap().shadows[0], pitcher);
+        assert!(sim.validate().is_ok());
+    }
+
+    #[test]
+    fn trade() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.tick_until(|games| games[0].at_bat.is_some() && games[0].teams.home.pitcher.is_some());
+        let game = sim.games_today()[0].clone();
+        let (away, home) = (game.teams.away.id, game.teams.home.id);
+        let pitcher = game.teams.home.pitcher.unwrap();
+        let batter = game.at_bat.unwrap();
+        let other = sim.games_today()[1].teams.home.id;
+        let other_team = sim.teams().get(&other).unwrap().clone();
+        let before = sim.teams().clone();
+
+        // players in a game can't be traded away mid-game, to the other team or anywhere else
+        for player in [pitcher, batter] {
+            assert!(matches!(
+                sim.swap_players(player, other_team.rotation[0]),
+                Err(DatabaseError::PlayerInUse { kind: "game", .. })
+            ));
+            assert!(matches!(
+                sim.swap_players(other_team.lineup[0], player),
+                Err(DatabaseError::PlayerInUse { kind: "game", .. })
+            ));
+        }
+        let home_shadow = sim.teams().get(&home).unwrap().shadows[0];
+        assert!(matches!(
+            sim.swap_players(pitcher, home_shadow),
+            Err(DatabaseError::PlayerInUse { kind: "game", .. })
+        ));
+        assert_eq!(*sim.teams(), before);
+
+        // anyone else can go anywhere
+        let away_shadow = sim.teams().get(&away).unwrap().shadows[0];
+        sim.swap_players(away_shadow, other_team.shadows[1])
+            .unwrap();
+        assert_eq!(
+            sim.teams().get(&away).unwrap().shadows[0],
+            other_team.shadows[1]
+        );
+        assert_eq!(sim.teams().get(&other).unwrap().shadows[1], away_shadow);
+        let slot = |index| RosterSlot {
+            position: RosterPosition::Shadows,
+            index,
+        };
+        sim.trade(home, slot(0), other, slot(0)).unwrap();
+        assert_eq!(sim.teams().get(&other).unwrap().shadows[0], home_shadow);
+        assert!(sim.validate().is_ok());
+
+        // a trade that fails changes nothing
+        let before = sim.teams().clone();
+        assert!(matches!(
+            sim.trade(home, slot(RosterSize::STANDARD.shadows), other, slot(0)),
+            Err(DatabaseError::EmptySlot { .. })
+        ));
+        assert_eq!(*sim.teams(), before);
+        let mut broken = sim.clone();
+        broken.set_check_policy(crate::CheckPolicy::Never);
+        let [a, b] = [home, other].map(|team| broken.teams().get(&team).unwrap().shadows[0]);
+        broken.database.teams.get_mut(&other).unwrap().shadows[1] = a;
+        assert!(matches!(
+            broken.trade(home, slot(0), other, slot(0)),
+            Err(DatabaseError::DuplicatePlayer { player }) if player == a
+        ));
+        assert_eq!(broken.teams().get(&home).unwrap().shadows[0], a);
+        assert_eq!(broken.teams().get(&other).unwrap().shadows[0], b);
+    }
+
+    #[test]
+    fn remove_player() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.tick_until(|games| games[0].at_bat.is_some() && games[0].teams.home.pitcher.is_some());
+        let game = sim.games_today()[0].clone();
+        let pitcher = game.teams.home.pitcher.unwrap();
+        let batter = game.at_bat.unwrap();
+
+        for player in [pitcher, batter] {
+            assert!(matches!(
+                sim.remove_player(player),
+                Err(DatabaseError::PlayerInUse { kind: "team", .. })
+            ));
+        }
+        // off the roster but still in the game
+        let mut unrostered = sim.clone();
+        unrostered.set_check_policy(crate::CheckPolicy::Never);
+        let team = unrostered
+            .database
+            .teams
+            .get_mut(&game.teams.home.id)
+            .unwrap();
+        team.rotation.retain(|p| *p != pitcher);
+        assert!(matches!(
+            unrostered.remove_player(pitcher),
+            Err(DatabaseError::PlayerInUse { kind: "game", id, .. }) if id == game.id.0
+        ));
+
+        let removed = sim.remove_player_force(batter).unwrap();
+        assert_eq!(removed.id, batter);
+        assert!(!sim.players().contains_key(&batter));
+        assert!(sim.database.player_references(batter).is_empty());
+        assert!(sim.validate().is_ok());
+        assert!(matches!(
+            sim.remove_player(batter),
+            Err(DatabaseError::BadReference { kind: "player", .. })
+        ));
+
+        sim.finish_day();
+        let shadow = sim.teams().get(&game.teams.away.id).unwrap().shadows[0];
+        sim.remove_player(shadow).unwrap_err();
+        sim.remove_player_force(shadow).unwrap();
+        assert!(sim.database.player_references(shadow).is_empty());
+        assert!(sim.validate().is_ok());
+    }
+
+    #[test]
+    fn remove_rotation_slot_past_end() {
+        let mut sim = crate::sim::tests::two_games();
+        let home = sim.games_today()[0].teams.home.id;
+        let rotation = sim.teams().get(&home).unwrap().rotation.clone();
+        let len = rotation.len();
+
+        // a slot past the end points at `slot % len`; removing an earlier pitcher keeps it there
+        sim.database.teams.get_mut(&home).unwrap().rotation_slot = len + 2;
+        sim.remove_player_force(rotation[0]).unwrap();
+        let team = sim.teams().get(&home).unwrap();
+        assert_eq!(team.rotation[team.rotation_slot], rotation[2]);
+
+        // removing the pitcher who's up last in the rotation wraps around to the top
+        sim.database.teams.get_mut(&home).unwrap().rotation_slot = 2 * (len - 1) - 1;
+        sim.remove_player_force(rotation[len - 1]).unwrap();
+        let team = sim.teams().get(&home).unwrap();
+        assert_eq!(team.rotation_slot, 0);
+        assert_eq!(team.rotation[0], rotation[1]);
+        assert!(sim.validate().is_ok());
+    }
+
+    #[test]
+    fn audit_mirror() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let teams = sim.games_today()[0].teams.as_ref().map(|team| team.id);
+        let [team_a, team_b] =
+            [teams.away, teams.home].map(|team| sim.teams().get(&team).unwrap().clone());
+        let before = sim.teams().clone();
+        sim.take_audit_log();
+
+        let shadows = RosterSlot {
+            position: RosterPosition::Shadows,
+            index: 0,
+        };
+        sim.move_player(team_a.lineup[0], shadows).unwrap();
+        sim.swap_players(team_a.lineup[1], team_a.rotation[0])
+            .unwrap();
+        sim.swap_players(team_a.shadows[1], team_b.lineup[2])
+            .unwrap();
+        sim.remove_player_force(team_b.rotation[1]).unwrap();
+
+        // replaying the audit log's roster changes onto the old rosters gets the new ones
+        let mut mirror = before.clone();
+        for entry in sim.audit_log() {
+            match entry.mutation {
+                Mutation::RosterRemoved { team, player, slot } => {
+                    let players = mirror.get_mut(&team).unwrap().players_mut(slot.position);
+                    assert_eq!(players.remove(slot.index), player);
+                }
+                Mutation::RosterAdded { team, player, slot } => {
+                    let players = mirror.get_mut(&team).unwrap().players_mut(slot.position);
+                    players.insert(slot.index, player);
+                }
+                _ => {}
+            }
+        }
+        assert_ne!(*sim.teams(), before);
+        assert_eq!(mirror, *sim.teams());
+        assert_eq!(
+            sim.audit_log().last().unwrap().mutation,
+            Mutation::PlayerRemoved {
+                player: team_b.rotation[1]
+            }
+        );
+
+        // with the log off, nothing more is added
+        let len = sim.audit_log().len();
+        sim.set_audit_log(false);
+        sim.add_player(Player::builder().build()).unwrap();
+        assert_eq!(sim.audit_log().len(), len);
+        sim.set_audit_log(true);
+        let player = Player::builder().build();
+        let id = player.id;
+        sim.add_player(player).unwrap();
+        assert_eq!(
+            sim.audit_log().last().unwrap().mutation,
+            Mutation::PlayerAdded { player: id }
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use crate::id::{PlayerId, TeamId};
+use crate::{
+    mods, Database, DatabaseError, Date, Mutation, Player, RngSource, RosterPosition, RosterSlot,
+    Sim, Table, Team,
+};
+use serde::{Deserialize, Serialize};
+
+/// What a team does when nobody in its rotation can pitch, either because the rotation is empty
+/// or because every pitcher in it is [unavailable](Player::is_available). Set with
+/// [`Sim::set_empty_rotation`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum EmptyRotation {
+    /// Generate a Pitching Machine and add it to the rotation (see [`MachinePolicy`]).
+    #[default]
+    PitchingMachine,
+    /// Start the first available player in the shadows, without moving them into the rotation.
+    /// If nobody in the shadows is available either, generate a Pitching Machine.
+    CallUpShadows,
+}
+
+/// What happens when a game needs a Machine: a Pitching Machine for a team nobody can pitch for
+/// (after its [`EmptyRotation`] policy), or a Batting Machine for a team with an empty lineup.
+/// Set with [`Sim::set_machine_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum MachinePolicy {
+    /// Generate the Machine and add it to the team's roster.
+    #[default]
+    AutoGenerate,
+    /// Suspend the game (see [`Game::suspended`](crate::Game::suspended)) with a
+    /// [`DatabaseError::RosterTooSmall`], which [`Sim::try_tick`] returns.
+    Error,
+    /// The team forfeits the game, as with [`Sim::forfeit_game`].
+    Forfeit,
+}
+
+impl Player {
+    /// Whether the player can take the field: they aren't [Shelled](mods::SHELLED) or
+    /// [Elsewhere](mods::ELSEWHERE).
+    pub fn is_available(&self) -> bool {
+        !self.has_mod(mods::SHELLED) && !self.has_mod(mods::ELSEWHERE)
+    }
+}
+
+impl Team {
+    /// The rotation index of the first available pitcher at or after `slot`, wrapping around the
+    /// end of the rotation.
+    fn next_in_rotation(&self, players: &Table<PlayerId, Player>, slot: usize) -> Option<usize> {
+        let len = self.rotation.len();
+        (0..len).map(|i| (slot + i) % len).find(|i| {
+            players
+                .get(&self.rotation[*i])
+                .is_some_and(Player::is_available)
+        })
+    }
+
+    /// The pitcher in line to start the team's game `games_ahead` games from now (0 for the
+    /// next game), going by [`Team::rotation_slot`] and skipping unavailable pitchers. Returns
+    /// `None` if nobody in the rotation is available, in which case the team falls back to its
+    /// [`EmptyRotation`] policy.
+    ///
+    /// This assumes nobody's availability changes in the meantime.
+    pub fn probable_pitcher(
+        &self,
+        players: &Table<PlayerId, Player>,
+        games_ahead: usize,
+    ) -> Option<PlayerId> {
+        let mut slot = self.rotation_slot;
+        for _ in 0..games_ahead {
+            slot = self.next_in_rotation(players, slot)? + 1;
+        }
+        let index = self.next_in_rotation(players, slot)?;
+        Some(self.rotation[index])
+    }
+}
+
+impl Database {
+    /// Whether a team with nobody pitching would have to generate a Pitching Machine to start
+    /// a game.
+    pub(crate) fn needs_pitching_machine(&self, team: TeamId) -> bool {
+        let data = team.load(self);
+        data.next_in_rotation(&self.players, data.rotation_slot)
+            .is_none()
+            && !(self.empty_rotation == EmptyRotation::CallUpShadows
+                && data
+                    .shadows
+                    .iter()
+                    .any(|player| self.players.get(player).is_some_and(Player::is_available)))
+    }
+
+    /// Pick the pitcher who starts a team's game, pointing the team's rotation slot at them.
+    pub(crate) fn starting_pitcher(&mut self, rng: &mut impl RngSource, team: TeamId) -> PlayerId {
+        let data = team.load(self);
+        if let Some(index) = data.next_in_rotation(&self.players, data.rotation_slot) {
+            let player = data.rotation[index];
+            team.load_mut(self).rotation_slot = index;
+            return player;
+        }
+        if self.empty_rotation == EmptyRotation::CallUpShadows {
+            let shadow = data
+                .shadows
+                .iter()
+                .find(|player| self.players.get(player).is_some_and(Player::is_available));
+            if let Some(player) = shadow {
+                return *player;
+            }
+        }
+
+        let player = Player::generate_with_name(rng, "Pitching Machine".to_string());
+        let player_id = player.id;
+        self.players.insert(player_id, player);
+        self.record(Mutation::PlayerAdded { player: player_id });
+        let data = team.load_mut(self);
+        let index = data.rotation.len();
+        data.rotation.push(player_id);
+        data.rotation_slot = index;
+        self.record(Mutation::RosterAdded {
+            team,
+            player: player_id,
+            slot: RosterSlot {
+                position: RosterPosition::Rotation,
+                index,
+            },
+        });
+        player_id
+    }
+
+    /// Move a team's rotation slot past the pitcher who just started, wrapping around to the
+    /// start of the rotation.
+    pub(crate) fn advance_rotation(&mut self, team: TeamId) {
+        let data = team.load_mut(self);
+        data.rotation_slot = (data.rotation_slot + 1) % data.rotation.len().max(1);
+        let slot = data.rotation_slot;
+        self.record(Mutation::RotationAdvanced { team, slot });
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// The pitcher in line to start a team's game on `date`, assuming the team plays once on
+    /// each day from now until then (see [`Team::probable_pitcher`]). If the team's game today
+    /// already has a pitcher, that's the pitcher for today.
+    ///
+    /// Returns `None` if `date` isn't later in the current season, or if nobody in the team's
+    /// rotation is available.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the team is not in the database.
+    pub fn probable_pitcher(
+        &self,
+        team: TeamId,
+        date: Date,
+    ) -> Result<Option<PlayerId>, DatabaseError> {
+        let team_data = team.try_load(&self.database)?;
+        let today = self.date();
+        if date.season != today.season || date.day < today.day {
+            return Ok(None);
+        }
+        let game = self
+            .games_today()
+            .iter()
+            .filter(|game| !game.is_finished())
+            .find_map(|game| game.teams.iter().find(|side| side.id == team));
+        let days = usize::from(date.day - today.day);
+        let games_ahead = match game {
+            Some(game) if days == 0 => {
+                return Ok(game
+                    .pitcher
+                    .or_else(|| team_data.probable_pitcher(&self.database.players, 0)))
+            }
+            // today's game still has to use up a slot
+            Some(_) => days,
+            None => days.saturating_sub(1),
+        };
+        Ok(team_data.probable_pitcher(&self.database.players, games_ahead))
+    }
+
+    pub fn empty_rotation(&self) -> EmptyRotation {
+        self.database.empty_rotation
+    }
+
+    /// Set what teams do when nobody in their rotation can pitch.
+    pub fn set_empty_rotation(&mut self, policy: EmptyRotation) {
+        self.database.empty_rotation = policy;
+    }
+
+    pub fn machine_policy(&self) -> MachinePolicy {
+        self.database.machine_policy
+    }
+
+    /// Set what happens when a game would need a Pitching Machine or Batting Machine. Strict
+    /// leagues can use [`MachinePolicy::Error`] or [`MachinePolicy::Forfeit`] to surface roster
+    /// problems instead of quietly making up players.
+    pub fn set_machine_policy(&mut self, policy: MachinePolicy) {
+        self.database.machine_policy = policy;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EmptyRotation, MachinePolicy};
+    use crate::{mods, CalledOff, DatabaseError, Date, RosterPosition, SimError};
+
+    #[test]
+    fn rotation() {
+        let mut sim = crate::sim::tests::two_games();
+        let team = sim.games_today()[0].teams.home.id;
+        let data = sim.teams().get(&team).unwrap().clone();
+        let len = data.rotation.len();
+        let slot = data.rotation_slot;
+        let next = |i: usize| data.rotation[(slot + i) % len];
+        let tomorrow = Date { season: 0, day: 1 };
+        assert_eq!(
+            sim.probable_pitcher(team, sim.date()).unwrap(),
+            Some(next(0))
+        );
+        assert_eq!(sim.probable_pitcher(team, tomorrow).unwrap(), Some(next(1)));
+
+        // a Shelled pitcher is skipped, both today and in the days to come
+        sim.update_player(next(0), |player| player.mods.push(mods::SHELLED.into()))
+            .unwrap();
+        assert_eq!(
+            sim.probable_pitcher(team, sim.date()).unwrap(),
+            Some(next(1))
+        );
+        assert_eq!(sim.probable_pitcher(team, tomorrow).unwrap(), Some(next(2)));
+        sim.finish_day();
+        assert_eq!(sim.games_today()[0].teams.home.pitcher, Some(next(1)));
+        let data = sim.teams().get(&team).unwrap();
+        assert_eq!(data.rotation_slot, (slot + 2) % len);
+        assert!(sim.teams().values().all(|team| team.rotation_slot < len));
+        assert_eq!(sim.probable_pitcher(team, tomorrow).unwrap(), Some(next(2)));
+        assert_eq!(
+            sim.probable_pitcher(team, Date { season: 1, day: 0 })
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn empty_rotation() {
+        let mut sim = crate::sim::tests::two_games();
+        let team = sim.games_today()[0].teams.home.id;
+        let data = sim.teams().get(&team).unwrap().clone();
+        for player in &data.rotation {
+            sim.update_player(*player, |player| player.mods.push(mods::ELSEWHERE.into()))
+                .unwrap();
+        }
+        assert_eq!(sim.probable_pitcher(team, sim.date()).unwrap(), None);
+
+        let mut shadows = sim.clone();
+        shadows.set_empty_rotation(EmptyRotation::CallUpShadows);
+        shadows.finish_day();
+        let pitcher = shadows.games_today()[0].teams.home.pitcher.unwrap();
+        assert_eq!(pitcher, data.shadows[0]);
+        assert_eq!(shadows.teams().get(&team).unwrap().rotation, data.rotation);
+
+        sim.finish_day();
+        let pitcher = sim.games_today()[0].teams.home.pitcher.unwrap();
+        assert_eq!(
+            sim.players().get(&pitcher).unwrap().name,
+            "Pitching Machine"
+        );
+        assert_eq!(
+            sim.teams().get(&team).unwrap().rotation.last(),
+            Some(&pitcher)
+        );
+    }
+
+    #[test]
+    fn machine_policy() {
+        let mut sim = crate::sim::tests::two_games();
+        let game = sim.games_today()[0].clone();
+        let team = game.teams.home.id;
+        let data = sim.teams().get(&team).unwrap().clone();
+        for player in &data.rotation {
+            sim.update_player(*player, |player| player.mods.push(mods::ELSEWHERE.into()))
+                .unwrap();
+        }
+        let players = sim.players().len();
+
+        let mut strict = sim.clone();
+        strict.set_machine_policy(MachinePolicy::Error);
+        let error = loop {
+            if let Err(error) = strict.try_tick() {
+                break error;
+            }
+        };
+        assert!(matches!(
+            error,
+            SimError::Suspended {
+                source: DatabaseError::RosterTooSmall {
+                    position: RosterPosition::Rotation,
+                    ..
+                },
+                ..
+            }
+        ));
+        assert!(strict.game(game.id).unwrap().is_suspended());
+        assert_eq!(strict.players().len(), players);
+
+        // plain ticking suspends the game, too
+        let mut strict = sim.clone();
+        strict.set_machine_policy(MachinePolicy::Error);
+        strict.finish_day();
+        assert!(strict.game(game.id).unwrap().is_suspended());
+
+        sim.set_machine_policy(MachinePolicy::Forfeit);
+        sim.finish_day();
+        let game = sim.game(game.id).unwrap();
+        assert!(matches!(
+            game.called_off,
+            Some(CalledOff::Forfeit { team: forfeited, .. }) if forfeited == team
+        ));
+        assert_eq!(game.winner, Some(game.teams.away.id));
+        assert_eq!(sim.players().len(), players);
+    }
+}
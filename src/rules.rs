@@ -0,0 +1,172 @@
+use crate::{Database, Date, Phase, RngSource, Sim, Weather};
+use serde::{Deserialize, Serialize};
+
+/// Rules that can differ between the regular season and the postseason. Each game gets the
+/// rules for its phase when its day starts (see [`Sim::set_rules`]); a resumed game keeps the
+/// rules it started with.
+///
+/// The default rules are the ones the sim always played by: no ties, weather left as it is,
+/// and no boost from ballpark hype.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+#[non_exhaustive]
+pub struct GameRules {
+    /// If set, a game still tied at the end of this many innings (but not before the ninth)
+    /// ends in a tie.
+    pub max_innings: Option<usize>,
+    /// The weather each game is played in is drawn from these, unless it has a
+    /// [forecast](Sim::forecast). If empty, games keep the weather they were created with.
+    pub weather: Vec<Weather>,
+    /// How much the home team's ballpark [hype](crate::Ballpark::hype) helps its pitchers: the
+    /// hype times this is added to the [home advantage](Sim::set_home_advantage) while the home
+    /// team is in the field.
+    pub home_pitcher_hype: f64,
+}
+
+impl Database {
+    /// The rules for games played on `date`. Offseason games play by regular season rules.
+    pub(crate) fn rules_for(&self, date: Date) -> &GameRules {
+        match date.phase(&self.season_config) {
+            Phase::Postseason => &self.postseason_rules,
+            Phase::RegularSeason | Phase::Offseason => &self.regular_season_rules,
+        }
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// The rules games in a phase are played by.
+    pub fn rules(&self, phase: Phase) -> &GameRules {
+        match phase {
+            Phase::Postseason => &self.database.postseason_rules,
+            Phase::RegularSeason | Phase::Offseason => &self.database.regular_season_rules,
+        }
+    }
+
+    /// Change the rules for games in a phase, starting with the next day's games. Offseason
+    /// games play by regular season rules, so setting the offseason rules sets those.
+    pub fn set_rules(&mut self, phase: Phase, rules: GameRules) {
+        match phase {
+            Phase::Postseason => self.database.postseason_rules = rules,
+            Phase::RegularSeason | Phase::Offseason => self.database.regular_season_rules = rules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GameRules;
+    use crate::{Ballpark, Date, Formulas, Game, Phase, Pitch, SeasonConfig, Weather};
+
+    #[derive(Debug)]
+    struct Scoreless;
+
+    impl Formulas for Scoreless {
+        fn strike(&self, _pitch: &Pitch<'_>) -> f64 {
+            1.0
+        }
+
+        fn swing(&self, _pitch: &Pitch<'_>, _strike: bool) -> f64 {
+            0.0
+        }
+    }
+
+    /// Records the home advantage of every pitch thrown to the away team.
+    #[derive(Debug, Default)]
+    struct AwayAdvantage(std::sync::Mutex<Vec<f64>>);
+
+    impl Formulas for AwayAdvantage {
+        fn strike(&self, pitch: &Pitch<'_>) -> f64 {
+            if pitch.home_advantage <= 0.0 {
+                self.0.lock().unwrap().push(pitch.home_advantage);
+            }
+            crate::DefaultFormulas.strike(pitch)
+        }
+    }
+
+    #[test]
+    fn postseason_rules() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.set_season_config(SeasonConfig::new(1, 1));
+        let postseason = GameRules {
+            weather: vec![Weather::Eclipse],
+            home_pitcher_hype: 0.5,
+            ..GameRules::default()
+        };
+        sim.set_rules(Phase::Postseason, postseason.clone());
+        assert_eq!(sim.rules(Phase::RegularSeason), &GameRules::default());
+        assert!(sim.games_today()[0].rules == GameRules::default());
+
+        sim.finish_day();
+        let games = sim
+            .games_today()
+            .iter()
+            .map(|game| Game::new(game.teams.as_ref().map(|team| team.id)))
+            .collect();
+        sim.start_day(Date { season: 0, day: 1 }, games).unwrap();
+        for game in sim.games_today() {
+            assert_eq!(game.rules, postseason);
+            assert_eq!(game.weather, Weather::Eclipse);
+        }
+
+        // hype in the home team's ballpark makes its pitchers better: batters facing them see a
+        // bigger advantage for the pitcher
+        let home = sim.games_today()[0].teams.home.id;
+        let ballpark = Ballpark {
+            id: crate::id::BallparkId::new(),
+            team_id: home,
+            hype: 1.0,
+            ..Ballpark::default()
+        };
+        let mut hyped = sim.clone();
+        hyped.add_ballpark(ballpark).unwrap();
+        let advantage = |sim: &mut crate::Sim| {
+            let spy = std::sync::Arc::new(AwayAdvantage::default());
+            sim.database.formulas = crate::formulas::FormulaSet(spy.clone());
+            sim.finish_day();
+            let seen = spy.0.lock().unwrap();
+            assert!(!seen.is_empty());
+            seen.iter().copied().fold(f64::INFINITY, f64::min)
+        };
+        assert!((advantage(&mut sim) - 0.0).abs() < 1e-9);
+        assert!((advantage(&mut hyped) + 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn default_rules_roll_nothing() {
+        // starting a day under the default rules leaves the sim's RNG where it was
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let games = sim
+            .games_today()
+            .iter()
+            .map(|game| Game::new(game.teams.as_ref().map(|team| team.id)))
+            .collect();
+        let rng = sim.rng.clone();
+        sim.start_day(Date { season: 0, day: 1 }, games).unwrap();
+        assert_eq!(sim.rng, rng);
+    }
+
+    #[test]
+    fn ties() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.set_formulas(Scoreless);
+        for game in &mut sim.database.games_today {
+            game.rules.max_innings = Some(10);
+        }
+        sim.finish_day();
+        for game in sim.games_today() {
+            assert!(game.is_finished() && game.is_tied());
+            assert_eq!(game.winner, None);
+            assert_eq!(game.inning, crate::Inning::End(10));
+            assert!(game.last_update.starts_with("Game over."));
+            assert_eq!(game.outcome.as_ref().unwrap().winning_pitcher, None);
+        }
+        let stats = sim.stats(0).unwrap();
+        assert_eq!(stats.teams.len(), 4);
+        assert!(stats
+            .teams
+            .values()
+            .all(|team| team.ties == 1 && team.wins == 0 && team.losses == 0));
+    }
+}
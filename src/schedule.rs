@@ -0,0 +1,295 @@
+use crate::id::TeamId;
+use crate::{AwayHome, Game, RngSource, Sim};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Options for [`Sim::schedule`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ScheduleConfig {
+    /// Groups of teams that play each other more often. A team listed in more than one division
+    /// only counts toward the first, and teams that aren't in the database are ignored.
+    pub divisions: Vec<Vec<TeamId>>,
+    /// How many times teams play each division rival for every time they play the rest of the
+    /// league. 1 treats divisions like any other team.
+    pub division_weight: u16,
+    /// How many days in a row two teams play each other, at the same ballpark.
+    pub series_length: u16,
+    /// If set, a team that has been the away team for this many series in a row is made the
+    /// home team in its next series, unless its opponent has been on the road at least as long.
+    pub max_road_series: Option<u16>,
+}
+
+impl Default for ScheduleConfig {
+    /// No divisions, single-game series, and no limit on road trips: the same schedule as
+    /// [`Sim::round_robin`].
+    fn default() -> ScheduleConfig {
+        ScheduleConfig {
+            divisions: Vec::new(),
+            division_weight: 1,
+            series_length: 1,
+            max_road_series: None,
+        }
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// One round of a round-robin schedule in each [league](crate::League): each team plays once
+    /// (except one team per round in a league with an odd number of teams). Every team plays
+    /// every other team in its league once over `n - 1` rounds (or `n` for an odd number of
+    /// teams), after which the schedule repeats with home and away swapped. The main league's
+    /// games come first.
+    pub fn round_robin(&self, round: u16) -> Vec<Game> {
+        self.database
+            .teams_by_league()
+            .values()
+            .flat_map(|teams| circle(teams, usize::from(round)))
+            .map(Game::new)
+            .collect()
+    }
+
+    /// Every day of games for a regular season, [`SeasonConfig::regular_season_days`] long,
+    /// shaped by `config`.
+    ///
+    /// The schedule is built from rounds, where each team plays at most once. It repeats a
+    /// cycle of one [round robin](Sim::round_robin) across the league, followed by enough
+    /// round robins inside each division to make up the [division
+    /// weight](ScheduleConfig::division_weight); teams outside any division are off for
+    /// those. Each round is played as a series of [`ScheduleConfig::series_length`] days.
+    ///
+    /// Each [league](crate::League) gets its own schedule, built the same way from its own
+    /// teams (and the part of each division in it), and each day has every league's games.
+    ///
+    /// Nothing here is random, so the same teams and config always give the same schedule.
+    ///
+    /// [`SeasonConfig::regular_season_days`]: crate::SeasonConfig::regular_season_days
+    pub fn schedule(&self, config: &ScheduleConfig) -> Vec<Vec<Game>> {
+        let mut seen = BTreeSet::new();
+        let divisions = config
+            .divisions
+            .iter()
+            .map(|division| {
+                division
+                    .iter()
+                    .filter(|team| self.teams().contains_key(team) && seen.insert(**team))
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let days = usize::from(self.season_config().regular_season_days);
+        let series_length = usize::from(config.series_length.max(1));
+        let needed = days.div_ceil(series_length);
+
+        let mut series = vec![Vec::new(); needed];
+        for teams in self.database.teams_by_league().values() {
+            let divisions = divisions
+                .iter()
+                .map(|division| {
+                    division
+                        .iter()
+                        .filter(|team| teams.contains(team))
+                        .copied()
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let league_series = league_series(teams, &divisions, config, needed);
+            for (round, matchups) in series.iter_mut().zip(league_series) {
+                round.extend(matchups);
+            }
+        }
+        series.truncate(
+            series
+                .iter()
+                .rposition(|round| !round.is_empty())
+                .map_or(0, |i| i + 1),
+        );
+
+        if let Some(max) = config.max_road_series {
+            let mut road_trips = BTreeMap::<TeamId, u16>::new();
+            for round in &mut series {
+                for matchup in round.iter_mut() {
+                    let away = road_trips.get(&matchup.away).copied().unwrap_or_default();
+                    let home = road_trips.get(&matchup.home).copied().unwrap_or_default();
+                    if away >= max && home < away {
+                        *matchup = AwayHome {
+                            away: matchup.home,
+                            home: matchup.away,
+                        };
+                    }
+                    *road_trips.entry(matchup.away).or_default() += 1;
+                    road_trips.insert(matchup.home, 0);
+                }
+            }
+        }
+
+        series
+            .iter()
+            .flat_map(|round| std::iter::repeat_n(round, series_length))
+            .take(days)
+            .map(|round| round.iter().copied().map(Game::new).collect())
+            .collect()
+    }
+}
+
+/// The first `needed` rounds of one league's schedule (see [`Sim::schedule`]), or fewer if
+/// nobody in the league can play anyone.
+fn league_series(
+    teams: &[TeamId],
+    divisions: &[Vec<TeamId>],
+    config: &ScheduleConfig,
+    needed: usize,
+) -> Vec<Vec<AwayHome<TeamId>>> {
+    let league_rounds = rounds(teams.len());
+    let division_rounds = divisions
+        .iter()
+        .map(|division| rounds(division.len()))
+        .max()
+        .unwrap_or_default()
+        * usize::from(config.division_weight.saturating_sub(1));
+
+    let mut series = Vec::with_capacity(needed);
+    let (mut league, mut division) = (0, 0);
+    while series.len() < needed && league_rounds + division_rounds > 0 {
+        for _ in 0..league_rounds {
+            series.push(circle(teams, league));
+            league += 1;
+        }
+        for _ in 0..division_rounds {
+            series.push(
+                divisions
+                    .iter()
+                    .flat_map(|teams| circle(teams, division))
+                    .collect(),
+            );
+            division += 1;
+        }
+    }
+    series.truncate(needed);
+    series
+}
+
+/// How many rounds it takes for `n` teams to play each other once.
+fn rounds(n: usize) -> usize {
+    match n {
+        0 | 1 => 0,
+        n => n - 1 + n % 2,
+    }
+}
+
+/// One round of a round robin between `teams`, by the circle method: fix the first team in
+/// place and rotate the rest around it.
+fn circle(teams: &[TeamId], round: usize) -> Vec<AwayHome<TeamId>> {
+    let mut teams = teams.iter().copied().map(Some).collect::<Vec<_>>();
+    if teams.len() % 2 == 1 {
+        teams.push(None);
+    }
+    let n = teams.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let rounds = n - 1;
+    teams[1..].rotate_right(round % rounds);
+    let swap = (round / rounds) % 2 == 1;
+
+    (0..n / 2)
+        .filter_map(|i| {
+            let (a, b) = (teams[i]?, teams[n - 1 - i]?);
+            // alternate which side of the pairing is home, so the fixed team doesn't play
+            // every game at home
+            let (away, home) = if (i == 0 && round % 2 == 1) ^ swap {
+                (b, a)
+            } else {
+                (a, b)
+            };
+            Some(AwayHome { away, home })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScheduleConfig;
+    use crate::id::TeamId;
+    use crate::{AwayHome, Game, SeasonConfig, Team};
+    use std::collections::BTreeMap;
+
+    fn matchups(day: &[Game]) -> Vec<AwayHome<TeamId>> {
+        day.iter()
+            .map(|game| game.teams.as_ref().map(|team| team.id))
+            .collect()
+    }
+
+    #[test]
+    fn schedule() {
+        let mut sim = crate::sim::tests::two_games();
+        for _ in 0..2 {
+            sim.add_team(Team {
+                id: TeamId::new(),
+                ..Team::default()
+            })
+            .unwrap();
+        }
+        sim.set_season_config(SeasonConfig::new(30, 0));
+        let teams = sim.teams().keys().copied().collect::<Vec<_>>();
+
+        let flat = sim.schedule(&ScheduleConfig::default());
+        assert_eq!(flat.len(), 30);
+        for (day, games) in (0..).zip(&flat) {
+            assert_eq!(matchups(games), matchups(&sim.round_robin(day)));
+        }
+
+        let config = ScheduleConfig {
+            divisions: vec![teams[..3].to_vec(), teams[3..].to_vec()],
+            division_weight: 3,
+            series_length: 3,
+            ..ScheduleConfig::default()
+        };
+        let schedule = sim.schedule(&config);
+        assert_eq!(schedule.len(), 30);
+        for series in schedule.chunks(3) {
+            assert!(series
+                .iter()
+                .all(|day| matchups(day) == matchups(&series[0])));
+        }
+        let division = |team: &TeamId| usize::from(teams[3..].contains(team));
+        let (inside, outside) = schedule
+            .iter()
+            .flat_map(|day| matchups(day))
+            .partition::<Vec<_>, _>(|teams| division(&teams.away) == division(&teams.home));
+        // each team plays its two division rivals three times for every once it plays the
+        // other three teams
+        assert!(inside.len() > outside.len());
+
+        let longest_road_trip = |schedule: &[Vec<Game>]| {
+            let mut trips = BTreeMap::<TeamId, usize>::new();
+            let mut longest = 0;
+            for day in schedule {
+                for teams in matchups(day) {
+                    let trip = trips.entry(teams.away).or_default();
+                    *trip += 1;
+                    longest = longest.max(*trip);
+                    trips.insert(teams.home, 0);
+                }
+            }
+            longest
+        };
+        let max = 1;
+        let limited = sim.schedule(&ScheduleConfig {
+            max_road_series: Some(max),
+            ..config.clone()
+        });
+        assert!(longest_road_trip(&limited) < longest_road_trip(&schedule));
+        // a team only stays on the road past the limit when its opponent has been away at least
+        // as long, so one of them has to be
+        let mut trips = BTreeMap::<TeamId, u16>::new();
+        for series in limited.chunks(3) {
+            for teams in matchups(&series[0]) {
+                let away = trips.get(&teams.away).copied().unwrap_or_default();
+                let home = trips.get(&teams.home).copied().unwrap_or_default();
+                assert!(away < max || home >= away);
+                trips.insert(teams.away, away + 1);
+                trips.insert(teams.home, 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::database::Database;
+use crate::Sim;
+use serde::{Deserialize, Serialize, Serializer};
+
+/// The schema version written into every serialized [`Sim`].
+///
+/// Saves without a version are treated as version 0, from before versioning was introduced.
+pub const SCHEMA_VERSION: u32 = 3;
+
+// Adding a field with `#[serde(default)]` or renaming one with `#[serde(alias)]` does not need a
+// new schema version. Anything else (changing what a field means, moving data between objects)
+// should bump `SCHEMA_VERSION` and add a migration here, which runs on the loaded database before
+// its consistency is checked.
+//
+// `MIGRATIONS[n]` upgrades a database from version `n` to version `n + 1`.
+const MIGRATIONS: [fn(&mut Database); SCHEMA_VERSION as usize] = [
+    // 0 -> 1: every field added before versioning (tick, ballparks, idols, mods, audit log) has a
+    // serde default, so unversioned saves load as-is.
+    |_| {},
+    // 1 -> 2: free agents used to be every player not on a team's roster, and are now an explicit
+    // pool.
+    |database| {
+        database.free_agents = database
+            .players
+            .keys()
+            .filter(|player| database.roster_slot_of(**player).is_err())
+            .copied()
+            .collect();
+    },
+    // 2 -> 3: baserunners used to be `[player, base]` pairs, which load as runners who reached
+    // some other way; work out which of them are forced.
+    |database| {
+        for game in &mut database.games_today {
+            game.update_forced();
+        }
+    },
+];
+
+fn migrate(database: &mut Database, from: u32) {
+    for migration in &MIGRATIONS[from as usize..] {
+        migration(database);
+    }
+}
+
+/// Always serializes as [`SCHEMA_VERSION`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct CurrentVersion;
+
+impl Serialize for CurrentVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(SCHEMA_VERSION)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for CurrentVersion {
+    fn schema_name() -> String {
+        "SchemaVersion".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        u32::json_schema(gen)
+    }
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct SimRepr<R> {
+    #[serde(default)]
+    schema_version: u32,
+    rng: R,
+    #[serde(default)]
+    game_streams: Option<(u64, u64)>,
+    #[serde(flatten)]
+    database: Database,
+}
+
+impl<R> TryFrom<SimRepr<R>> for Sim<R> {
+    type Error = String;
+
+    fn try_from(repr: SimRepr<R>) -> Result<Sim<R>, String> {
+        if repr.schema_version > SCHEMA_VERSION {
+            return Err(format!(
+                "save has schema version {}, but this version of simx only supports up to {}",
+                repr.schema_version, SCHEMA_VERSION
+            ));
+        }
+        let mut database = repr.database;
+        migrate(&mut database, repr.schema_version);
+        database.check_consistency()?;
+        Ok(Sim {
+            schema_version: CurrentVersion,
+            rng: repr.rng,
+            game_streams: repr.game_streams,
+            database,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SCHEMA_VERSION;
+    use crate::Sim;
+    use serde_json::json;
+
+    #[test]
+    fn unversioned() {
+        let sim: Sim = serde_json::from_value(json!({
+            "rng": Sim::seeded(1, 2).rng,
+            "season": 0,
+            "day": 0,
+            "teams": {},
+            "players": {},
+            "games_today": [],
+        }))
+        .unwrap();
+        let value = serde_json::to_value(&sim).unwrap();
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn unrostered_players_become_free_agents() {
+        let mut sim = crate::sim::tests::two_games();
+        let team = sim.games_today()[0].teams.home.id;
+        let shadow = sim.teams().get(&team).unwrap().shadows[0];
+        sim.release_player(shadow).unwrap();
+        let mut value = serde_json::to_value(&sim).unwrap();
+        value["schema_version"] = json!(1);
+        value.as_object_mut().unwrap().remove("free_agents");
+        let sim: Sim = serde_json::from_value(value).unwrap();
+        let free_agents = sim.free_agents();
+        assert_eq!(free_agents.len(), 1);
+        assert_eq!(free_agents[0].id, shadow);
+    }
+
+    #[test]
+    fn too_new() {
+        let mut value = serde_json::to_value(Sim::seeded(1, 2)).unwrap();
+        value["schema_version"] = json!(SCHEMA_VERSION + 1);
+        assert!(serde_json::from_value::<Sim>(value).is_err());
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn json_schema() {
+        let schema = schemars::schema_for!(Sim);
+        let object = schema.schema.object.unwrap();
+        assert!(object.properties.contains_key("schema_version"));
+        assert!(object.properties.contains_key("games_today"));
+    }
+}
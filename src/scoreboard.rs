@@ -0,0 +1,166 @@
+use crate::sim::{BALLS_NEEDED, OUTS_NEEDED, STRIKES_NEEDED};
+use crate::{AwayHome, Game, Inning, RngSource, Sim};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+/// A compact summary of a game's state, with names already looked up, for rendering a
+/// scoreboard. See [`Game::scoreboard`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Scoreboard {
+    /// Each team's shorthand, like "SEA".
+    pub teams: AwayHome<String>,
+    pub score: AwayHome<u16>,
+    /// Each team's runs in each inning it has batted in, starting from the first: the line
+    /// score. A home team that didn't need to bat in the last inning has one fewer entry than
+    /// the away team. The winning runs of a [forfeit](crate::Sim::forfeit_game) aren't scored
+    /// in any inning, so they're missing here but counted in `score`.
+    #[serde(default)]
+    pub innings: AwayHome<Vec<u16>>,
+    pub inning: Inning,
+    pub balls: u8,
+    pub strikes: u8,
+    pub outs: u8,
+    pub balls_needed: u8,
+    pub strikes_needed: u8,
+    pub outs_needed: u8,
+    /// Whether each base is occupied, starting from first base. This always covers every base
+    /// before home, and any base past that a runner is somehow standing on.
+    pub bases: Vec<bool>,
+    pub batter: Option<String>,
+    pub pitcher: Option<String>,
+}
+
+impl Game {
+    /// Summarize this game's state, looking up team and player names in `sim`.
+    pub fn scoreboard<R: RngSource>(&self, sim: &Sim<R>) -> Scoreboard {
+        let name = |id| sim.players().get(&id).map(|player| player.name.clone());
+        let occupied = self.bases_occupied();
+        let last_base = occupied
+            .last()
+            .copied()
+            .unwrap_or_default()
+            .max(self.home_base() - 1);
+        Scoreboard {
+            teams: self.teams.as_ref().map(|team| {
+                sim.teams()
+                    .get(&team.id)
+                    .map(|team| team.shorthand.clone())
+                    .unwrap_or_default()
+            }),
+            score: self.teams.as_ref().map(|team| team.runs),
+            innings: self.line_score(),
+            inning: self.inning,
+            balls: self.balls,
+            strikes: self.strikes,
+            outs: self.outs,
+            balls_needed: BALLS_NEEDED,
+            strikes_needed: STRIKES_NEEDED,
+            outs_needed: OUTS_NEEDED,
+            bases: (1..=last_base)
+                .map(|base| occupied.contains(&base))
+                .collect(),
+            batter: self.at_bat.and_then(name),
+            pitcher: self
+                .teams
+                .select(self.inning.fielding())
+                .pitcher
+                .and_then(name),
+        }
+    }
+
+    fn line_score(&self) -> AwayHome<Vec<u16>> {
+        let n = self.inning.number();
+        let home_innings = match self.inning {
+            Inning::Top(_) | Inning::Mid(_) => n.saturating_sub(1),
+            Inning::Bottom(_) | Inning::End(_) => n,
+        };
+        let mut innings = AwayHome {
+            away: vec![0; n],
+            home: vec![0; home_innings],
+        };
+        for play in &self.plays {
+            let i = play.inning.number().saturating_sub(1);
+            let team = innings.select_mut(play.scoring_team());
+            if team.len() <= i {
+                team.resize(i + 1, 0);
+            }
+            team[i] += play.runs;
+        }
+        innings
+    }
+}
+
+/// A one-line scoreboard, like `SEA 3 LOVE 1  ▲ 4  ◇◆◆  ●○○ ●○ ○○`.
+impl Display for Scoreboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}  ",
+            self.teams.away, self.score.away, self.teams.home, self.score.home
+        )?;
+        match self.inning {
+            Inning::Top(n) | Inning::Mid(n) => write!(f, "▲ {n}  ")?,
+            Inning::Bottom(n) | Inning::End(n) => write!(f, "▼ {n}  ")?,
+        }
+        for occupied in self.bases.iter().rev() {
+            f.write_str(if *occupied { "◆" } else { "◇" })?;
+        }
+        f.write_str(" ")?;
+        for (num, needed) in [
+            (self.balls, self.balls_needed),
+            (self.strikes, self.strikes_needed),
+            (self.outs, self.outs_needed),
+        ] {
+            f.write_str(" ")?;
+            for i in 0..needed.saturating_sub(1).max(num) {
+                f.write_str(if num > i { "●" } else { "○" })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AwayHome, Inning, TeamSelect};
+
+    #[test]
+    fn extra_innings() {
+        // every pitch is a called strike, so the only runs are the ones awarded
+        let mut sim = crate::sim::tests::scripted(
+            |label| if label == "strike" { 0.0 } else { 0.99 },
+            |game, _| game,
+        );
+        let game = sim.games_today()[0].clone();
+        for (team, shorthand) in [(game.teams.away.id, "AWY"), (game.teams.home.id, "HOM")] {
+            sim.update_team(team, |team| team.shorthand = shorthand.into())
+                .unwrap();
+        }
+        for (inning, team) in [
+            (Inning::Top(5), TeamSelect::Away),
+            (Inning::Bottom(7), TeamSelect::Home),
+            (Inning::Top(10), TeamSelect::Away),
+        ] {
+            sim.tick_until(|games| games[0].inning == inning);
+            sim.award_run(game.id, team).unwrap();
+        }
+        sim.finish_day();
+
+        let game = &sim.games_today()[0];
+        assert!(game.is_finished());
+        let scoreboard = game.scoreboard(&sim);
+        assert_eq!(scoreboard.score, AwayHome { away: 2, home: 1 });
+        assert_eq!(
+            scoreboard.innings,
+            AwayHome {
+                away: vec![0, 0, 0, 0, 1, 0, 0, 0, 0, 1],
+                home: vec![0, 0, 0, 0, 0, 0, 1, 0, 0, 0],
+            }
+        );
+        assert_eq!(scoreboard.innings.away.iter().sum::<u16>(), 2);
+        assert_eq!(scoreboard.innings.home.iter().sum::<u16>(), 1);
+        assert_eq!(scoreboard.to_string(), "AWY 2 HOM 1  ▼ 10  ◇◇◇  ○○○ ○○ ○○");
+    }
+}
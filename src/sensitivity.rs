@@ -0,0 +1,136 @@
+use crate::id::PlayerId;
+use crate::{DatabaseError, Play, PlayEvent, Player, RngSource, Sim};
+
+/// How plate appearances ended, as a share of all plate appearances.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[non_exhaustive]
+pub struct OutcomeRates {
+    pub plate_appearances: u32,
+    pub strikeouts: f64,
+    /// Walks, including hit-by-pitches.
+    pub walks: f64,
+    /// Hits, including home runs.
+    pub hits: f64,
+    pub home_runs: f64,
+    /// Outs on balls in play, including foul outs and bunts.
+    pub outs_in_play: f64,
+}
+
+impl OutcomeRates {
+    fn from_plays<'a>(plays: impl Iterator<Item = &'a Play>) -> OutcomeRates {
+        let (mut pa, mut k, mut bb, mut h, mut hr, mut out) = (0, 0, 0, 0, 0, 0);
+        for play in plays.filter(|play| play.event.ends_plate_appearance()) {
+            pa += 1;
+            match play.event {
+                PlayEvent::Strikeout { .. } => k += 1,
+                PlayEvent::Walk | PlayEvent::HitByPitch => bb += 1,
+                PlayEvent::Hit { .. } | PlayEvent::BuntHit => h += 1,
+                PlayEvent::HomeRun => {
+                    h += 1;
+                    hr += 1;
+                }
+                _ => out += 1,
+            }
+        }
+        let rate = |count: u32| f64::from(count) / f64::from(pa.max(1));
+        OutcomeRates {
+            plate_appearances: pa,
+            strikeouts: rate(k),
+            walks: rate(bb),
+            hits: rate(h),
+            home_runs: rate(hr),
+            outs_in_play: rate(out),
+        }
+    }
+}
+
+/// The outcome of one run of [`Sim::sensitivity`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct SensitivityPoint {
+    /// The attribute's value for this run.
+    pub value: f64,
+    /// Rates across every plate appearance in the run.
+    pub all: OutcomeRates,
+    /// Rates across the plate appearances where the player batted or pitched.
+    pub player: OutcomeRates,
+}
+
+impl<R: RngSource + Clone> Sim<R> {
+    /// Play out the rest of today's games once for each of `values`, with one of a player's
+    /// attributes set to that value by `set`, and report how the plate appearances ended. This
+    /// is meant for checking that a [formula](crate::Formulas) responds to an attribute the way
+    /// it should.
+    ///
+    /// Every run starts from a copy of this sim, including the state of its RNG, so the runs
+    /// roll the same sequence of numbers. Runs only diverge once the attribute changes the
+    /// result of a roll. This sim is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not in the database.
+    pub fn sensitivity<F>(
+        &self,
+        player: PlayerId,
+        values: &[f64],
+        set: F,
+    ) -> Result<Vec<SensitivityPoint>, DatabaseError>
+    where
+        F: Fn(&mut Player, f64),
+    {
+        values
+            .iter()
+            .map(|value| {
+                let mut sim = self.clone();
+                sim.update_player(player, |player| set(player, *value))?;
+                let before = sim
+                    .games_today()
+                    .iter()
+                    .map(|game| game.plays.len())
+                    .collect::<Vec<_>>();
+                sim.finish_day();
+                let plays = || {
+                    sim.games_today()
+                        .iter()
+                        .zip(&before)
+                        .flat_map(|(game, before)| &game.plays[*before..])
+                };
+                Ok(SensitivityPoint {
+                    value: *value,
+                    all: OutcomeRates::from_plays(plays()),
+                    player: OutcomeRates::from_plays(
+                        plays().filter(|play| play.batter == player || play.pitcher == player),
+                    ),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn sensitivity() {
+        let sim = crate::sim::tests::two_games();
+        let game = &sim.games_today()[0];
+        let team = sim.teams().get(&game.teams.home.id).unwrap();
+        let pitcher = team.probable_pitcher(sim.players(), 0).unwrap();
+        let before = sim.clone();
+        let sweep = sim
+            .sensitivity(pitcher, &[0.0, 0.0, 1.0], |player, value| {
+                player.ruthlessness = value;
+            })
+            .unwrap();
+        assert_eq!(sim, before);
+        assert_eq!(sweep.len(), 3);
+        // the same value with the same rolls gives the same results
+        assert_eq!(sweep[0].all, sweep[1].all);
+        assert!(sweep[0].player.plate_appearances > 0);
+        assert!(sweep[2].player.strikeouts > sweep[0].player.strikeouts);
+        assert!(sweep[2].all.plate_appearances >= sweep[2].player.plate_appearances);
+
+        assert!(sim
+            .sensitivity(crate::id::PlayerId::new(), &[0.0], |_, _| {})
+            .is_err());
+    }
+}
@@ -1,21 +1,46 @@
-use crate::id::PlayerId;
-use crate::{Ballpark, Database, Date, Game, Inning, Player, Rng, Sim, TeamSelect};
+use crate::commentary::Commentary;
+use crate::database::CheckEntity;
+use crate::fielding::BattedBall;
+use crate::id::{GameId, PlayerId, TeamId};
+use crate::play::Before;
+use crate::util::BaseDisplay;
+use crate::{
+    mods, Ballpark, Baserunner, Database, DatabaseError, Formulas, Game, Inning, MachinePolicy,
+    Mutation, Pitch, Play, PlayEvent, Player, Reached, RngSource, RosterPosition, RosterSlot, Sim,
+    TeamSelect, Weather,
+};
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt::Write;
 use std::ops::ControlFlow;
 
 // some consts, so we know what to fix when we start implementing modifications
-const BALLS_NEEDED: u8 = 4;
-const STRIKES_NEEDED: u8 = 3;
-const OUTS_NEEDED: u8 = 3;
-const HOME_BASE: u8 = 4;
+pub(crate) const BALLS_NEEDED: u8 = 4;
+pub(crate) const STRIKES_NEEDED: u8 = 3;
+pub(crate) const OUTS_NEEDED: u8 = 3;
+pub(crate) const HOME_BASE: u8 = 4;
 
 // some newtypes so i write fewer bugs
 struct Batter<'a>(&'a Player);
-struct Fielder<'a>(&'a Player);
-struct Pitcher<'a>(&'a Player);
+struct Fielder<'a>(Cow<'a, Player>);
 
-impl Sim {
+impl<R: RngSource> Sim<R> {
+    /// Advance each of today's active games by one play.
+    ///
+    /// # Ordering
+    ///
+    /// Ticking is deterministic: the same sim (down to its RNG state) makes the same rolls,
+    /// writes the same updates, and makes the same changes to the database in the same order.
+    /// Games tick one at a time in the order they were scheduled, and everything a game changes,
+    /// including its entries in the [audit log](Sim::audit_log), is applied before the next game
+    /// ticks.
+    ///
+    /// Games with their own RNG streams (see [`Sim::set_game_streams`]) don't depend on each
+    /// other's rolls, but they still see each other's changes to the database. Ticking them in
+    /// parallel has to keep this order, by applying each game's changes in schedule order once
+    /// every game has ticked.
     // TODO: Right now this returns nothing, but in the future I'd like it to return a batch of
     // events (think The Feed) that can get shoved into a database. (Databases are strictly outside
     // the scope of this crate.)
@@ -25,100 +50,502 @@ impl Sim {
         // still be able to roll the RNG.
         let rng = &mut self.rng;
         let database = &mut self.database;
+        database.tick += 1;
+        // If tracing is enabled, start a fresh trace for this tick.
+        rng.take_trace();
 
-        // To mutably borrow an individual game and the rest of the sim at the same time, we swap
-        // the game out of the sim (replacing it with a default nil game), run `Game::tick`, and
-        // then swap the game back into the sim.
         for i in 0..database.games_today.len() {
-            if !database.games_today[i].is_finished() {
-                let mut game = std::mem::take(&mut database.games_today[i]);
-                game.last_update = into_update(game.tick(rng, database));
-                database.games_today[i] = game;
+            if database.games_today[i].is_active() && !database.is_waiting(i) {
+                tick_game(rng, database, i);
             }
         }
 
-        // In debug mode (dev/test profiles), panic if we've introduced a database consistency
-        // problem.
-        database.debug_check();
+        // Depending on the check policy (by default, in dev/test profiles), panic if we've
+        // introduced a database consistency problem.
+        database.check_after_tick();
+    }
+
+    /// Like [`Sim::tick`], but instead of panicking on a game that can't be ticked (for example,
+    /// one that refers to a player who has since been removed), mark that game suspended (see
+    /// [`Game::suspended`]) and tick the rest.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SimError::Suspended`] for the first game suspended during this tick, or
+    /// [`SimError::Database`] if the check policy calls for a consistency check after the tick
+    /// and the check fails.
+    pub fn try_tick(&mut self) -> Result<(), SimError> {
+        let rng = &mut self.rng;
+        let database = &mut self.database;
+        database.tick += 1;
+        rng.take_trace();
+
+        let mut result = Ok(());
+        for i in 0..database.games_today.len() {
+            let game = &database.games_today[i];
+            if !game.is_active() || database.is_waiting(i) {
+                continue;
+            }
+            match game.check_tickable(database) {
+                Ok(()) => tick_game(rng, database, i),
+                Err(source) => {
+                    let game = &mut database.games_today[i];
+                    game.suspended = Some(source.to_string());
+                    let game = game.id;
+                    database.record(Mutation::GameSuspended {
+                        game,
+                        reason: source.to_string(),
+                    });
+                    if result.is_ok() {
+                        result = Err(SimError::Suspended { game, source });
+                    }
+                }
+            }
+        }
+        result?;
+        database.try_check_after_tick()?;
+        Ok(())
+    }
+
+    /// Tick until `done` returns true for today's games (checked before each tick), or until
+    /// every game is finished or suspended. Returns the number of ticks.
+    ///
+    /// This is equivalent to calling [`Sim::tick`] in a loop, but cheaper: finished games are
+    /// dropped from consideration as they end, and the consistency check (see [`CheckPolicy`])
+    /// runs once at the end instead of after every tick.
+    ///
+    /// [`CheckPolicy`]: crate::CheckPolicy
+    pub fn tick_until<F>(&mut self, mut done: F) -> u64
+    where
+        F: FnMut(&[Game]) -> bool,
+    {
+        let rng = &mut self.rng;
+        let database = &mut self.database;
+        let mut active = (0..database.games_today.len())
+            .filter(|i| database.games_today[*i].is_active())
+            .collect::<Vec<_>>();
+        let mut ticks = 0;
+        while !active.is_empty() && !done(&database.games_today) {
+            database.tick += 1;
+            ticks += 1;
+            rng.take_trace();
+            for i in &active {
+                if !database.is_waiting(*i) {
+                    tick_game(rng, database, *i);
+                }
+            }
+            active.retain(|i| database.games_today[*i].is_active());
+        }
+        database.check_after_tick();
+        ticks
+    }
+
+    /// Tick until every one of today's games is finished. Returns the number of ticks.
+    ///
+    /// See [`Sim::tick_until`].
+    pub fn finish_day(&mut self) -> u64 {
+        self.tick_until(|_| false)
     }
 }
 
-enum Never {}
+impl Database {
+    /// Whether one of today's games is waiting on an earlier game between either of its teams,
+    /// as in the second game of a doubleheader.
+    fn is_waiting(&self, i: usize) -> bool {
+        let teams = &self.games_today[i].teams;
+        self.games_today[..i].iter().any(|game| {
+            game.is_active()
+                && game
+                    .teams
+                    .iter()
+                    .any(|team| teams.iter().any(|other| other.id == team.id))
+        })
+    }
+}
 
-fn into_update(c: ControlFlow<String, Never>) -> String {
-    match c {
+fn tick_game(rng: &mut impl RngSource, database: &mut Database, i: usize) {
+    // To mutably borrow an individual game and the rest of the sim at the same time, we swap
+    // the game out of the sim (replacing it with a default nil game), run `Game::tick`, and
+    // then swap the game back into the sim.
+    let mut game = std::mem::take(&mut database.games_today[i]);
+    game.ticks += 1;
+    // Reuse the previous update's allocation for this one.
+    let mut update = std::mem::take(&mut game.last_update);
+    update.clear();
+    let flow = if let Some(mut game_rng) = game.rng.take() {
+        let flow = game.tick(&mut game_rng, database, &mut update);
+        game.rng = Some(game_rng);
+        flow
+    } else {
+        game.tick(rng, database, &mut update)
+    };
+    match flow {
         ControlFlow::Continue(nothing) => match nothing {},
-        ControlFlow::Break(update) => update,
+        ControlFlow::Break(()) => {}
     }
+    game.last_update = update;
+    database.games_today[i] = game;
+}
+
+/// An error from [`Sim::try_tick`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum SimError {
+    #[error("game {game} was suspended: {source}")]
+    Suspended { game: GameId, source: DatabaseError },
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+enum Never {}
+
+// Each tick writes its update into a buffer and breaks out of the tick. Pitches also break with
+// the structured event.
+type Outcome = ControlFlow<PlayEvent, Never>;
+
+/// How many runs a pitcher gives up before a [Fax Machine](mods::FAX_MACHINE) swaps them out.
+const FAX_MACHINE_RUNS: u16 = 10;
+
+macro_rules! update {
+    ($out:expr, $($arg:tt)*) => {
+        write!($out, $($arg)*).expect("std::fmt::Write does not fail on String")
+    };
+}
+
+/// Give a batter hit by a pitch the mark of the pitcher's debt, if they don't already have it.
+fn mark_batter(database: &mut Database, pitcher: PlayerId, batter: PlayerId, out: &mut String) {
+    let pitcher_data = pitcher.load(database);
+    let (mark, word) = if pitcher_data.has_mod(mods::DEBT) {
+        (mods::UNSTABLE, "Unstable")
+    } else if pitcher_data.has_mod(mods::REFINANCED) {
+        (mods::OBSERVED, "Observed")
+    } else {
+        return;
+    };
+    let player = batter.load_mut(database);
+    if player.has_mod(mark) {
+        return;
+    }
+    player.mods.push(mark.to_owned());
+    player.marked_by = Some(pitcher);
+    update!(out, " {} is now {}.", player.name, word);
+    database.record(Mutation::PlayerModAdded {
+        player: batter,
+        name: mark.to_owned(),
+        source: Some(pitcher),
+    });
+}
+
+/// If a team has [Party Time](mods::PARTY_TIME), maybe pick one of its players to party,
+/// permanently boosting all of their attributes.
+fn party(rng: &mut impl RngSource, database: &mut Database, team: TeamId, out: &mut String) {
+    let team_data = team.load(database);
+    if !team_data.has_mod(mods::PARTY_TIME)
+        || !rng.roll("party", database.formulas.0.party(team_data))
+    {
+        return;
+    }
+    let roster = team_data.roster().copied().collect::<Vec<_>>();
+    let Some(player) = rng.choose(roster) else {
+        return;
+    };
+    let amount = rng.next_range(0.04, 0.08);
+    database.boost_player(player, amount);
+    update!(out, " {} is Partying!", player.load(database).name);
+}
+
+/// Roll against a formula's threshold, recording the terms it reported (see [`Pitch::term`]) in
+/// the trace. Terms left over from formulas that weren't rolled against are dropped first.
+fn roll_formula<'a>(
+    rng: &mut impl RngSource,
+    label: &'static str,
+    pitch: &Pitch<'a>,
+    threshold: impl FnOnce(&Pitch<'a>) -> f64,
+) -> bool {
+    pitch.take_terms();
+    let outcome = rng.roll(label, threshold(pitch));
+    let terms = pitch.take_terms();
+    if !terms.is_empty() {
+        rng.explain(terms);
+    }
+    outcome
+}
+
+/// Roll how a ball in play left the bat, weighted by [`Formulas::batted_ball`].
+fn roll_batted_ball(
+    rng: &mut impl RngSource,
+    formulas: &dyn Formulas,
+    pitch: &Pitch<'_>,
+) -> BattedBall {
+    let weights = BattedBall::ALL.map(|ball| formulas.batted_ball(pitch, ball).max(0.0));
+    let mut roll = rng.next_labeled("batted ball") * weights.iter().sum::<f64>();
+    BattedBall::ALL
+        .into_iter()
+        .zip(weights)
+        .find(|(_, weight)| {
+            roll -= weight;
+            roll < 0.0
+        })
+        .map_or(BattedBall::Grounder, |(ball, _)| ball)
 }
 
 impl Game {
-    fn tick(&mut self, rng: &mut Rng, database: &mut Database) -> ControlFlow<String, Never> {
-        self.handle_game_over(database)?;
+    /// Check everything `Game::tick` would otherwise `expect`: that every team and player the
+    /// game refers to exists, and that both teams have someone to field (unless the
+    /// [`MachinePolicy`] has a team without anyone forfeit). Under [`MachinePolicy::Error`], also
+    /// check that the game doesn't need a Machine.
+    pub(crate) fn check_tickable(&self, database: &Database) -> Result<(), DatabaseError> {
+        self.check(database)?;
+        for data in self.teams.iter() {
+            let team = data.id.try_load(database)?;
+            team.check(database)?;
+            if team.lineup.is_empty() && database.machine_policy != MachinePolicy::Forfeit {
+                return Err(DatabaseError::RosterTooSmall {
+                    team: team.id,
+                    position: RosterPosition::Lineup,
+                    minimum: 1,
+                });
+            }
+        }
+        if database.machine_policy == MachinePolicy::Error {
+            if let Some((select, position)) = self.needs_machine(database) {
+                return Err(DatabaseError::RosterTooSmall {
+                    team: self.teams.select(select).id,
+                    position,
+                    minimum: 1,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The team that would need a Machine generated to play the next pitch, and where on its
+    /// roster, if one would. A team with nobody in its lineup to field counts, too.
+    fn needs_machine(&self, database: &Database) -> Option<(TeamSelect, RosterPosition)> {
+        if self.inning == Inning::default()
+            || matches!(self.inning, Inning::Mid(_) | Inning::End(_))
+        {
+            return None;
+        }
+        let (fielding, batting) = (self.inning.fielding(), self.inning.batting());
+        let fielding_team = self.teams.select(fielding);
+        let lineup_empty = |select: TeamSelect| {
+            let team = self.teams.select(select).id.load(database);
+            team.lineup.is_empty()
+        };
+        if fielding_team.pitcher.is_none() && database.needs_pitching_machine(fielding_team.id) {
+            Some((fielding, RosterPosition::Rotation))
+        } else if lineup_empty(fielding) {
+            Some((fielding, RosterPosition::Lineup))
+        } else if self.at_bat.is_none() && lineup_empty(batting) {
+            Some((batting, RosterPosition::Lineup))
+        } else {
+            None
+        }
+    }
+
+    /// Apply the [`MachinePolicy`] when the next pitch would need a Machine that the policy
+    /// doesn't allow generating, taking up the tick.
+    fn enforce_machine_policy(
+        &mut self,
+        database: &mut Database,
+        out: &mut String,
+    ) -> ControlFlow<()> {
+        if database.machine_policy == MachinePolicy::AutoGenerate {
+            return ControlFlow::Continue(());
+        }
+        let Some((select, position)) = self.needs_machine(database) else {
+            return ControlFlow::Continue(());
+        };
+        let error = DatabaseError::RosterTooSmall {
+            team: self.teams.select(select).id,
+            position,
+            minimum: 1,
+        };
+        if database.machine_policy == MachinePolicy::Forfeit {
+            self.forfeit(database, select, &error.to_string(), out);
+        } else {
+            update!(out, "Game suspended: {error}");
+            self.suspended = Some(error.to_string());
+            database.record(Mutation::GameSuspended {
+                game: self.id,
+                reason: error.to_string(),
+            });
+        }
+        ControlFlow::Break(())
+    }
+
+    fn tick(
+        &mut self,
+        rng: &mut impl RngSource,
+        database: &mut Database,
+        out: &mut String,
+    ) -> ControlFlow<(), Never> {
+        self.handle_game_over(database, out)?;
         if self.inning == Inning::default() {
             self.inning = Inning::End(0);
-            return ControlFlow::Break("Play ball!".into());
+            out.push_str("Play ball!");
+            if let Some(attendance) = self.attendance {
+                update!(out, " {attendance} fans are in attendance.");
+            }
+            return ControlFlow::Break(());
         }
         if matches!(self.inning, Inning::Mid(_) | Inning::End(_)) {
             self.inning.advance();
-            return ControlFlow::Break(format!(
-                "{} of {}, {} batting.",
+            let team = self.teams.select(self.inning.batting()).id.load(database);
+            update!(
+                out,
+                "{} of {}, {} {} batting.",
                 self.inning.word(),
                 self.inning.number(),
-                self.teams
-                    .select(self.inning.batting())
-                    .id
-                    .load(database)
-                    .name(),
-            ));
+                team.location,
+                team.nickname,
+            );
+            return ControlFlow::Break(());
         }
 
+        self.enforce_machine_policy(database, out)?;
+        self.fax_machine(rng, database, out)?;
         let pitcher = self.get_pitcher(rng, database);
-        let batter = self.get_batter(rng, database)?;
-        let pitcher = Pitcher(pitcher.load(database));
-        let batter = Batter(batter.load(database));
+        let batter = self.get_batter(rng, database, out)?;
+        let before = Before::new(self);
+        let batting = self.inning.batting();
+        let lineup_slot = self.teams.select(batting).lineup_slot;
+        let event = match self.pitch(rng, database, pitcher, batter, out) {
+            ControlFlow::Continue(nothing) => match nothing {},
+            ControlFlow::Break(event) => event,
+        };
+        if event == PlayEvent::HitByPitch {
+            mark_batter(database, pitcher, batter, out);
+        }
+        if event.ends_plate_appearance() {
+            let batter = batter.load(database);
+            let repeats = batter.has_mod(mods::REVERBERATING)
+                || (self.weather == Weather::Reverb && batter.has_mod(mods::REPEATING));
+            if repeats && rng.roll("reverberating", database.formulas.0.reverberate(batter)) {
+                // put the lineup back where it was, so this batter is up next
+                self.teams.select_mut(batting).lineup_slot = lineup_slot;
+                update!(out, " {} is Reverberating!", batter.name);
+            }
+            party(rng, database, self.teams.select(batting).id, out);
+        }
+        self.update_forced();
+        let batted_ball = self.batted_ball.take();
+        let pitch = self.pitch.take();
+        // the update buffer is reused next tick, so a play that keeps its description needs its
+        // own copy
+        let description = if database.skip_descriptions {
+            String::new()
+        } else {
+            out.clone()
+        };
+        self.plays.push(Play {
+            batted_ball,
+            pitch,
+            ..before.into_play(self, batter, pitcher, event, description)
+        });
+        ControlFlow::Break(())
+    }
 
-        self.handle_steal(rng, database)?;
-        let strike = roll_strike(rng, database.date, &pitcher, &batter);
-        if !roll_swing(rng, database.date, &pitcher, &batter, strike) {
+    fn pitch(
+        &mut self,
+        rng: &mut impl RngSource,
+        database: &Database,
+        pitcher: PlayerId,
+        batter: PlayerId,
+        out: &mut String,
+    ) -> Outcome {
+        let pitcher = self.effective(database, pitcher, self.inning.fielding());
+        let pitcher = &*pitcher;
+        let batter = self.effective(database, batter, self.inning.batting());
+        let batter = Batter(&batter);
+
+        self.handle_steal(rng, database, out)?;
+        let ballpark = Ballpark::default(); // TODO
+        let formulas = &*database.formulas.0;
+        let baserunners = self.baserunners.clone();
+        let terms = RefCell::new(Vec::new());
+        let pitch = Pitch {
+            date: database.date,
+            pitcher,
+            batter: batter.0,
+            ballpark: &ballpark,
+            balls: self.balls,
+            strikes: self.strikes,
+            outs: self.outs,
+            baserunners: &baserunners,
+            home_advantage: match self.inning.batting() {
+                TeamSelect::Away => -(database.home_advantage + self.home_pitcher_hype(database)),
+                TeamSelect::Home => database.home_advantage,
+            },
+            batted_ball: None,
+            terms: rng.trace().is_some().then_some(&terms),
+        };
+        let mut commentary = self.start_pitch(pitcher.id);
+
+        if (pitcher.has_mod(mods::DEBT) || pitcher.has_mod(mods::REFINANCED))
+            && roll_formula(rng, "debt", &pitch, |pitch| formulas.bean(pitch))
+        {
+            return self.handle_hit_by_pitch(pitcher, &batter, out);
+        }
+        let strike = roll_formula(rng, "strike", &pitch, |pitch| formulas.strike(pitch));
+        if !roll_formula(rng, "swing", &pitch, |pitch| formulas.swing(pitch, strike)) {
+            if let Some(detail) = &mut self.pitch {
+                commentary.locate(detail, strike);
+            }
             return if strike {
-                self.handle_strike(&batter, "looking")
+                self.handle_strike(&batter, "looking", out)
             } else {
-                self.handle_ball(&batter, database)
+                self.handle_ball(&batter, out)
             };
         }
-        if !roll_contact(rng, database.date, &pitcher, &batter, strike) {
-            return self.handle_strike(&batter, "swinging");
+        // formulas are pure, so asking again for the roll just gets the bunt's terms back
+        if formulas.bunt(&pitch) > 0.0
+            && roll_formula(rng, "bunt", &pitch, |pitch| formulas.bunt(pitch))
+        {
+            return self.handle_bunt(rng, database, &pitch, &batter, out);
         }
-        if roll_foul(rng, database.date, &batter) {
-            self.strikes = 2.min(self.strikes + 1);
-            return ControlFlow::Break(format!("Foul Ball. {}-{}", self.balls, self.strikes));
+        if !roll_formula(rng, "contact", &pitch, |pitch| {
+            formulas.contact(pitch, strike)
+        }) {
+            return self.handle_strike(&batter, "swinging", out);
         }
-        let fielder = self.roll_fielder(rng, database);
-        if roll_out(rng, database.date, &pitcher, &fielder, &batter) {
-            // TODO: double play / fielder's choice
-            let kind = if roll_flyout(rng, &batter) {
-                "flyout"
-            } else {
-                // TODO: ground out advances
-                "ground out"
-            };
-            self.clear_batter();
-            self.handle_out();
-            return ControlFlow::Break(format!(
-                "{} hit a {} to {}.",
-                batter.0.name, kind, fielder.0.name
-            ));
+        if roll_formula(rng, "foul", &pitch, |pitch| formulas.foul(pitch)) {
+            return self.handle_foul(rng, database, &pitch, &batter, out);
         }
-        if roll_home_run(rng, database.date, &pitcher, &batter) {
-            return self.handle_home_run(&batter);
+        let batted_ball = roll_batted_ball(rng, formulas, &pitch);
+        self.batted_ball = Some(batted_ball);
+        let pitch = Pitch {
+            batted_ball: Some(batted_ball),
+            ..pitch
+        };
+        let fielder = self.roll_fielder(rng, database, Some(batted_ball));
+        if roll_formula(rng, "out", &pitch, |pitch| formulas.out(pitch, &fielder.0)) {
+            return self.handle_fielded_out(rng, database, &pitch, &batter, &fielder, out);
         }
-        let defender = self.roll_fielder(rng, database);
-        self.handle_base_hit(
-            &batter,
-            database,
-            roll_base_hit(rng, database.date, &pitcher, &defender, &batter),
-        )
+        if roll_formula(rng, "home run", &pitch, |pitch| formulas.home_run(pitch)) {
+            return self.handle_home_run(&batter, out);
+        }
+        let defender = self.roll_fielder(rng, database, Some(batted_ball));
+        let triple = roll_formula(rng, "triple", &pitch, |pitch| {
+            formulas.triple(pitch, &defender.0)
+        });
+        let double = roll_formula(rng, "double", &pitch, |pitch| {
+            formulas.double(pitch, &defender.0)
+        });
+        // TODO: Unsure which order these are checked in.
+        let bases = if triple {
+            3
+        } else if double {
+            2
+        } else {
+            1
+        };
+        self.handle_base_hit(&batter, database, bases, out, |runner| {
+            roll_formula(rng, "advance", &pitch, |pitch| {
+                formulas.advance_on_hit(pitch, &defender.0, runner)
+            })
+        })
     }
 }
 
@@ -130,6 +557,7 @@ macro_rules! next_in_order {
         data = $data:expr,
         pos = $pos:expr,
         field = $team_field:ident,
+        position = $position:expr,
         new_name = $new_name:expr,
     ) => {
         if let Some(current) = $current {
@@ -150,7 +578,17 @@ macro_rules! next_in_order {
                 let player = Player::generate_with_name($rng, $new_name.to_string());
                 let player_id = player.id;
                 $db.players.insert(player_id, player);
+                $db.record(Mutation::PlayerAdded { player: player_id });
+                let index = data.id.load($db).$team_field.len();
                 data.id.load_mut($db).$team_field.push(player_id);
+                $db.record(Mutation::RosterAdded {
+                    team: data.id,
+                    player: player_id,
+                    slot: RosterSlot {
+                        position: $position,
+                        index,
+                    },
+                });
                 player_id
             };
 
@@ -171,30 +609,92 @@ macro_rules! next_in_order {
 }
 
 impl Game {
-    fn get_pitcher(&mut self, rng: &mut Rng, database: &mut Database) -> PlayerId {
-        match next_in_order!(
-            rng = rng,
-            database = database,
-            current = self.teams.select_mut(self.inning.fielding()).pitcher,
-            data = self.teams.select_mut(self.inning.fielding()),
-            pos = self
-                .teams
-                .select_mut(self.inning.fielding())
-                .id
-                .load_mut(database)
-                .rotation_slot,
-            field = rotation,
-            new_name = "Pitching Machine",
-        ) {
-            ControlFlow::Continue(player) | ControlFlow::Break(player) => player,
+    fn get_pitcher(&mut self, rng: &mut impl RngSource, database: &mut Database) -> PlayerId {
+        let data = self.teams.select_mut(self.inning.fielding());
+        if let Some(pitcher) = data.pitcher {
+            return pitcher;
+        }
+        let pitcher = database.starting_pitcher(rng, data.id);
+        data.pitcher = Some(pitcher);
+        pitcher
+    }
+
+    /// If the home team has a [Fax Machine](mods::FAX_MACHINE) and their pitcher has given up
+    /// [`FAX_MACHINE_RUNS`] in this game, swap the pitcher with a random shadows player, taking
+    /// up the tick.
+    fn fax_machine(
+        &mut self,
+        rng: &mut impl RngSource,
+        database: &mut Database,
+        out: &mut String,
+    ) -> ControlFlow<()> {
+        let team = self.teams.home.id;
+        let Some(pitcher) = self.teams.home.pitcher else {
+            return ControlFlow::Continue(());
+        };
+        if self.inning.fielding() != TeamSelect::Home
+            || !team.load(database).has_mod(mods::FAX_MACHINE)
+        {
+            return ControlFlow::Continue(());
+        }
+        let allowed = self
+            .plays
+            .iter()
+            .filter(|play| play.pitcher == pitcher)
+            .map(|play| play.runs)
+            .sum::<u16>();
+        if allowed < FAX_MACHINE_RUNS {
+            return ControlFlow::Continue(());
+        }
+
+        let team_data = team.load(database);
+        let Some(slot) = team_data
+            .slot_of(pitcher)
+            .filter(|slot| slot.position == RosterPosition::Rotation)
+        else {
+            return ControlFlow::Continue(());
+        };
+        let shadows = (0..team_data.shadows.len()).collect::<Vec<_>>();
+        let Some(index) = rng.choose(shadows) else {
+            return ControlFlow::Continue(());
+        };
+        let replacement = team_data.shadows[index];
+        let shadow = RosterSlot {
+            position: RosterPosition::Shadows,
+            index,
+        };
+        if database.trade(team, slot, team, shadow).is_err() {
+            return ControlFlow::Continue(());
+        }
+        database.record_trade(team, slot, team, shadow);
+        self.teams.home.pitcher = Some(replacement);
+        update!(
+            out,
+            "The Fax Machine activates! {} is faxed out, and {} takes the mound for the {}.",
+            pitcher.load(database).name,
+            replacement.load(database).name,
+            team.load(database).nickname,
+        );
+        ControlFlow::Break(())
+    }
+
+    /// The extra advantage the home team's pitchers get from their ballpark's hype, under
+    /// [`GameRules::home_pitcher_hype`](crate::GameRules::home_pitcher_hype).
+    fn home_pitcher_hype(&self, database: &Database) -> f64 {
+        if self.rules.home_pitcher_hype == 0.0 {
+            return 0.0;
         }
+        database
+            .ballpark_of(self.teams.home.id)
+            .map_or(0.0, |ballpark| ballpark.hype * self.rules.home_pitcher_hype)
     }
 
     fn get_batter(
         &mut self,
-        rng: &mut Rng,
+        rng: &mut impl RngSource,
         database: &mut Database,
-    ) -> ControlFlow<String, PlayerId> {
+        out: &mut String,
+    ) -> ControlFlow<(), PlayerId> {
         match next_in_order!(
             rng = rng,
             database = database,
@@ -202,85 +702,204 @@ impl Game {
             data = self.teams.select_mut(self.inning.batting()),
             pos = self.teams.select_mut(self.inning.batting()).lineup_slot,
             field = lineup,
+            position = RosterPosition::Lineup,
             new_name = "Batting Machine",
         ) {
             ControlFlow::Continue(player) => ControlFlow::Continue(player),
-            ControlFlow::Break(player) => ControlFlow::Break(format!(
-                "{} batting for the {}.",
-                player.load(database).name,
-                self.teams
-                    .select(self.inning.batting())
-                    .id
-                    .load(database)
-                    .nickname,
-            )),
+            ControlFlow::Break(player) => {
+                update!(
+                    out,
+                    "{} batting for the {}.",
+                    player.load(database).name,
+                    self.teams
+                        .select(self.inning.batting())
+                        .id
+                        .load(database)
+                        .nickname,
+                );
+                ControlFlow::Break(())
+            }
         }
     }
 
-    fn handle_game_over(&mut self, database: &mut Database) -> ControlFlow<String> {
+    fn handle_game_over(&mut self, database: &mut Database, out: &mut String) -> ControlFlow<()> {
         let winner = match (self.inning, self.teams.away.runs.cmp(&self.teams.home.runs)) {
-            (Inning::Mid(n) | Inning::End(n), Ordering::Less) if n >= 9 => TeamSelect::Home,
-            (Inning::End(n), Ordering::Greater) if n >= 9 => TeamSelect::Away,
+            (Inning::Mid(n) | Inning::End(n), Ordering::Less) if n >= 9 => Some(TeamSelect::Home),
+            (Inning::End(n), Ordering::Greater) if n >= 9 => Some(TeamSelect::Away),
+            (Inning::End(n), Ordering::Equal)
+                if n >= 9 && self.rules.max_innings.is_some_and(|max| n >= max) =>
+            {
+                None
+            }
             _ => return ControlFlow::Continue(()),
         };
-        self.winner = Some(self.teams.select(winner).id);
+        self.finish(database, winner, out);
+        if let Some(outcome) = &mut self.outcome {
+            outcome.shamed = matches!(self.inning, Inning::Mid(_));
+        }
+        ControlFlow::Break(())
+    }
+
+    /// Give `team` a run from outside the sim, recording it as a play, then end the game if the
+    /// run decides it, as the next tick would have.
+    pub(crate) fn award_run(
+        &mut self,
+        database: &mut Database,
+        team: TeamSelect,
+        out: &mut String,
+    ) {
+        let before = Before::new(self);
+        self.teams.select_mut(team).runs += 1;
+        let nickname = &self.teams.select(team).id.load(database).nickname;
+        update!(out, "The {nickname} are awarded a run.");
+        let description = if database.skip_descriptions {
+            String::new()
+        } else {
+            out.clone()
+        };
+        let event = PlayEvent::AwardedRun { team };
+        let nobody = PlayerId::default();
+        self.plays.push(Play {
+            runs: 1,
+            inherited_runners: BTreeMap::new(),
+            ..before.into_play(self, nobody, nobody, event, description)
+        });
+
+        let mut game_over = String::new();
+        if self.handle_game_over(database, &mut game_over).is_break() {
+            update!(out, " {game_over}");
+        }
+    }
+
+    /// End the game with `winner` as the winner (or in a tie, if `None`), advancing both teams'
+    /// rotations.
+    pub(crate) fn finish(
+        &mut self,
+        database: &mut Database,
+        winner: Option<TeamSelect>,
+        out: &mut String,
+    ) {
+        self.winner = winner.map(|winner| self.teams.select(winner).id);
+        self.outcome = Some(self.outcome(false));
+        let ballpark = database
+            .ballpark_of(self.teams.home.id)
+            .map(|ballpark| ballpark.id);
+        database.season_stats_mut().record_game_at(self, ballpark);
+        database.update_ratings(self);
         for team in self.teams.iter() {
-            team.id.load_mut(database).rotation_slot += 1;
+            database.advance_rotation(team.id);
         }
-        ControlFlow::Break(format!(
+        update!(
+            out,
             "Game over. {} {}, {} {}",
             self.teams.away.id.load(database).nickname,
             self.teams.away.runs,
             self.teams.home.id.load(database).nickname,
             self.teams.home.runs
-        ))
-    }
-
-    fn roll_fielder<'a>(&mut self, rng: &mut Rng, database: &'a Database) -> Fielder<'a> {
-        Fielder(
-            rng.choose(
-                self.teams
-                    .select(self.inning.fielding())
-                    .id
-                    .load(database)
-                    .lineup
-                    .clone(),
-            )
-            .expect("lineup was empty")
-            .load(database),
+        );
+    }
+
+    /// Pick the fielder who makes a play. For a team with fielding positions, that's one of the
+    /// players whose position goes after the ball, weighted by [`Formulas::fielder_range`];
+    /// otherwise, or if nobody in the lineup plays there, anyone in the lineup.
+    fn roll_fielder<'a>(
+        &mut self,
+        rng: &mut impl RngSource,
+        database: &'a Database,
+        ball: Option<BattedBall>,
+    ) -> Fielder<'a> {
+        let fielding = self.inning.fielding();
+        let team = self.teams.select(fielding).id.load(database);
+        let candidates = ball
+            .filter(|_| team.has_positions())
+            .map(|ball| {
+                team.lineup
+                    .iter()
+                    .filter_map(|player| Some((*player, team.position(*player)?)))
+                    .filter(|(_, position)| position.fields(ball))
+                    .map(|(player, position)| {
+                        let effective = self.effective(database, player, fielding);
+                        let range = database.formulas.0.fielder_range(&effective, position);
+                        (player, range.max(0.0))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let total = candidates.iter().map(|(_, range)| range).sum::<f64>();
+        let fielder = if total > 0.0 {
+            let mut roll = rng.next_labeled("fielder") * total;
+            candidates
+                .iter()
+                .find(|(_, range)| {
+                    roll -= range;
+                    roll < 0.0
+                })
+                .or(candidates.last())
+                .map(|(player, _)| *player)
+                .expect("candidates were empty")
+        } else {
+            *rng.choose_labeled("fielder", team.lineup.iter())
+                .expect("lineup was empty")
+        };
+        Fielder(self.effective(database, fielder, fielding))
+    }
+
+    /// A player's attributes as they play for one of the teams in this game today.
+    fn effective<'a>(
+        &self,
+        database: &'a Database,
+        player: PlayerId,
+        team: TeamSelect,
+    ) -> Cow<'a, Player> {
+        mods::effective(
+            player.load(database),
+            self.teams.select(team).id.load(database),
+            database.date,
+            database.season_config,
         )
     }
 
-    fn handle_steal(&mut self, rng: &mut Rng, database: &Database) -> ControlFlow<String> {
-        let _fielder = self.roll_fielder(rng, database);
+    fn handle_steal(
+        &mut self,
+        rng: &mut impl RngSource,
+        database: &Database,
+        out: &mut String,
+    ) -> ControlFlow<PlayEvent> {
+        let _fielder = self.roll_fielder(rng, database, None);
         let occupied = self.bases_occupied();
-        let mut event: Option<String> = None;
-        for (runner, base) in std::mem::take(&mut self.baserunners) {
+        let mut event: Option<PlayEvent> = None;
+        for baserunner in std::mem::take(&mut self.baserunners) {
+            let base = baserunner.base;
             if event.is_some() || occupied.contains(&(base + 1)) {
                 //   ↑                 ⬑ can't steal an occupied base
                 // a steal already happened
-                self.baserunners.push((runner, base));
+                self.baserunners.push(baserunner);
             } else {
-                let runner = runner.load(database);
+                let runner = baserunner.player.load(database);
 
                 // TODO: get steal attempt formula in here
                 let attempt_threshold = 0.02;
-                if rng.next_f64() < attempt_threshold {
-                    let display = crate::util::BaseDisplay {
-                        base: base + 1,
-                        home: HOME_BASE,
-                    };
+                if rng.roll("steal attempt", attempt_threshold) {
+                    let display = BaseDisplay::new(base + 1, self.home_base());
                     // TODO: get steal success formula in here
                     let success_threshold = 0.5;
-                    if rng.next_f64() < success_threshold {
-                        event = Some(format!("{} steals {}!", runner.name, display));
+                    if rng.roll("steal success", success_threshold) {
+                        event = Some(PlayEvent::StolenBase {
+                            runner: runner.id,
+                            base: base + 1,
+                        });
+                        update!(out, "{} steals {}!", runner.name, display);
                         if base + 1 >= HOME_BASE {
                             self.teams.select_mut(self.inning.batting()).runs += 1;
                         } else {
-                            self.baserunners.push((runner.id, base + 1));
+                            self.baserunners.push(baserunner.to(base + 1));
                         }
                     } else {
-                        event = Some(format!("{} gets caught stealing {}.", runner.name, display));
+                        event = Some(PlayEvent::CaughtStealing {
+                            runner: runner.id,
+                            base: base + 1,
+                        });
+                        update!(out, "{} gets caught stealing {}.", runner.name, display);
                         if self.handle_out() {
                             // This was the last out of the half-inning, and baserunners should be
                             // cleared. Return early to avoid adding them.
@@ -288,7 +907,7 @@ impl Game {
                         }
                     }
                 } else {
-                    self.baserunners.push((runner.id, base));
+                    self.baserunners.push(baserunner);
                 }
             }
         }
@@ -302,6 +921,7 @@ impl Game {
     fn clear_batter(&mut self) {
         self.balls = 0;
         self.strikes = 0;
+        self.pitches = 0;
         if self.at_bat.take().is_some() {
             self.teams.select_mut(self.inning.batting()).lineup_slot += 1;
         }
@@ -312,6 +932,7 @@ impl Game {
         if self.outs >= OUTS_NEEDED {
             self.balls = 0;
             self.strikes = 0;
+            self.pitches = 0;
             self.outs = 0;
 
             // don't call `clear_batter` since that advances the lineup order. if that needs to be
@@ -326,33 +947,75 @@ impl Game {
         }
     }
 
-    fn handle_ball(
+    /// Put the batter on first, moving up any runners who are forced to advance.
+    fn force_to_first(&mut self, batter: PlayerId, reached: Reached) {
+        // work this out from where the runners are, rather than trusting `Baserunner::forced`,
+        // which could be stale in an edited game or an old save
+        let occupied = self.bases_occupied();
+        for runner in std::mem::take(&mut self.baserunners) {
+            let base = if (1..runner.base).all(|base| occupied.contains(&base)) {
+                runner.base + 1
+            } else {
+                runner.base
+            };
+            if base >= HOME_BASE {
+                self.teams.select_mut(self.inning.batting()).runs += 1;
+            } else {
+                self.baserunners.push(runner.to(base));
+            }
+        }
+        self.put_on_base(batter, 1, reached);
+    }
+
+    /// Put the batter on base, charged to the pitcher they reached against.
+    fn put_on_base(&mut self, batter: PlayerId, base: u8, reached: Reached) {
+        let pitcher = self.teams.select(self.inning.fielding()).pitcher;
+        self.baserunners
+            .push(Baserunner::reached(batter, base, reached, pitcher));
+    }
+
+    fn handle_hit_by_pitch(
         &mut self,
+        pitcher: &Player,
         batter: &Batter<'_>,
-        database: &Database,
-    ) -> ControlFlow<String, Never> {
+        out: &mut String,
+    ) -> Outcome {
+        self.force_to_first(batter.0.id, Reached::HitByPitch);
+        self.clear_batter();
+        update!(out, "{} hits {} with a pitch!", pitcher.name, batter.0.name);
+        ControlFlow::Break(PlayEvent::HitByPitch)
+    }
+
+    /// Count a pitch toward the plate appearance and the pitcher's game, and roll its
+    /// commentary. Returns the commentary stream, for anything else said about the pitch.
+    fn start_pitch(&mut self, pitcher: PlayerId) -> Commentary {
+        self.pitches += 1;
+        let fielding = self.teams.select_mut(self.inning.fielding());
+        *fielding.pitch_counts.entry(pitcher).or_default() += 1;
+        let mut commentary = Commentary::new(self);
+        self.pitch = Some(commentary.pitch());
+        commentary
+    }
+
+    /// Start a pitch's update with its commentary, like "Slider low. ".
+    fn describe_pitch(&self, out: &mut String) {
+        if let Some(detail) = self.pitch {
+            update!(out, "{}. ", detail);
+        }
+    }
+
+    fn handle_ball(&mut self, batter: &Batter<'_>, out: &mut String) -> Outcome {
         self.balls += 1;
         ControlFlow::Break(if self.balls >= BALLS_NEEDED {
-            let mut message = format!("{} draws a walk.", batter.0.name);
-            let occupied = self.bases_occupied();
-            for (runner, mut base) in std::mem::take(&mut self.baserunners) {
-                if (1..base).all(|b| occupied.contains(&b)) {
-                    base += 1;
-                }
-                if base >= HOME_BASE {
-                    self.teams.select_mut(self.inning.batting()).runs += 1;
-                    write!(message, " {} scores!", runner.load(database).name)
-                        .expect("std::fmt::Write does not fail on String");
-                } else {
-                    self.baserunners.push((runner, base));
-                }
-            }
-            self.baserunners.push((batter.0.id, 1));
+            self.force_to_first(batter.0.id, Reached::Walk);
             self.clear_batter();
             self.teams.select_mut(self.inning.batting()).lineup_slot += 1;
-            format!("{} draws a walk.", batter.0.name)
+            update!(out, "{} draws a walk.", batter.0.name);
+            PlayEvent::Walk
         } else {
-            format!("Ball. {}-{}", self.balls, self.strikes)
+            self.describe_pitch(out);
+            update!(out, "Ball. {}-{}", self.balls, self.strikes);
+            PlayEvent::Ball
         })
     }
 
@@ -360,32 +1023,165 @@ impl Game {
         &mut self,
         batter: &Batter<'_>,
         kind: &'static str,
-    ) -> ControlFlow<String, Never> {
+        out: &mut String,
+    ) -> Outcome {
+        let swinging = kind == "swinging";
         self.strikes += 1;
         ControlFlow::Break(if self.strikes >= STRIKES_NEEDED {
             self.clear_batter();
             self.handle_out();
             self.teams.select_mut(self.inning.batting()).lineup_slot += 1;
-            format!("{} strikes out {}.", batter.0.name, kind)
+            update!(out, "{} strikes out {}.", batter.0.name, kind);
+            PlayEvent::Strikeout { swinging }
         } else {
-            format!("Strike, {}. {}-{}", kind, self.balls, self.strikes)
+            self.describe_pitch(out);
+            update!(out, "Strike, {}. {}-{}", kind, self.balls, self.strikes);
+            PlayEvent::Strike { swinging }
         })
     }
 
-    fn handle_home_run(&mut self, batter: &Batter<'_>) -> ControlFlow<String, Never> {
+    fn handle_foul(
+        &mut self,
+        rng: &mut impl RngSource,
+        database: &Database,
+        pitch: &Pitch<'_>,
+        batter: &Batter<'_>,
+        out: &mut String,
+    ) -> Outcome {
+        let formulas = &*database.formulas.0;
+        let fielder = self.roll_fielder(rng, database, Some(BattedBall::Popup));
+        if roll_formula(rng, "foul out", pitch, |pitch| {
+            formulas.foul_out(pitch, &fielder.0)
+        }) {
+            self.clear_batter();
+            self.batted_ball = Some(BattedBall::Popup);
+            update!(out, "{} fouls out to {}.", batter.0.name, fielder.0.name);
+            if !self.handle_out() {
+                // runners can tag up, same as on a flyout
+                self.advance_runners(database, out, |runner| {
+                    roll_formula(rng, "advance", pitch, |pitch| {
+                        formulas.advance_on_out(pitch, &fielder.0, runner, true)
+                    })
+                });
+            }
+            return ControlFlow::Break(PlayEvent::FoulOut {
+                fielder: fielder.0.id,
+            });
+        }
+        self.strikes = 2.min(self.strikes + 1);
+        self.describe_pitch(out);
+        update!(out, "Foul Ball. {}-{}", self.balls, self.strikes);
+        ControlFlow::Break(PlayEvent::Foul)
+    }
+
+    /// A ball in play caught or fielded for an out by `fielder`.
+    fn handle_fielded_out(
+        &mut self,
+        rng: &mut impl RngSource,
+        database: &Database,
+        pitch: &Pitch<'_>,
+        batter: &Batter<'_>,
+        fielder: &Fielder<'_>,
+        out: &mut String,
+    ) -> Outcome {
+        let formulas = &*database.formulas.0;
+        let batted_ball = pitch.batted_ball.unwrap_or(BattedBall::Grounder);
+        // TODO: double play / fielder's choice
+        let id = fielder.0.id;
+        let (event, kind) = match batted_ball {
+            // TODO: ground out advances
+            BattedBall::Grounder => (PlayEvent::GroundOut { fielder: id }, "ground out"),
+            BattedBall::LineDrive => (PlayEvent::LineOut { fielder: id }, "line out"),
+            BattedBall::FlyBall => (PlayEvent::Flyout { fielder: id }, "flyout"),
+            BattedBall::Popup => (PlayEvent::PopOut { fielder: id }, "pop out"),
+        };
+        self.clear_batter();
+        update!(
+            out,
+            "{} hit a {} to {}.",
+            batter.0.name,
+            kind,
+            fielder.0.name
+        );
+        if !self.handle_out() {
+            self.advance_runners(database, out, |runner| {
+                let tag_up = batted_ball.is_in_air();
+                roll_formula(rng, "advance", pitch, |pitch| {
+                    formulas.advance_on_out(pitch, &fielder.0, runner, tag_up)
+                })
+            });
+        }
+        ControlFlow::Break(event)
+    }
+
+    fn handle_bunt(
+        &mut self,
+        rng: &mut impl RngSource,
+        database: &Database,
+        pitch: &Pitch<'_>,
+        batter: &Batter<'_>,
+        out: &mut String,
+    ) -> Outcome {
+        let formulas = &*database.formulas.0;
+        let fielder = self.roll_fielder(rng, database, Some(BattedBall::Grounder));
+        if roll_formula(rng, "bunt popout", pitch, |pitch| {
+            formulas.bunt_popout(pitch, &fielder.0)
+        }) {
+            self.clear_batter();
+            self.handle_out();
+            update!(
+                out,
+                "{} bunts, popping out to {}.",
+                batter.0.name,
+                fielder.0.name
+            );
+            return ControlFlow::Break(PlayEvent::BuntPopout {
+                fielder: fielder.0.id,
+            });
+        }
+
+        let hit = roll_formula(rng, "bunt hit", pitch, |pitch| {
+            formulas.bunt_hit(pitch, &fielder.0)
+        });
+        self.clear_batter();
+        let event = if hit {
+            update!(out, "{} bunts for a single!", batter.0.name);
+            PlayEvent::BuntHit
+        } else {
+            update!(
+                out,
+                "{} lays down a sacrifice bunt to {}.",
+                batter.0.name,
+                fielder.0.name
+            );
+            PlayEvent::SacrificeBunt {
+                fielder: fielder.0.id,
+            }
+        };
+        if hit || !self.handle_out() {
+            self.advance_runners(database, out, |_| true);
+        }
+        if hit {
+            self.put_on_base(batter.0.id, 1, Reached::Hit);
+        }
+        ControlFlow::Break(event)
+    }
+
+    fn handle_home_run(&mut self, batter: &Batter<'_>, out: &mut String) -> Outcome {
         let mut runs = 1;
         for _ in self.baserunners.drain(..) {
             runs += 1;
         }
         self.teams.select_mut(self.inning.batting()).runs += runs;
         self.clear_batter();
-        ControlFlow::Break(if runs == 1 {
-            format!("{} hits a solo home run!", batter.0.name)
+        if runs == 1 {
+            update!(out, "{} hits a solo home run!", batter.0.name);
         } else if runs == u16::from(HOME_BASE) {
-            format!("{} hits a grand slam!", batter.0.name)
+            update!(out, "{} hits a grand slam!", batter.0.name);
         } else {
-            format!("{} hits a {}-run home run!", batter.0.name, runs)
-        })
+            update!(out, "{} hits a {}-run home run!", batter.0.name, runs);
+        }
+        ControlFlow::Break(PlayEvent::HomeRun)
     }
 
     fn handle_base_hit(
@@ -393,235 +1189,1068 @@ impl Game {
         batter: &Batter<'_>,
         database: &Database,
         bases: u8,
-    ) -> ControlFlow<String, Never> {
-        let mut message = match bases {
-            1 => format!("{} hits a Single!", batter.0.name),
-            2 => format!("{} hits a Double!", batter.0.name),
-            3 => format!("{} hits a Triple!", batter.0.name),
-            4 => format!("{} hits a Quadruple!", batter.0.name),
-            _ => format!("{} hits a {}-base Hit!", batter.0.name, bases),
-        };
-        for (runner, mut base) in std::mem::take(&mut self.baserunners) {
-            // TODO: extra base advancement
-            base += bases;
+        out: &mut String,
+        advance: impl FnMut(&Player) -> bool,
+    ) -> Outcome {
+        match bases {
+            1 => update!(out, "{} hits a Single!", batter.0.name),
+            2 => update!(out, "{} hits a Double!", batter.0.name),
+            3 => update!(out, "{} hits a Triple!", batter.0.name),
+            4 => update!(out, "{} hits a Quadruple!", batter.0.name),
+            _ => update!(out, "{} hits a {}-base Hit!", batter.0.name, bases),
+        }
+        for runner in std::mem::take(&mut self.baserunners) {
+            let base = runner.base + bases;
             if base >= HOME_BASE {
                 self.teams.select_mut(self.inning.batting()).runs += 1;
-                write!(message, " {} scores!", runner.load(database).name)
-                    .expect("std::fmt::Write does not fail on String");
+                update!(out, " {} scores!", runner.player.load(database).name);
             } else {
-                self.baserunners.push((runner, base));
+                self.baserunners.push(runner.to(base));
             }
         }
-        self.baserunners.push((batter.0.id, bases));
+        self.advance_runners(database, out, advance);
+        self.put_on_base(batter.0.id, bases, Reached::Hit);
         self.clear_batter();
-        ControlFlow::Break(message)
+        ControlFlow::Break(PlayEvent::Hit { bases })
+    }
+
+    /// Give each baserunner, lead runner first, the chance to take an extra base if it's open.
+    fn advance_runners(
+        &mut self,
+        database: &Database,
+        out: &mut String,
+        mut advance: impl FnMut(&Player) -> bool,
+    ) {
+        let home = self.home_base();
+        // lead runners go first, so a runner behind them knows whether the next base is open
+        let mut runners = std::mem::take(&mut self.baserunners);
+        runners.sort_by_key(|runner| std::cmp::Reverse(runner.base));
+        for baserunner in runners {
+            let base = baserunner.base;
+            let runner = self.effective(database, baserunner.player, self.inning.batting());
+            let runner = &*runner;
+            // only roll for runners who have somewhere to go
+            let open = !self.baserunners.iter().any(|other| other.base == base + 1);
+            if !(open && advance(runner)) {
+                self.baserunners.push(baserunner);
+            } else if base + 1 >= HOME_BASE {
+                self.teams.select_mut(self.inning.batting()).runs += 1;
+                update!(out, " {} scores!", runner.name);
+            } else {
+                self.baserunners.push(baserunner.to(base + 1));
+                update!(
+                    out,
+                    " {} advances to {}.",
+                    runner.name,
+                    BaseDisplay::new(base + 1, home)
+                );
+            }
+        }
     }
 }
 
-fn roll_strike(rng: &mut Rng, date: Date, pitcher: &Pitcher<'_>, batter: &Batter<'_>) -> bool {
-    let ballpark = Ballpark::default(); // TODO
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::SimError;
+    use crate::id::PlayerId;
+    use crate::rng::tests::Scripted;
+    use crate::{
+        AwayHome, Baserunner, CalledOff, CheckPolicy, DatabaseError, Date, Expansion, Formulas,
+        Game, GameBuilder, Inning, Mutation, Pitch, PlayEvent, Player, Reached, Rng,
+        RosterPosition, RosterSize, RunnerResult, SeasonStats, Sim, Team, TeamId, TeamSelect,
+        Weather, FORFEIT_RUNS,
+    };
+    use std::collections::BTreeMap;
 
-    // NOTE: mostly using the season 14 formula
-    let threshold = (0.2
-        + (0.285 * (pitcher.0.ruthlessness * (1.0 + 0.2 * pitcher.0.vibes(date))))
-        + (0.2 * ballpark.forwardness)
-        + (0.1 * batter.0.musclitude))
-        .min(0.86);
-    rng.next_f64() < threshold
-}
+    pub(crate) fn two_games() -> Sim {
+        let mut sim = Sim::with_rng(Rng::seeded(1, 2));
+        let teams = ["Firefighters", "Lovers", "Crabs", "Jazz Hands"].map(|nickname| {
+            let team = Team {
+                id: TeamId::new(),
+                nickname: nickname.into(),
+                ..Team::default()
+            };
+            let expansion = Expansion {
+                roster: Some(RosterSize::STANDARD),
+                ..Expansion::default()
+            };
+            sim.add_expansion_team(team, expansion).unwrap()
+        });
+        let games = vec![
+            Game::new(AwayHome {
+                away: teams[0],
+                home: teams[1],
+            }),
+            Game::new(AwayHome {
+                away: teams[2],
+                home: teams[3],
+            }),
+        ];
+        sim.start_day(Date::default(), games).unwrap();
+        sim
+    }
 
-fn roll_swing(
-    rng: &mut Rng,
-    date: Date,
-    pitcher: &Pitcher<'_>,
-    batter: &Batter<'_>,
-    strike: bool,
-) -> bool {
-    let ballpark = Ballpark::default(); // TODO
-    let batter_vibes_mod = 1.0 + 0.2 * batter.0.vibes(date);
-    let pitcher_vibes_mod = 1.0 + 0.2 * pitcher.0.vibes(date);
-
-    let threshold = if strike {
-        let div = batter.0.divinity * batter_vibes_mod;
-        let musc = batter.0.musclitude * batter_vibes_mod;
-        let thwack = batter.0.thwackability * batter_vibes_mod;
-        let invpath = (1.0 - batter.0.patheticism) * batter_vibes_mod;
-        let ruth = pitcher.0.ruthlessness * pitcher_vibes_mod;
-        let combined = (div + musc + invpath + thwack) / 4.0;
-        0.6 + (0.35 * combined) - (0.2 * ruth) + (0.2 * (ballpark.viscosity - 0.5))
-    } else {
-        let moxie = batter.0.moxie * batter_vibes_mod;
-        let path = batter.0.patheticism;
-        let ruth = pitcher.0.ruthlessness * pitcher_vibes_mod;
-        let combined = (12.0 * ruth - 5.0 * moxie + 5.0 * path + 4.0 * ballpark.viscosity) / 20.0;
-        if combined < 0.0 {
-            f64::NAN
-        } else {
-            combined.powf(1.5).clamp(0.1, 0.95)
+    /// A sim playing the first of [`two_games`]'s matchups from the top of the first, set up by
+    /// `build` (which is given the batting team), with every roll answered by `script`.
+    pub(crate) fn scripted(
+        script: fn(&'static str) -> f64,
+        build: impl FnOnce(GameBuilder, &Team) -> GameBuilder,
+    ) -> Sim<Scripted> {
+        let base = two_games();
+        let teams = base.games_today()[0].teams.as_ref().map(|team| team.id);
+        let batting = base.teams().get(&teams.away).unwrap();
+        let game = build(Game::builder(teams).inning(Inning::Top(1)), batting)
+            .try_build(&base)
+            .unwrap();
+        let mut sim = Sim::with_rng(Scripted(script));
+        sim.database = base.database;
+        sim.start_day(Date { season: 0, day: 1 }, vec![game])
+            .unwrap();
+        sim
+    }
+
+    #[test]
+    fn tick_order() {
+        let mut sim = two_games();
+        sim.finish_day();
+        sim.set_game_streams(true);
+        let games = sim.round_robin(1);
+        sim.start_day(Date { season: 0, day: 1 }, games).unwrap();
+        sim.take_audit_log();
+        let mut other = sim.clone();
+        while !sim.games_today().iter().all(Game::is_finished) {
+            sim.tick();
+            other.tick();
+            // the same sim makes the same changes, in the same order
+            assert_eq!(sim.audit_log(), other.audit_log());
+            for (game, other_game) in sim.games_today().iter().zip(other.games_today()) {
+                assert_eq!(game.last_update, other_game.last_update);
+            }
         }
-    };
-    rng.next_f64() < threshold
-}
 
-fn roll_contact(
-    rng: &mut Rng,
-    date: Date,
-    pitcher: &Pitcher<'_>,
-    batter: &Batter<'_>,
-    strike: bool,
-) -> bool {
-    let ballpark = Ballpark::default(); // TODO
-    let fort = ballpark.fortification - 0.5;
-    let visc = ballpark.viscosity - 0.5;
-    let fwd = ballpark.forwardness - 0.5;
-    let ballpark_sum = (fort + 3.0 * visc - 6.0 * fwd) / 10.0;
-
-    let batter_vibes_mod = 1.0 + 0.2 * batter.0.vibes(date);
-    let pitcher_vibes_mod = 1.0 + 0.2 * pitcher.0.vibes(date);
-
-    // NOTE: mostly using the season 14 formula
-    let threshold = if strike {
-        let div = batter.0.divinity;
-        let musc = batter.0.musclitude;
-        let thwack = batter.0.thwackability;
-        let path = batter.0.patheticism;
-        let combined = (div + musc + thwack - path) / 2.0 * batter_vibes_mod;
-        if combined < 0.0 {
-            f64::NAN
-        } else {
-            let ruth = pitcher.0.ruthlessness * pitcher_vibes_mod;
-            (0.78 - (0.08 * ruth) + (0.16 * ballpark_sum) + 0.17 * combined.powf(1.2)).min(0.9)
+        // each game's changes land in schedule order
+        let games = sim.games_today();
+        let order = sim
+            .audit_log()
+            .iter()
+            .filter_map(|entry| match entry.mutation {
+                Mutation::RotationAdvanced { team, .. } => {
+                    let playing = |game: &Game| game.teams.iter().any(|t| t.id == team);
+                    Some((entry.tick, games.iter().position(playing).unwrap()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(order.len(), 4);
+        assert!(order.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn game_streams() {
+        let mut base = two_games();
+        base.finish_day();
+        let games = base.round_robin(1);
+        let date = Date { season: 0, day: 1 };
+        let plays = |streams, games: Vec<Game>| {
+            let mut sim = base.clone();
+            sim.set_game_streams(streams);
+            sim.start_day(date, games).unwrap();
+            sim.finish_day();
+            sim.games_today()
+                .iter()
+                .map(|game| (game.id, game.plays.clone()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let id = games[1].id;
+
+        let scheduled = plays(true, games.clone());
+        let reordered = plays(true, games.iter().rev().cloned().collect());
+        assert_eq!(reordered, scheduled);
+        let alone = plays(true, vec![games[1].clone()]);
+        assert_eq!(alone.len(), 1);
+        assert_eq!(alone[&id], scheduled[&id]);
+
+        // sharing the sim's RNG, the other game changes what this one rolls
+        let shared = plays(false, games.clone());
+        assert_ne!(plays(false, vec![games[1].clone()])[&id], shared[&id]);
+
+        // the streams come from the sim's seed
+        let mut reseeded = base.clone();
+        reseeded.rng = Rng::seeded(5, 6);
+        reseeded.set_game_streams(true);
+        reseeded.start_day(date, games.clone()).unwrap();
+        reseeded.finish_day();
+        assert_ne!(reseeded.game(id).unwrap().plays, scheduled[&id]);
+    }
+
+    #[test]
+    fn play_descriptions() {
+        let mut sim = two_games();
+        sim.set_play_descriptions(false);
+        let mut buffer = None;
+        let mut reused = 0;
+        while !sim.games_today()[0].is_finished() {
+            sim.tick();
+            let update = &sim.games_today()[0].last_update;
+            assert!(!update.is_empty());
+            // once the buffer is big enough for every update, ticks stop reallocating it
+            if buffer == Some(update.as_ptr()) {
+                reused += 1;
+            }
+            buffer = Some(update.as_ptr());
+        }
+        let ticks = sim.games_today()[0].ticks;
+        assert!(reused > ticks * 9 / 10);
+        // plays didn't allocate descriptions
+        for play in &sim.games_today()[0].plays {
+            assert_eq!(play.description.capacity(), 0);
         }
-    } else {
-        let path = ((1.0 - batter.0.patheticism) * batter_vibes_mod).max(0.0);
-        let ruth = pitcher.0.ruthlessness * pitcher_vibes_mod;
-        (0.4 - (0.1 * ruth) + (0.35 * path.powf(1.5)) + (0.14 * ballpark_sum)).min(1.0)
-    };
-    rng.next_f64() < threshold
-}
 
-fn roll_foul(rng: &mut Rng, date: Date, batter: &Batter<'_>) -> bool {
-    let ballpark = Ballpark::default(); // TODO
-    let batter_vibes_mod = 1.0 + 0.2 * batter.0.vibes(date);
+        let mut other = two_games();
+        other.finish_day();
+        sim.finish_day();
+        for (game, other) in sim.games_today().iter().zip(other.games_today()) {
+            assert_eq!(game.plays.len(), other.plays.len());
+            assert_eq!(game.last_update, other.last_update);
+            assert!(other.plays.iter().all(|play| !play.description.is_empty()));
+        }
+    }
 
-    let batter_sum =
-        (batter.0.musclitude + batter.0.thwackability + batter.0.divinity) * batter_vibes_mod / 3.0;
-    let threshold =
-        0.25 + (0.1 * ballpark.forwardness) - (0.1 * ballpark.obtuseness) + (0.1 * batter_sum);
-    rng.next_f64() < threshold
-}
+    #[test]
+    fn finish_day() {
+        let mut sim = two_games();
+        let mut other = sim.clone();
+        let mut ticks = 0;
+        while !sim.games_today().iter().all(Game::is_finished) {
+            sim.tick();
+            ticks += 1;
+        }
+        assert_eq!(other.finish_day(), ticks);
+        assert_eq!(sim, other);
+    }
 
-fn roll_out(
-    rng: &mut Rng,
-    date: Date,
-    pitcher: &Pitcher<'_>,
-    fielder: &Fielder<'_>,
-    batter: &Batter<'_>,
-) -> bool {
-    let ballpark = Ballpark::default(); // TODO
-    let batter_vibes_mod = 1.0 + 0.2 * batter.0.vibes(date);
-    let fielder_vibes_mod = 1.0 + 0.2 * fielder.0.vibes(date);
-    let pitcher_vibes_mod = 1.0 + 0.2 * pitcher.0.vibes(date);
-
-    // rough formula for season 14 from
-    // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_out.ipynb
-    let thwack = batter.0.thwackability * batter_vibes_mod;
-    let unthwack = pitcher.0.unthwackability * pitcher_vibes_mod;
-    let omni = fielder.0.omniscience * fielder_vibes_mod;
-    let grand = ballpark.grandiosity - 0.5;
-    let obt = ballpark.obtuseness - 0.5;
-    let omin = ballpark.ominousness - 0.5;
-    let incon = ballpark.inconvenience - 0.5;
-    let visc = ballpark.viscosity - 0.5;
-    let fwd = ballpark.forwardness - 0.5;
-
-    let threshold = 0.3115 + (0.1 * thwack) - (0.08 * unthwack) - (0.065 * omni)
-        + (0.01 * grand)
-        + (0.0085 * obt)
-        - (0.0033 * omin)
-        - (0.0015 * incon)
-        - (0.0033 * visc)
-        + (0.01 * fwd);
-    rng.next_f64() < threshold
-}
+    #[test]
+    fn pitch_counts() {
+        let mut sim = two_games();
+        while sim.games_today()[0].plays.is_empty() {
+            sim.tick();
+        }
+        let game = &sim.games_today()[0];
+        assert_eq!(game.pitches, u16::from(game.at_bat.is_some()));
+        assert_eq!(game.teams.home.pitch_count(), 1);
+        assert_eq!(game.teams.away.pitch_count(), 0);
 
-fn roll_flyout(rng: &mut Rng, batter: &Batter<'_>) -> bool {
-    let ballpark = Ballpark::default(); // TODO
-    let omin = ballpark.ominousness - 0.5;
+        sim.finish_day();
+        let stats = sim.stats(0).unwrap();
+        for game in sim.games_today() {
+            for (team, select) in game.teams.iter().zip([TeamSelect::Away, TeamSelect::Home]) {
+                let thrown = game
+                    .plays
+                    .iter()
+                    .filter(|play| play.inning.fielding() == select && play.event.is_pitch())
+                    .count();
+                assert_eq!(team.pitch_counts.values().sum::<u32>() as usize, thrown);
+                for (pitcher, count) in &team.pitch_counts {
+                    assert_eq!(stats.pitching[pitcher].pitches_thrown, *count);
+                }
+            }
+        }
+        let seen = stats.batting.values().map(|s| s.pitches_seen).sum::<u32>();
+        let thrown = stats
+            .pitching
+            .values()
+            .map(|s| s.pitches_thrown)
+            .sum::<u32>();
+        assert_eq!(seen, thrown);
+    }
 
-    // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_fly.ipynb
-    let threshold = 0.18 + (0.3 * batter.0.buoyancy) - (0.16 * batter.0.suppression) - (0.1 * omin);
-    rng.next_f64() < threshold
-}
+    #[test]
+    fn doubleheader() {
+        let mut sim = two_games();
+        sim.finish_day();
+        let teams = sim.games_today()[0].teams.as_ref().map(|team| team.id);
+        let rotations = teams.map(|team| sim.teams().get(&team).unwrap().clone());
+        let first = Game::new(teams);
+        let duplicate = vec![first.clone(), first.clone()];
+        assert!(matches!(
+            sim.start_day(Date { season: 0, day: 1 }, duplicate),
+            Err(DatabaseError::DuplicateGame { .. })
+        ));
 
-fn roll_home_run(rng: &mut Rng, date: Date, pitcher: &Pitcher<'_>, batter: &Batter<'_>) -> bool {
-    let ballpark = Ballpark::default(); // TODO
-    let batter_vibes_mod = 1.0 + 0.2 * batter.0.vibes(date);
-    let pitcher_vibes_mod = 1.0 + 0.2 * pitcher.0.vibes(date);
-
-    // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_hr.ipynb
-    let div = batter.0.divinity * batter_vibes_mod;
-    let opw = pitcher.0.overpowerment * pitcher_vibes_mod;
-    let supp = pitcher.0.suppression * pitcher_vibes_mod;
-    let opw_supp = (10.0 * opw + supp) / 11.0;
-
-    let grand = ballpark.grandiosity - 0.5;
-    let fort = ballpark.fortification - 0.5;
-    let visc = ballpark.viscosity - 0.5;
-    let omin = ballpark.ominousness - 0.5;
-    let fwd = ballpark.forwardness - 0.5;
-    let ballpark_sum = (0.4 * grand) + (0.2 * fort) + (0.08 * visc) + (0.08 * omin) - (0.24 * fwd);
-
-    let threshold = 0.12 + (0.16 * div) - 0.08 * (opw_supp) - (0.18 * ballpark_sum);
-    rng.next_f64() < threshold
-}
+        // the other two teams have the day off
+        let second = Game::new(teams);
+        let games = vec![first.clone(), second.clone()];
+        sim.start_day(Date { season: 0, day: 1 }, games).unwrap();
+        while !sim.game(first.id).unwrap().is_finished() {
+            assert_eq!(sim.game(second.id).unwrap().inning, Inning::default());
+            sim.tick();
+        }
+        sim.finish_day();
+        let pitchers = |game: &Game| game.teams.as_ref().map(|team| team.pitcher.unwrap());
+        let (first, second) = (sim.game(first.id).unwrap(), sim.game(second.id).unwrap());
+        assert!(second.is_finished());
+        for (team, select) in rotations.iter().zip([TeamSelect::Away, TeamSelect::Home]) {
+            let slot = team.rotation_slot;
+            let len = team.rotation.len();
+            assert_eq!(*pitchers(first).select(select), team.rotation[slot]);
+            assert_eq!(
+                *pitchers(second).select(select),
+                team.rotation[(slot + 1) % len]
+            );
+        }
 
-fn roll_base_hit(
-    rng: &mut Rng,
-    date: Date,
-    pitcher: &Pitcher<'_>,
-    fielder: &Fielder<'_>,
-    batter: &Batter<'_>,
-) -> u8 {
-    let ballpark = Ballpark::default(); // TODO
-    let batter_vibes_mod = 1.0 + 0.2 * batter.0.vibes(date);
-    let fielder_vibes_mod = 1.0 + 0.2 * fielder.0.vibes(date);
-    let pitcher_vibes_mod = 1.0 + 0.2 * pitcher.0.vibes(date);
-
-    let gf = batter.0.ground_friction * batter_vibes_mod;
-    let musc = batter.0.musclitude * batter_vibes_mod;
-    let opw = pitcher.0.overpowerment * pitcher_vibes_mod;
-    let chase = fielder.0.chasiness * fielder_vibes_mod;
-    let fwd = ballpark.forwardness - 0.5;
-    let grand = ballpark.grandiosity - 0.5;
-    let obt = ballpark.obtuseness - 0.5;
-    let visc = ballpark.viscosity - 0.5;
-    let omin = ballpark.ominousness - 0.5;
-    let elong = ballpark.elongation - 0.5;
-
-    // season 14
-    // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_triples_kidror.ipynb
-    let triple_threshold = 0.05 + (0.2 * gf) - (0.04 * opw) - (0.06 * chase)
-        + (0.02 * fwd)
-        + (0.035 * grand)
-        + (0.035 * obt)
-        - (0.005 * omin)
-        - (0.005 * visc);
-    // https://github.com/xSke/resim/blob/main/notebooks/find_roll_formula_doubles.ipynb
-    let double_threshold = 0.165 + (0.2 * musc) - (0.04 * opw) - (0.009 * chase) + (0.027 * fwd)
-        - (0.015 * elong)
-        - (0.01 * omin)
-        - (0.008 * visc);
-
-    let triple_roll = rng.next_f64();
-    let double_roll = rng.next_f64();
-
-    // TODO: Unsure which order these are checked in.
-    if triple_roll < triple_threshold {
-        3
-    } else if double_roll < double_threshold {
-        2
-    } else {
-        1
+        let stats = sim.stats(0).unwrap();
+        for team in teams.iter() {
+            let record = &stats.teams[team];
+            assert_eq!(record.wins + record.losses, 3);
+        }
+        assert!(stats
+            .teams
+            .iter()
+            .filter(|(team, _)| !teams.iter().any(|id| id == *team))
+            .all(|(_, record)| record.wins + record.losses == 1));
+    }
+
+    #[test]
+    fn outcome() {
+        let mut sim = two_games();
+        assert!(sim.games_today().iter().all(|game| game.outcome.is_none()));
+        let ticks = sim.finish_day();
+        for game in sim.games_today() {
+            let outcome = game.outcome.as_ref().unwrap();
+            assert_eq!(outcome.ticks, game.ticks);
+            assert!(u64::from(outcome.ticks) <= ticks);
+            assert!(outcome.innings >= 9);
+            assert_eq!(outcome.innings, game.inning.number());
+            assert_eq!(outcome.shamed, matches!(game.inning, Inning::Mid(_)));
+            assert_eq!(
+                outcome.plate_appearances as usize,
+                game.plays
+                    .iter()
+                    .filter(|play| play.event.ends_plate_appearance())
+                    .count()
+            );
+
+            let winner = if game.winner == Some(game.teams.away.id) {
+                TeamSelect::Away
+            } else {
+                TeamSelect::Home
+            };
+            let pitched_for = |team: TeamSelect, pitcher: PlayerId| {
+                game.plays
+                    .iter()
+                    .any(|play| play.inning.fielding() == team && play.pitcher == pitcher)
+            };
+            assert!(pitched_for(winner, outcome.winning_pitcher.unwrap()));
+            assert!(pitched_for(winner.other(), outcome.losing_pitcher.unwrap()));
+        }
+    }
+
+    #[test]
+    fn try_tick() {
+        let mut sim = two_games();
+        sim.tick();
+        let [first, second] = [0, 1].map(|i| sim.games_today()[i].id);
+        let team = sim.games_today()[0].teams.home.id;
+        let player = sim.teams().get(&team).unwrap().lineup[0];
+        sim.database.players.remove(&player);
+
+        let error = sim.try_tick().unwrap_err();
+        assert!(matches!(error, SimError::Suspended { game, .. } if game == first));
+        assert!(sim.game(first).unwrap().is_suspended());
+        assert!(!sim.game(second).unwrap().is_suspended());
+        assert_eq!(sim.active_games().count(), 1);
+
+        // the other game keeps going
+        sim.set_check_policy(CheckPolicy::Never);
+        let before = sim.game(second).cloned();
+        sim.try_tick().unwrap();
+        assert_ne!(sim.game(second), before.as_ref());
+    }
+
+    #[test]
+    fn in_game_mutations() {
+        let mut sim = two_games();
+        let game = sim.games_today()[0].id;
+        sim.set_weather(game, Weather::Eclipse).unwrap();
+        sim.award_run(game, TeamSelect::Home).unwrap();
+        assert!(matches!(
+            sim.replace_baserunner(game, 1, PlayerId::new()),
+            Err(DatabaseError::EmptyBase { base: 1, .. })
+        ));
+        assert!(sim
+            .update_game(game, |game| game.at_bat = Some(PlayerId::new()))
+            .is_err());
+
+        sim.force_end_game(game, TeamSelect::Home).unwrap();
+        let game = sim.game(game).unwrap();
+        assert_eq!(game.weather, Weather::Eclipse);
+        assert_eq!(game.teams.home.runs, 1);
+        assert_eq!(game.at_bat, None);
+        assert_eq!(game.winner, Some(game.teams.home.id));
+        assert!(sim.force_end_game(game.id, TeamSelect::Away).is_err());
+    }
+
+    #[test]
+    fn suspend_and_resume() {
+        let mut sim = two_games();
+        for _ in 0..40 {
+            sim.tick();
+        }
+        let id = sim.games_today()[0].id;
+        sim.suspend_game(id, "curfew").unwrap();
+        assert!(sim.suspend_game(id, "curfew").is_err());
+        sim.finish_day();
+        let suspended = sim.game(id).unwrap().clone();
+        assert!(suspended.is_suspended() && !suspended.is_finished());
+
+        // a game that can't be ticked isn't resumed
+        let mut broken = sim.clone();
+        let team = suspended.teams.home.id;
+        let shadow = broken.teams().get(&team).unwrap().shadows[0];
+        broken.database.players.remove(&shadow);
+        assert!(matches!(
+            broken.start_day(Date { season: 0, day: 1 }, vec![suspended.clone()]),
+            Err(DatabaseError::BadReference { kind: "player", id }) if id == shadow.0
+        ));
+
+        sim.start_day(Date { season: 0, day: 1 }, vec![suspended.clone()])
+            .unwrap();
+        let log = sim.take_audit_log();
+        assert!(log.iter().any(|entry| entry.mutation
+            == Mutation::GameSuspended {
+                game: id,
+                reason: "curfew".into(),
+            }));
+        assert!(log.iter().any(
+            |entry| entry.date.day == 1 && entry.mutation == Mutation::GameResumed { game: id }
+        ));
+        let resumed = sim.game(id).unwrap();
+        assert_eq!(resumed.suspended, None);
+        assert_eq!(
+            (resumed.inning, resumed.balls, resumed.strikes, resumed.outs),
+            (
+                suspended.inning,
+                suspended.balls,
+                suspended.strikes,
+                suspended.outs
+            )
+        );
+        assert!(sim.resume_game(id).is_err());
+        sim.finish_day();
+        let finished = sim.game(id).unwrap();
+        assert!(finished.is_finished());
+        assert!(finished.plays.starts_with(&suspended.plays));
+        assert!(finished.plays.len() > suspended.plays.len());
+    }
+
+    #[test]
+    fn forfeit_and_cancel() {
+        let mut sim = two_games();
+        let forfeited = sim.games_today()[0].clone();
+        let cancelled = sim.games_today()[1].clone();
+        for _ in 0..20 {
+            sim.tick();
+        }
+        sim.forfeit_game(forfeited.id, TeamSelect::Away, "not enough players")
+            .unwrap();
+        sim.cancel_game(cancelled.id, "rained out").unwrap();
+        assert!(sim
+            .forfeit_game(forfeited.id, TeamSelect::Home, "")
+            .is_err());
+        assert!(sim.cancel_game(cancelled.id, "").is_err());
+        assert_eq!(sim.active_games().count(), 0);
+        assert_eq!(sim.finish_day(), 0);
+
+        let game = sim.game(forfeited.id).unwrap();
+        let (away, home) = (game.teams.away.id, game.teams.home.id);
+        assert_eq!(game.winner, Some(home));
+        assert_eq!(
+            (game.teams.away.runs, game.teams.home.runs),
+            (0, FORFEIT_RUNS)
+        );
+        assert!(game.last_update.starts_with("The "));
+        assert_eq!(
+            game.called_off,
+            Some(CalledOff::Forfeit {
+                team: away,
+                reason: "not enough players".into(),
+            })
+        );
+        assert!(sim.audit_log().iter().any(|entry| entry.mutation
+            == Mutation::GameForfeited {
+                game: forfeited.id,
+                team: away,
+                reason: "not enough players".into(),
+            }));
+        let game = sim.game(cancelled.id).unwrap();
+        assert!(game.is_finished() && game.is_cancelled());
+        assert_eq!(game.winner, None);
+
+        let stats = sim.stats(0).unwrap();
+        assert_eq!(stats.teams[&home].wins, 1);
+        assert_eq!(stats.teams[&home].runs_scored, u32::from(FORFEIT_RUNS));
+        assert_eq!(stats.teams[&away].losses, 1);
+        assert_eq!(stats.teams.len(), 2);
+        assert!(cancelled
+            .teams
+            .iter()
+            .all(|team| !stats.teams.contains_key(&team.id)));
+    }
+
+    #[test]
+    fn scenario() {
+        let mut sim = two_games();
+        let teams = sim.games_today()[0].teams.as_ref().map(|team| team.id);
+        let runner = sim.teams().get(&teams.away).unwrap().lineup[0];
+
+        let invalid = Game::builder(teams).balls(4).try_build(&sim);
+        assert!(matches!(
+            invalid,
+            Err(DatabaseError::InvalidGameState { .. })
+        ));
+        let invalid = Game::builder(teams)
+            .baserunner(runner, 1)
+            .baserunner(runner, 2)
+            .try_build(&sim);
+        assert!(matches!(
+            invalid,
+            Err(DatabaseError::InvalidGameState { .. })
+        ));
+
+        // the home team leads after the top of the 9th, so the game ends without a bottom half
+        let game = Game::builder(teams)
+            .inning(Inning::Mid(9))
+            .runs(AwayHome { away: 1, home: 2 })
+            .try_build(&sim)
+            .unwrap();
+        let id = game.id;
+        sim.start_day(Date::default(), vec![game]).unwrap();
+        sim.tick();
+        assert_eq!(sim.game(id).unwrap().winner, Some(teams.home));
+    }
+
+    #[test]
+    fn advance_lead_runner_first() {
+        let sim = two_games();
+        let teams = sim.games_today()[0].teams.as_ref().map(|team| team.id);
+        let lineup = &sim.teams().get(&teams.away).unwrap().lineup;
+        let (trailing, lead) = (lineup[5], lineup[6]);
+        // the trailing runner is listed first
+        let game = Game::builder(teams)
+            .inning(Inning::Top(1))
+            .baserunner(trailing, 1)
+            .baserunner(lead, 2)
+            .try_build(&sim)
+            .unwrap();
+        let positions = |advance: fn(&Player, PlayerId) -> bool| {
+            let mut game = game.clone();
+            game.advance_runners(&sim.database, &mut String::new(), |runner| {
+                advance(runner, trailing)
+            });
+            game.runner_positions()
+                .into_iter()
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        assert_eq!(positions(|_, _| true), [(trailing, 2), (lead, 3)].into());
+        // a held lead runner holds up the runner behind them
+        assert_eq!(
+            positions(|runner, trailing| runner.id == trailing),
+            [(trailing, 1), (lead, 2)].into()
+        );
+    }
+
+    #[test]
+    fn tenacious_fielders_hold_runners() {
+        // a single with a runner on first, who tries for third
+        fn single(label: &'static str) -> f64 {
+            match label {
+                "strike" | "swing" | "contact" => 0.0,
+                "out" | "home run" | "triple" | "double" => 1.0,
+                "advance" => 0.3,
+                _ => 0.99,
+            }
+        }
+
+        let base_reached = |tenaciousness: f64| {
+            let mut sim = scripted(single, |game, batting| {
+                game.baserunner(batting.lineup[5], 1)
+            });
+            let game = &sim.games_today()[0];
+            let runner = game.baserunners[0].player;
+            let fielding = sim.teams().get(&game.teams.home.id).unwrap().clone();
+            sim.update_player(runner, |player| player.base_thirst = 0.0)
+                .unwrap();
+            for fielder in fielding.roster() {
+                sim.update_player(*fielder, |player| player.tenaciousness = tenaciousness)
+                    .unwrap();
+            }
+            sim.tick_until(|games| !games[0].plays.is_empty());
+            let play = &sim.games_today()[0].plays[0];
+            assert_eq!(play.event, PlayEvent::Hit { bases: 1 });
+            play.baserunners_after
+                .iter()
+                .find(|(player, _)| *player == runner)
+                .map(|(_, base)| *base)
+        };
+        assert_eq!(base_reached(0.0), Some(3));
+        assert_eq!(base_reached(1.0), Some(2));
+    }
+
+    #[test]
+    fn sacrifice_bunts() {
+        #[derive(Debug)]
+        struct AlwaysBunt;
+
+        impl Formulas for AlwaysBunt {
+            fn bunt(&self, _pitch: &Pitch<'_>) -> f64 {
+                1.0
+            }
+        }
+
+        fn sacrifice(label: &'static str) -> f64 {
+            match label {
+                "strike" | "swing" | "bunt" => 0.0,
+                _ => 0.99,
+            }
+        }
+
+        // runners on first and third
+        let play = |outs: u8| {
+            let mut sim = scripted(sacrifice, |game, batting| {
+                game.outs(outs)
+                    .baserunner(batting.lineup[5], 1)
+                    .baserunner(batting.lineup[6], 3)
+            });
+            sim.set_formulas(AlwaysBunt);
+            sim.tick_until(|games| !games[0].plays.is_empty());
+            sim.games_today()[0].plays[0].clone()
+        };
+
+        // every runner moves up, scoring the runner from third
+        let play_with_none_out = play(0);
+        assert!(matches!(
+            play_with_none_out.event,
+            PlayEvent::SacrificeBunt { .. }
+        ));
+        assert_eq!(play_with_none_out.outs_on_play, 1);
+        assert_eq!(play_with_none_out.runs, 1);
+        assert_eq!(play_with_none_out.baserunners_after.len(), 1);
+        assert_eq!(play_with_none_out.baserunners_after[0].1, 2);
+
+        // the third out ends the inning before anyone can score
+        let play_with_two_out = play(2);
+        assert_eq!(play_with_two_out.outs_on_play, 1);
+        assert_eq!(play_with_two_out.runs, 0);
+        assert!(play_with_two_out.baserunners_after.is_empty());
+    }
+
+    #[test]
+    fn runner_moves() {
+        let mut sim = two_games();
+        sim.finish_day();
+        for play in sim.games_today().iter().flat_map(|game| &game.plays) {
+            let moves = play.runner_moves();
+            let count = |to: RunnerResult| moves.iter().filter(|m| m.to == to).count();
+            assert_eq!(count(RunnerResult::Scored), usize::from(play.runs));
+            assert_eq!(count(RunnerResult::Out), usize::from(play.outs_on_play));
+            for (runner, base) in &play.baserunners_after {
+                assert!(moves
+                    .iter()
+                    .any(|m| m.runner == *runner && m.to == RunnerResult::Base(*base)));
+            }
+            if let PlayEvent::StolenBase { runner, base } = play.event {
+                assert!(moves
+                    .iter()
+                    .any(|m| m.runner == runner && m.from + 1 == base));
+            }
+        }
+        let events = sim.game_events();
+        assert!(events.iter().any(|event| event.base_runners.len() > 1));
+        for event in &events {
+            let stolen = event.base_runners.iter().filter(|r| r.was_base_stolen);
+            let caught = event.base_runners.iter().filter(|r| r.was_caught_stealing);
+            assert_eq!(
+                [stolen.count(), caught.count()],
+                match event.event_type {
+                    "STOLEN_BASE" => [1, 0],
+                    "CAUGHT_STEALING" => [0, 1],
+                    _ => [0, 0],
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn foul_out_tag_up() {
+        // a foul caught with a runner on third, who tags up
+        fn caught(label: &'static str) -> f64 {
+            match label {
+                "strike" | "swing" | "contact" | "foul" | "foul out" | "advance" => 0.0,
+                _ => 0.99,
+            }
+        }
+
+        let play = |outs: u8| {
+            let mut sim = scripted(caught, |game, batting| {
+                game.outs(outs).baserunner(batting.lineup[5], 3)
+            });
+            sim.tick_until(|games| !games[0].plays.is_empty());
+            sim.games_today()[0].plays[0].clone()
+        };
+
+        let play_with_none_out = play(0);
+        assert!(matches!(
+            play_with_none_out.event,
+            PlayEvent::FoulOut { .. }
+        ));
+        assert_eq!(play_with_none_out.outs_on_play, 1);
+        assert_eq!(play_with_none_out.runs, 1);
+        // with two out, the catch ends the inning and the runner can't score
+        let play_with_two_out = play(2);
+        assert!(matches!(play_with_two_out.event, PlayEvent::FoulOut { .. }));
+        assert_eq!(play_with_two_out.runs, 0);
+    }
+
+    #[test]
+    fn fax_machine() {
+        #[derive(Debug)]
+        struct Dingers;
+
+        impl Formulas for Dingers {
+            fn bean(&self, _pitch: &Pitch<'_>) -> f64 {
+                0.0
+            }
+
+            fn swing(&self, _pitch: &Pitch<'_>, _strike: bool) -> f64 {
+                1.0
+            }
+
+            fn contact(&self, _pitch: &Pitch<'_>, _strike: bool) -> f64 {
+                1.0
+            }
+
+            fn foul(&self, _pitch: &Pitch<'_>) -> f64 {
+                0.0
+            }
+
+            fn home_run(&self, _pitch: &Pitch<'_>) -> f64 {
+                1.0
+            }
+        }
+
+        let mut sim = two_games();
+        let team = sim.games_today()[0].teams.home.id;
+        sim.update_team(team, |team| team.mods.push(crate::mods::FAX_MACHINE.into()))
+            .unwrap();
+        sim.set_formulas(Dingers);
+        sim.take_audit_log();
+        let faxed = sim.tick_until(|games| games[0].last_update.starts_with("The Fax"));
+        assert!(faxed > 0);
+
+        let game = &sim.games_today()[0];
+        let old = game.plays.last().unwrap().pitcher;
+        let new = game.teams.home.pitcher.unwrap();
+        assert_ne!(old, new);
+        assert_eq!(game.teams.away.runs, 10);
+        let plays = game.plays.len();
+        let data = sim.teams().get(&team).unwrap();
+        assert!(data.shadows.contains(&old));
+        assert!(data.rotation.contains(&new));
+        // the faxed pitcher and the replacement trade places on the roster
+        let roster_moves = sim
+            .audit_log()
+            .iter()
+            .filter_map(|entry| match entry.mutation {
+                Mutation::RosterRemoved { player, slot, .. } => {
+                    Some((false, player, slot.position))
+                }
+                Mutation::RosterAdded { player, slot, .. } => Some((true, player, slot.position)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            roster_moves,
+            [
+                (false, old, RosterPosition::Rotation),
+                (true, new, RosterPosition::Rotation),
+                (false, new, RosterPosition::Shadows),
+                (true, old, RosterPosition::Shadows),
+            ]
+        );
+
+        sim.tick_until(|games| games[0].plays.len() > plays);
+        assert_eq!(sim.games_today()[0].plays.last().unwrap().pitcher, new);
+    }
+
+    #[test]
+    fn pitching_change() {
+        #[derive(Debug)]
+        struct Dingers;
+
+        impl Formulas for Dingers {
+            fn bean(&self, _pitch: &Pitch<'_>) -> f64 {
+                0.0
+            }
+
+            fn swing(&self, _pitch: &Pitch<'_>, _strike: bool) -> f64 {
+                1.0
+            }
+
+            fn contact(&self, _pitch: &Pitch<'_>, _strike: bool) -> f64 {
+                1.0
+            }
+
+            fn foul(&self, _pitch: &Pitch<'_>) -> f64 {
+                0.0
+            }
+
+            fn home_run(&self, _pitch: &Pitch<'_>) -> f64 {
+                1.0
+            }
+        }
+
+        let mut sim = two_games();
+        sim.set_formulas(Dingers);
+        sim.tick_until(|games| !games[0].plays.is_empty());
+        let game = sim.games_today()[0].clone();
+        let [fielding, batting] = [game.inning.fielding(), game.inning.batting()].map(|select| {
+            sim.teams()
+                .get(&game.teams.select(select).id)
+                .unwrap()
+                .clone()
+        });
+        let old = game.teams.select(game.inning.fielding()).pitcher.unwrap();
+        let new = fielding.shadows[0];
+        let elsewhere = sim.games_today()[1].teams.home.id;
+        for player in [
+            fielding.lineup[0],
+            batting.rotation[0],
+            sim.teams().get(&elsewhere).unwrap().rotation[0],
+        ] {
+            assert!(matches!(
+                sim.change_pitcher(game.id, player),
+                Err(DatabaseError::CannotPitch { .. })
+            ));
+        }
+
+        // a runner who reached against the old pitcher is still charged to them
+        let runner = batting.shadows[0];
+        sim.update_game(game.id, |game| {
+            let on_base = Baserunner::reached(runner, 1, Reached::Walk, Some(old));
+            game.baserunners.push(on_base);
+        })
+        .unwrap();
+        sim.take_audit_log();
+        sim.change_pitcher(game.id, new).unwrap();
+        assert_eq!(
+            sim.audit_log()[0].mutation,
+            Mutation::PitcherChanged {
+                game: game.id,
+                team: fielding.id,
+                pitcher: new,
+            }
+        );
+        sim.tick_until(|games| games[0].plays.len() > game.plays.len());
+        let game = &sim.games_today()[0];
+        let play = game.plays.last().unwrap();
+        assert_eq!(play.pitcher, new);
+        assert_eq!(play.runs, 2);
+        assert_eq!(play.inherited_runners, BTreeMap::from([(runner, old)]));
+        let mut stats = SeasonStats::default();
+        stats.record_game(game);
+        let earlier = game.plays.iter().filter(|play| play.pitcher == old);
+        let earlier = earlier.map(|play| u32::from(play.runs)).sum::<u32>();
+        assert_eq!(stats.pitching[&old].runs_allowed, earlier + 1);
+        assert_eq!(stats.pitching[&new].runs_allowed, 1);
+    }
+
+    #[test]
+    fn change_pitcher_between_innings() {
+        let mut sim = two_games();
+        sim.tick_until(|games| matches!(games[0].inning, Inning::Mid(1)));
+        let game = sim.games_today()[0].clone();
+        let [away, home] = [game.teams.away.id, game.teams.home.id]
+            .map(|team| sim.teams().get(&team).unwrap().rotation[1]);
+        // the away team just batted, and takes the field for the bottom of the inning
+        assert!(sim.change_pitcher(game.id, home).is_err());
+        sim.change_pitcher(game.id, away).unwrap();
+        assert_eq!(sim.games_today()[0].teams.away.pitcher, Some(away));
+    }
+
+    #[test]
+    fn replace_baserunner() {
+        let mut sim = scripted(
+            |_| 0.99,
+            |game, batting| {
+                game.baserunner(batting.lineup[5], 1)
+                    .baserunner(batting.lineup[6], 2)
+            },
+        );
+        let game = sim.games_today()[0].clone();
+        let batting = sim.teams().get(&game.teams.away.id).unwrap().clone();
+        let fielding = sim.teams().get(&game.teams.home.id).unwrap().clone();
+        sim.update_game(game.id, |game| game.at_bat = Some(batting.lineup[0]))
+            .unwrap();
+        for runner in [batting.lineup[0], batting.lineup[6], fielding.lineup[0]] {
+            assert!(matches!(
+                sim.replace_baserunner(game.id, 1, runner),
+                Err(DatabaseError::InvalidGameState { .. })
+            ));
+        }
+
+        sim.take_audit_log();
+        sim.replace_baserunner(game.id, 1, batting.lineup[7])
+            .unwrap();
+        assert_eq!(
+            sim.games_today()[0].baserunners[0].player,
+            batting.lineup[7]
+        );
+        assert_eq!(
+            sim.audit_log()[0].mutation,
+            Mutation::BaserunnerReplaced {
+                game: game.id,
+                base: 1,
+                player: batting.lineup[5],
+                replacement: batting.lineup[7],
+            }
+        );
+    }
+
+    #[test]
+    fn reverberating() {
+        // every pitch is a called strike
+        fn reverberates(label: &'static str) -> f64 {
+            match label {
+                "strike" | "reverberating" => 0.0,
+                _ => 0.99,
+            }
+        }
+
+        fn stops(label: &'static str) -> f64 {
+            match label {
+                "strike" => 0.0,
+                _ => 0.99,
+            }
+        }
+
+        let batters = |script: fn(&'static str) -> f64| {
+            let mut sim = scripted(script, |game, _| game);
+            let game = &sim.games_today()[0];
+            let lineup = sim.teams().get(&game.teams.away.id).unwrap().lineup.clone();
+            sim.update_player(lineup[0], |player| {
+                player.mods.push(crate::mods::REVERBERATING.into());
+            })
+            .unwrap();
+            // two strikeouts, and the next batter up
+            sim.tick_until(|games| games[0].plays.len() > 6);
+            let game = &sim.games_today()[0];
+            let batters = game.plays.iter().map(|play| play.batter);
+            let batters = batters.step_by(3).collect::<Vec<_>>();
+            let repeated = game.plays[2].description.ends_with("is Reverberating!");
+            (batters, repeated, lineup)
+        };
+
+        // the leadoff batter comes right back up after striking out
+        let (up, repeated, lineup) = batters(reverberates);
+        assert!(repeated);
+        assert_eq!(up, [lineup[0], lineup[0], lineup[0]]);
+        let (up, repeated, lineup) = batters(stops);
+        assert!(!repeated);
+        assert_eq!(up[0], lineup[0]);
+        assert!(!up[1..].contains(&lineup[0]));
+    }
+
+    #[test]
+    fn debt() {
+        #[derive(Debug)]
+        struct AlwaysBean;
+
+        impl Formulas for AlwaysBean {
+            fn bean(&self, _pitch: &Pitch<'_>) -> f64 {
+                1.0
+            }
+        }
+
+        let mut sim = two_games();
+        let game = &sim.games_today()[0];
+        let home = sim.teams().get(&game.teams.home.id).unwrap();
+        let pitcher = home.probable_pitcher(sim.players(), 0).unwrap();
+        let batter = sim.teams().get(&game.teams.away.id).unwrap().lineup[0];
+        let lender = sim.teams().get(&game.teams.away.id).unwrap().rotation[0];
+        sim.update_player(pitcher, |player| {
+            player.mods.push(crate::mods::DEBT.into());
+            player.marked_by = Some(lender);
+        })
+        .unwrap();
+        sim.set_formulas(AlwaysBean);
+        sim.take_audit_log();
+        for _ in 0..5 {
+            sim.tick();
+        }
+
+        let game = &sim.games_today()[0];
+        assert!(game
+            .plays
+            .iter()
+            .any(|play| play.event == PlayEvent::HitByPitch && play.batter == batter));
+        let marked = sim.players().get(&batter).unwrap();
+        assert!(marked.has_mod(crate::mods::UNSTABLE));
+        assert_eq!(marked.marked_by, Some(pitcher));
+        assert_eq!(sim.mark_chain(batter), [pitcher, lender]);
+        assert!(sim.audit_log().iter().any(|entry| entry.mutation
+            == Mutation::PlayerModAdded {
+                player: batter,
+                name: crate::mods::UNSTABLE.into(),
+                source: Some(pitcher),
+            }));
+    }
+
+    #[test]
+    fn season_part_mods() {
+        #[derive(Debug, Default)]
+        struct Spy(std::sync::Mutex<Vec<(PlayerId, f64)>>);
+
+        impl Formulas for Spy {
+            fn strike(&self, pitch: &Pitch<'_>) -> f64 {
+                let mut seen = self.0.lock().unwrap();
+                seen.push((pitch.batter.id, pitch.batter.thwackability));
+                0.5
+            }
+        }
+
+        let mut sim = two_games();
+        let game = &sim.games_today()[0];
+        let (away, home) = (game.teams.away.id, game.teams.home.id);
+        let batter = sim.teams().get(&away).unwrap().lineup[0];
+        let fielder = sim.teams().get(&home).unwrap().lineup[0];
+        let base = sim.players().get(&batter).unwrap().thwackability;
+        sim.update_team(away, |team| team.mods.push(crate::mods::EARLBIRDS.into()))
+            .unwrap();
+        sim.update_team(home, |team| {
+            team.mods.push(crate::mods::LATE_TO_PARTY.into());
+        })
+        .unwrap();
+
+        // day 1 is in the earlseason
+        let boosted = sim.effective_player(batter).unwrap().thwackability;
+        assert!((boosted - base * 1.2).abs() < 1e-9);
+        assert_eq!(
+            sim.effective_player(fielder).unwrap().into_owned(),
+            *sim.players().get(&fielder).unwrap()
+        );
+
+        let spy = std::sync::Arc::new(Spy::default());
+        sim.database.formulas = crate::formulas::FormulaSet(spy.clone());
+        for _ in 0..5 {
+            sim.tick();
+        }
+        let seen = spy.0.lock().unwrap();
+        assert!(seen.iter().any(|(player, _)| *player == batter));
+        assert!(seen
+            .iter()
+            .filter(|(player, _)| *player == batter)
+            .all(|(_, thwack)| (thwack - boosted).abs() < 1e-9));
     }
 }
@@ -0,0 +1,243 @@
+use crate::id::{GameId, PlayerId, TeamId};
+use crate::{Game, PlayEvent, Weather};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What a snack pays out for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum SnackKind {
+    /// Each of the fan's bets that wins.
+    WinningBets,
+    /// Each strikeout the fan's idol throws.
+    IdolStrikeouts,
+    /// Each hit the fan's idol gets, including home runs.
+    IdolHits,
+    /// Each home run the fan's idol hits.
+    IdolHomeRuns,
+    /// Each base the fan's idol steals.
+    IdolStolenBases,
+    /// Each Black Hole in any game. The sim doesn't swallow runs yet, so every 10 runs a team
+    /// scores in [`Weather::BlackHole`] counts as one.
+    BlackHoles,
+    /// Each Sun 2 in any game: every 10 runs a team scores in [`Weather::Sun2`].
+    Sun2s,
+}
+
+/// A bet on a team to win a game.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Bet {
+    pub game: GameId,
+    pub team: TeamId,
+    pub amount: u64,
+    /// The team's chance of winning when the bet was placed, like one side of
+    /// [`Sim::odds`](crate::Sim::odds). A winning bet pays `amount / odds`, so long shots pay
+    /// more.
+    pub odds: f64,
+}
+
+impl Bet {
+    pub fn new(game: GameId, team: TeamId, amount: u64, odds: f64) -> Bet {
+        Bet {
+            game,
+            team,
+            amount,
+            odds,
+        }
+    }
+}
+
+/// One fan's stake in a day of games: who they idolize, the snacks they hold, and their bets.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Fan {
+    #[serde(default)]
+    pub idol: Option<PlayerId>,
+    /// Coins each snack pays per event.
+    #[serde(default)]
+    pub snacks: BTreeMap<SnackKind, u64>,
+    #[serde(default)]
+    pub bets: Vec<Bet>,
+}
+
+/// What a fan earned over a day, from [`Fan::payout`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Payout {
+    /// Everything below, added up.
+    pub total: u64,
+    /// What winning bets returned, including the amount bet.
+    pub bets: u64,
+    /// What each snack paid.
+    pub snacks: BTreeMap<SnackKind, u64>,
+}
+
+impl Fan {
+    /// Work out what this fan earned from a finished day of games. Bets on games that aren't in
+    /// `games` or haven't finished pay nothing. Nothing in a [cancelled](Game::is_cancelled)
+    /// game pays, just as it doesn't count in stats.
+    pub fn payout(&self, games: &[Game]) -> Payout {
+        let mut events = BTreeMap::<SnackKind, u64>::new();
+        let mut payout = Payout::default();
+        for game in games.iter().filter(|game| !game.is_cancelled()) {
+            let ten_runs = game
+                .teams
+                .iter()
+                .map(|team| u64::from(team.runs / 10))
+                .sum::<u64>();
+            match game.weather {
+                Weather::BlackHole => *events.entry(SnackKind::BlackHoles).or_default() += ten_runs,
+                Weather::Sun2 => *events.entry(SnackKind::Sun2s).or_default() += ten_runs,
+                _ => {}
+            }
+
+            for bet in self.bets.iter().filter(|bet| bet.game == game.id) {
+                if game.winner == Some(bet.team) {
+                    *events.entry(SnackKind::WinningBets).or_default() += 1;
+                    payout.bets += winnings(bet);
+                }
+            }
+
+            let Some(idol) = self.idol else { continue };
+            for play in &game.plays {
+                let kinds: &[SnackKind] = match play.event {
+                    PlayEvent::Strikeout { .. } if play.pitcher == idol => {
+                        &[SnackKind::IdolStrikeouts]
+                    }
+                    PlayEvent::HomeRun if play.batter == idol => {
+                        &[SnackKind::IdolHits, SnackKind::IdolHomeRuns]
+                    }
+                    PlayEvent::Hit { .. } | PlayEvent::BuntHit if play.batter == idol => {
+                        &[SnackKind::IdolHits]
+                    }
+                    PlayEvent::StolenBase { runner, .. } if runner == idol => {
+                        &[SnackKind::IdolStolenBases]
+                    }
+                    _ => &[],
+                };
+                for kind in kinds {
+                    *events.entry(*kind).or_default() += 1;
+                }
+            }
+        }
+
+        for (kind, count) in events {
+            let coins = self.snacks.get(&kind).copied().unwrap_or_default();
+            if coins > 0 && count > 0 {
+                payout.snacks.insert(kind, coins.saturating_mul(count));
+            }
+        }
+        payout.total = payout
+            .snacks
+            .values()
+            .fold(payout.bets, |total, coins| total.saturating_add(*coins));
+        payout
+    }
+}
+
+/// What a winning bet returns: the amount bet, scaled up by how unlikely the win was.
+fn winnings(bet: &Bet) -> u64 {
+    if bet.odds > 0.0 && bet.odds < 1.0 {
+        // saturating float-to-int cast
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_precision_loss,
+            clippy::cast_sign_loss
+        )]
+        let winnings = (bet.amount as f64 / bet.odds).round() as u64;
+        winnings
+    } else {
+        bet.amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bet, Fan, SnackKind};
+
+    #[test]
+    fn payout() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let games = sim.games_today();
+        let game = &games[0];
+        let winner = game.winner.unwrap();
+        let loser = game.teams.iter().find(|team| team.id != winner).unwrap().id;
+        let batter = game
+            .plays
+            .iter()
+            .find(|play| matches!(play.event, crate::PlayEvent::Hit { .. }))
+            .unwrap()
+            .batter;
+        let hits = games
+            .iter()
+            .flat_map(|game| &game.plays)
+            .filter(|play| {
+                play.batter == batter
+                    && matches!(
+                        play.event,
+                        crate::PlayEvent::Hit { .. }
+                            | crate::PlayEvent::HomeRun
+                            | crate::PlayEvent::BuntHit
+                    )
+            })
+            .count() as u64;
+
+        let fan = Fan {
+            idol: Some(batter),
+            snacks: [(SnackKind::IdolHits, 5), (SnackKind::WinningBets, 100)]
+                .into_iter()
+                .collect(),
+            bets: vec![
+                Bet::new(game.id, winner, 100, 0.25),
+                Bet::new(game.id, loser, 100, 0.75),
+            ],
+        };
+        let payout = fan.payout(games);
+        assert_eq!(payout.bets, 400);
+        assert_eq!(payout.snacks[&SnackKind::IdolHits], 5 * hits);
+        assert_eq!(payout.snacks[&SnackKind::WinningBets], 100);
+        assert_eq!(payout.total, 400 + 5 * hits + 100);
+
+        assert_eq!(Fan::default().payout(games).total, 0);
+        let json = serde_json::to_string(&fan).unwrap();
+        assert_eq!(serde_json::from_str::<Fan>(&json).unwrap(), fan);
+    }
+
+    #[test]
+    fn cancelled() {
+        let mut sim = crate::sim::tests::two_games();
+        let id = sim.games_today()[0].id;
+        let hit = loop {
+            sim.tick();
+            let game = &sim.games_today()[0];
+            if let Some(play) = game
+                .plays
+                .last()
+                .filter(|play| matches!(play.event, crate::PlayEvent::Hit { .. }))
+            {
+                break play.batter;
+            }
+        };
+        sim.cancel_game(id, "rained out").unwrap();
+        sim.finish_day();
+
+        let fan = Fan {
+            idol: Some(hit),
+            snacks: [(SnackKind::IdolHits, 5)].into_iter().collect(),
+            bets: Vec::new(),
+        };
+        assert_eq!(fan.payout(sim.games_today()).total, 0);
+        assert!(sim.audit_log().iter().any(|entry| entry.mutation
+            == crate::Mutation::GameCancelled {
+                game: id,
+                reason: "rained out".into(),
+            }));
+    }
+}
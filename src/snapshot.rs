@@ -0,0 +1,232 @@
+use crate::id::GameId;
+use crate::{Ballpark, DatabaseError, Date, Game, League, Player, RngSource, Sim, Team};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// A single game along with the teams, players, ballparks, and league it refers to, which can be
+/// loaded into another sim with [`Sim::import_game`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct GameSnapshot {
+    /// The date the game was played on; rolls depend on it.
+    #[serde(flatten)]
+    pub date: Date,
+    pub game: Game,
+    pub teams: Vec<Team>,
+    pub players: Vec<Player>,
+    #[serde(default)]
+    pub ballparks: Vec<Ballpark>,
+    /// The game's league, unless it's in the main league.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub leagues: Vec<League>,
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Export one of today's games, along with both teams, every player on their rosters, their
+    /// ballparks, and their league.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is not one of today's games.
+    pub fn export_game(&self, id: GameId) -> Result<GameSnapshot, DatabaseError> {
+        let game = self.game(id).ok_or(DatabaseError::BadReference {
+            kind: "game",
+            id: id.0,
+        })?;
+        let teams = game
+            .teams
+            .iter()
+            .map(|data| data.id.load(&self.database).clone())
+            .collect::<Vec<_>>();
+        let players = teams
+            .iter()
+            .flat_map(Team::roster)
+            .chain(game.players())
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|player| player.load(&self.database).clone())
+            .collect();
+        let ballparks = self
+            .database
+            .ballparks
+            .values()
+            .filter(|ballpark| teams.iter().any(|team| team.id == ballpark.team_id))
+            .cloned()
+            .collect();
+        let leagues = self
+            .database
+            .leagues
+            .values()
+            .filter(|league| teams.iter().any(|team| team.league == Some(league.id)))
+            .cloned()
+            .collect();
+        Ok(GameSnapshot {
+            date: self.database.date,
+            game: game.clone(),
+            teams,
+            players,
+            ballparks,
+            leagues,
+        })
+    }
+}
+
+impl<R: RngSource + Clone> Sim<R> {
+    /// Load a game exported with [`Sim::export_game`] into today's games, replacing any game,
+    /// team, player, ballpark, or league already in the sim with the same ID. The sim's date is
+    /// set to the snapshot's date.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::Inconsistent`] if the snapshot is missing something it refers to,
+    /// or another error if a team in the game can't be scheduled (see
+    /// [`Team::check_schedulable`]). The sim is unchanged in either case.
+    pub fn import_game(&mut self, snapshot: GameSnapshot) -> Result<GameId, DatabaseError> {
+        self.transaction(|sim| {
+            let database = &mut sim.database;
+            database.players.extend(
+                snapshot
+                    .players
+                    .into_iter()
+                    .map(|player| (player.id, player)),
+            );
+            database
+                .teams
+                .extend(snapshot.teams.into_iter().map(|team| (team.id, team)));
+            database.ballparks.extend(
+                snapshot
+                    .ballparks
+                    .into_iter()
+                    .map(|ballpark| (ballpark.id, ballpark)),
+            );
+            database.leagues.extend(
+                snapshot
+                    .leagues
+                    .into_iter()
+                    .map(|league| (league.id, league)),
+            );
+            for team in snapshot.game.teams.iter() {
+                team.id.try_load(database)?.check_schedulable()?;
+            }
+            let id = snapshot.game.id;
+            database.games_today.retain(|game| game.id != id);
+            database.games_today.push(snapshot.game);
+            database.date = snapshot.date;
+            Ok(id)
+        })
+    }
+}
+
+/// A saved copy of a whole sim, including its RNG state, from [`Sim::snapshot`].
+///
+/// Snapshots are cheap to clone, so several experiments can branch from the same one.
+#[derive(Debug, Clone)]
+pub struct SimSnapshot<R = crate::Rng>(Arc<Sim<R>>);
+
+impl<R> SimSnapshot<R> {
+    /// The sim as it was when the snapshot was taken.
+    pub fn sim(&self) -> &Sim<R> {
+        &self.0
+    }
+}
+
+impl<R: RngSource + Clone> Sim<R> {
+    /// Save the sim's current state, to go back to later with [`Sim::restore`].
+    pub fn snapshot(&self) -> SimSnapshot<R> {
+        SimSnapshot(Arc::new(self.clone()))
+    }
+
+    /// Return the sim to the state saved in `snapshot`. The sim's check policy and formulas (see
+    /// [`Sim::set_check_policy`] and [`Sim::set_formulas`]) are settings rather than state, so
+    /// they are kept.
+    ///
+    /// If this is the last clone of `snapshot`, its state is moved into the sim without copying.
+    pub fn restore(&mut self, snapshot: SimSnapshot<R>) {
+        let check_policy = self.database.check_policy;
+        let formulas = self.database.formulas.clone();
+        *self = Arc::unwrap_or_clone(snapshot.0);
+        self.database.check_policy = check_policy;
+        self.database.formulas = formulas;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AwayHome, CheckPolicy, Date, Expansion, Game, RosterSize, Sim, Team, TeamId};
+
+    #[test]
+    fn snapshot_restore() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.tick();
+        let snapshot = sim.snapshot();
+        let before = sim.clone();
+
+        for _ in 0..10 {
+            sim.tick();
+        }
+        let after = sim.clone();
+        assert_ne!(sim, before);
+
+        sim.set_check_policy(CheckPolicy::Never);
+        sim.restore(snapshot.clone());
+        assert_eq!(sim, before);
+        assert_eq!(sim.check_policy(), CheckPolicy::Never);
+
+        // branching again from the same point plays out the same way
+        let mut branch = Sim::clone(snapshot.sim());
+        for _ in 0..10 {
+            branch.tick();
+        }
+        assert_eq!(branch, after);
+        sim.restore(snapshot);
+        assert_eq!(sim, before);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut sim = Sim::seeded(1, 2);
+        sim.set_game_streams(true);
+        let teams = ["Firefighters", "Lovers"].map(|nickname| {
+            let team = Team {
+                id: TeamId::new(),
+                nickname: nickname.into(),
+                ..Team::default()
+            };
+            let expansion = Expansion {
+                roster: Some(RosterSize::STANDARD),
+                ..Expansion::default()
+            };
+            sim.add_expansion_team(team, expansion).unwrap()
+        });
+        let game = Game::new(AwayHome {
+            away: teams[0],
+            home: teams[1],
+        });
+        let id = game.id;
+        sim.start_day(Date { season: 11, day: 3 }, vec![game])
+            .unwrap();
+        for _ in 0..30 {
+            sim.tick();
+        }
+
+        let snapshot = sim.export_game(id).unwrap();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let mut other = Sim::seeded(3, 4);
+        other
+            .import_game(serde_json::from_str(&json).unwrap())
+            .unwrap();
+        // the game has its own RNG stream, so it plays out the same in both sims
+        while !sim.games_today()[0].is_finished() {
+            sim.tick();
+            other.tick();
+            assert_eq!(
+                sim.games_today()[0].last_update,
+                other.games_today()[0].last_update
+            );
+        }
+        assert!(other.games_today()[0].is_finished());
+    }
+}
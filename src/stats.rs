@@ -0,0 +1,567 @@
+use crate::id::{BallparkId, PlayerId, TeamId};
+use crate::{
+    BattedBall, Database, Game, Play, PlayEvent, RngSource, RunnerResult, Sim, TeamSelect, Weather,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Statistics for one season, accumulated from each game's plays as the game ends.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct SeasonStats {
+    pub season: u16,
+    pub batting: BTreeMap<PlayerId, BattingStats>,
+    pub pitching: BTreeMap<PlayerId, PitchingStats>,
+    pub teams: BTreeMap<TeamId, TeamRecord>,
+    /// Batting and pitching stats split up by the circumstances of each play. See
+    /// [`SeasonStats::split`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub splits: Vec<SplitStats>,
+}
+
+/// A way of dividing up a season's stats. Each play counts toward the batter's and pitcher's
+/// stats in every split it falls in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[non_exhaustive]
+pub enum Split {
+    /// Plays where the player's team was the home team.
+    Home,
+    /// Plays where the player's team was the away team.
+    Away,
+    /// Plays in games with this weather.
+    Weather { weather: Weather },
+    /// Plays against this team.
+    Opponent { team: TeamId },
+    /// Plays in games at this ballpark.
+    Ballpark { ballpark: BallparkId },
+}
+
+impl Split {
+    fn side(select: TeamSelect) -> Split {
+        match select {
+            TeamSelect::Away => Split::Away,
+            TeamSelect::Home => Split::Home,
+        }
+    }
+}
+
+/// Every player's stats within one [`Split`] of a season.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct SplitStats {
+    pub split: Split,
+    pub batting: BTreeMap<PlayerId, BattingStats>,
+    pub pitching: BTreeMap<PlayerId, PitchingStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct BattingStats {
+    pub plate_appearances: u32,
+    pub at_bats: u32,
+    pub hits: u32,
+    pub doubles: u32,
+    pub triples: u32,
+    pub home_runs: u32,
+    pub runs_batted_in: u32,
+    pub walks: u32,
+    pub strikeouts: u32,
+    pub stolen_bases: u32,
+    pub caught_stealing: u32,
+    /// Pitches thrown to the batter.
+    #[serde(default)]
+    pub pitches_seen: u32,
+    /// Balls put in play, other than bunts, by how they left the bat. Foul outs count as popups.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub batted_balls: BTreeMap<BattedBall, u32>,
+}
+
+impl BattingStats {
+    /// The share of the batter's [batted balls](BattingStats::batted_balls) that left the bat
+    /// as `ball` (GB% for grounders, FB% for fly balls), or `None` if they haven't put any in
+    /// play.
+    pub fn batted_ball_rate(&self, ball: BattedBall) -> Option<f64> {
+        batted_ball_rate(&self.batted_balls, ball)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct PitchingStats {
+    pub batters_faced: u32,
+    pub outs_recorded: u32,
+    pub hits_allowed: u32,
+    pub home_runs_allowed: u32,
+    pub walks: u32,
+    pub strikeouts: u32,
+    pub runs_allowed: u32,
+    #[serde(default)]
+    pub pitches_thrown: u32,
+    /// Balls put in play against the pitcher, other than bunts, by how they left the bat.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub batted_balls: BTreeMap<BattedBall, u32>,
+}
+
+impl PitchingStats {
+    /// The share of [batted balls](PitchingStats::batted_balls) against the pitcher that left
+    /// the bat as `ball`, or `None` if nobody has put one in play against them.
+    pub fn batted_ball_rate(&self, ball: BattedBall) -> Option<f64> {
+        batted_ball_rate(&self.batted_balls, ball)
+    }
+}
+
+fn batted_ball_rate(batted_balls: &BTreeMap<BattedBall, u32>, ball: BattedBall) -> Option<f64> {
+    let total = batted_balls.values().sum::<u32>();
+    (total > 0).then(|| f64::from(batted_balls.get(&ball).copied().unwrap_or(0)) / f64::from(total))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct TeamRecord {
+    pub wins: u32,
+    pub losses: u32,
+    /// Games that ended in a tie, under [`GameRules::max_innings`](crate::GameRules::max_innings).
+    #[serde(default)]
+    pub ties: u32,
+    pub runs_scored: u32,
+    pub runs_allowed: u32,
+    /// The team's record against each opponent it has played this season.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub head_to_head: BTreeMap<TeamId, HeadToHead>,
+}
+
+/// A team's wins and losses against one opponent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct HeadToHead {
+    pub wins: u32,
+    pub losses: u32,
+}
+
+impl TeamRecord {
+    /// The team's record against an opponent, which is 0-0 if they haven't played.
+    pub fn against(&self, opponent: TeamId) -> HeadToHead {
+        self.head_to_head
+            .get(&opponent)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl SeasonStats {
+    /// Add a finished game's plays and result to these stats. Cancelled games don't count.
+    ///
+    /// The sim also splits out stats by the home team's ballpark, which isn't part of the game;
+    /// stats recorded here don't have a [`Split::Ballpark`].
+    pub fn record_game(&mut self, game: &Game) {
+        self.record_game_at(game, None);
+    }
+
+    /// Like [`SeasonStats::record_game`], with the game played at `ballpark`.
+    pub(crate) fn record_game_at(&mut self, game: &Game, ballpark: Option<BallparkId>) {
+        if game.is_cancelled() {
+            return;
+        }
+        for play in &game.plays {
+            if matches!(play.event, PlayEvent::AwardedRun { .. }) {
+                // nobody batted or pitched; the run only counts toward the team's score
+                continue;
+            }
+            record_batting(&mut self.batting, play);
+            record_pitching(&mut self.pitching, play);
+
+            let batting = play.inning.batting();
+            let fielding = play.inning.fielding();
+            let shared = [
+                Some(Split::Weather {
+                    weather: game.weather,
+                }),
+                ballpark.map(|ballpark| Split::Ballpark { ballpark }),
+            ];
+            let batter_splits = [
+                Some(Split::side(batting)),
+                Some(Split::Opponent {
+                    team: game.teams.select(fielding).id,
+                }),
+            ];
+            let pitcher_splits = [
+                Some(Split::side(fielding)),
+                Some(Split::Opponent {
+                    team: game.teams.select(batting).id,
+                }),
+            ];
+            for split in shared.into_iter().chain(batter_splits).flatten() {
+                record_batting(&mut self.split_mut(split).batting, play);
+            }
+            for split in shared.into_iter().chain(pitcher_splits).flatten() {
+                record_pitching(&mut self.split_mut(split).pitching, play);
+            }
+        }
+
+        if game.winner.is_none() && !game.is_tied() {
+            return;
+        }
+        for team in game.teams.iter() {
+            let opponent = game.teams.iter().find(|other| other.id != team.id);
+            let record = self.teams.entry(team.id).or_default();
+            match game.winner {
+                Some(winner) if winner == team.id => record.wins += 1,
+                Some(_) => record.losses += 1,
+                None => record.ties += 1,
+            }
+            if let (Some(winner), Some(opponent)) = (game.winner, opponent) {
+                let head_to_head = record.head_to_head.entry(opponent.id).or_default();
+                if team.id == winner {
+                    head_to_head.wins += 1;
+                } else {
+                    head_to_head.losses += 1;
+                }
+            }
+            record.runs_scored += u32::from(team.runs);
+            record.runs_allowed += u32::from(opponent.map_or(0, |other| other.runs));
+        }
+    }
+
+    /// Stats for every player in one [split](Split) of the season, if anyone has played in it.
+    pub fn split(&self, split: Split) -> Option<&SplitStats> {
+        self.splits.iter().find(|stats| stats.split == split)
+    }
+
+    /// A player's batting stats in one split of the season.
+    pub fn batting_split(&self, player: PlayerId, split: Split) -> Option<&BattingStats> {
+        self.split(split)?.batting.get(&player)
+    }
+
+    /// A player's pitching stats in one split of the season.
+    pub fn pitching_split(&self, player: PlayerId, split: Split) -> Option<&PitchingStats> {
+        self.split(split)?.pitching.get(&player)
+    }
+
+    fn split_mut(&mut self, split: Split) -> &mut SplitStats {
+        let i = self
+            .splits
+            .iter()
+            .position(|stats| stats.split == split)
+            .unwrap_or_else(|| {
+                self.splits.push(SplitStats {
+                    split,
+                    batting: BTreeMap::new(),
+                    pitching: BTreeMap::new(),
+                });
+                self.splits.len() - 1
+            });
+        &mut self.splits[i]
+    }
+
+    /// Every team with a record, from most to fewest wins. Ties are broken by fewest losses,
+    /// then by head-to-head results among the tied teams (wins minus losses in games against
+    /// each other), then by team ID so the order is stable.
+    pub fn standings(&self) -> Vec<(TeamId, &TeamRecord)> {
+        let mut standings = self
+            .teams
+            .iter()
+            .map(|(team, record)| (*team, record))
+            .collect::<Vec<_>>();
+        standings.sort_by(|a, b| {
+            (b.1.wins.cmp(&a.1.wins))
+                .then(a.1.losses.cmp(&b.1.losses))
+                .then(a.0.cmp(&b.0))
+        });
+        for tied in standings.chunk_by_mut(|a, b| a.1.wins == b.1.wins && a.1.losses == b.1.losses)
+        {
+            let teams = tied.iter().map(|(team, _)| *team).collect::<Vec<_>>();
+            let margin = |record: &TeamRecord| {
+                teams
+                    .iter()
+                    .map(|opponent| record.against(*opponent))
+                    .map(|h2h| i64::from(h2h.wins) - i64::from(h2h.losses))
+                    .sum::<i64>()
+            };
+            // stable, so teams that are still tied stay in team ID order
+            tied.sort_by_key(|(_, record)| std::cmp::Reverse(margin(record)));
+        }
+        standings
+    }
+}
+
+/// Add a play to the batting stats of the batter, or of the runner on a steal attempt.
+fn record_batting(stats: &mut BTreeMap<PlayerId, BattingStats>, play: &Play) {
+    let batting = stats.entry(play.batter).or_default();
+    if play.event.is_pitch() {
+        batting.pitches_seen += 1;
+    }
+    if let Some(ball) = play.batted_ball {
+        *batting.batted_balls.entry(ball).or_default() += 1;
+    }
+    if play.event.ends_plate_appearance() {
+        batting.plate_appearances += 1;
+        batting.runs_batted_in += u32::from(play.runs);
+        if !matches!(
+            play.event,
+            PlayEvent::Walk | PlayEvent::HitByPitch | PlayEvent::SacrificeBunt { .. }
+        ) {
+            batting.at_bats += 1;
+        }
+    }
+    match play.event {
+        PlayEvent::Walk => batting.walks += 1,
+        PlayEvent::Strikeout { .. } => batting.strikeouts += 1,
+        PlayEvent::HomeRun => {
+            batting.hits += 1;
+            batting.home_runs += 1;
+        }
+        PlayEvent::BuntHit => batting.hits += 1,
+        PlayEvent::Hit { bases } => {
+            batting.hits += 1;
+            match bases {
+                2 => batting.doubles += 1,
+                3 => batting.triples += 1,
+                _ => {}
+            }
+        }
+        PlayEvent::StolenBase { runner, .. } => {
+            stats.entry(runner).or_default().stolen_bases += 1;
+        }
+        PlayEvent::CaughtStealing { runner, .. } => {
+            stats.entry(runner).or_default().caught_stealing += 1;
+        }
+        _ => {}
+    }
+}
+
+/// Add a play to the pitcher's stats, charging runs scored by inherited runners to the pitchers
+/// responsible for them.
+fn record_pitching(stats: &mut BTreeMap<PlayerId, PitchingStats>, play: &Play) {
+    let mut runs = u32::from(play.runs);
+    if !play.inherited_runners.is_empty() {
+        for runner_move in play.runner_moves() {
+            let responsible = play.inherited_runners.get(&runner_move.runner);
+            if let (RunnerResult::Scored, Some(responsible)) = (runner_move.to, responsible) {
+                stats.entry(*responsible).or_default().runs_allowed += 1;
+                runs = runs.saturating_sub(1);
+            }
+        }
+    }
+    let pitching = stats.entry(play.pitcher).or_default();
+    pitching.outs_recorded += u32::from(play.outs_on_play);
+    pitching.runs_allowed += runs;
+    if play.event.is_pitch() {
+        pitching.pitches_thrown += 1;
+    }
+    if play.event.ends_plate_appearance() {
+        pitching.batters_faced += 1;
+    }
+    if let Some(ball) = play.batted_ball {
+        *pitching.batted_balls.entry(ball).or_default() += 1;
+    }
+    match play.event {
+        PlayEvent::Walk => pitching.walks += 1,
+        PlayEvent::Strikeout { .. } => pitching.strikeouts += 1,
+        PlayEvent::HomeRun => {
+            pitching.hits_allowed += 1;
+            pitching.home_runs_allowed += 1;
+        }
+        PlayEvent::BuntHit | PlayEvent::Hit { .. } => pitching.hits_allowed += 1,
+        _ => {}
+    }
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Stats for a season, if any of its games have finished.
+    pub fn stats(&self, season: u16) -> Option<&SeasonStats> {
+        self.database
+            .stats
+            .iter()
+            .find(|stats| stats.season == season)
+    }
+}
+
+impl Database {
+    /// The current season's stats, creating them if needed.
+    pub(crate) fn season_stats_mut(&mut self) -> &mut SeasonStats {
+        let season = self.date.season;
+        let i = match self
+            .stats
+            .binary_search_by_key(&season, |stats| stats.season)
+        {
+            Ok(i) => i,
+            Err(i) => {
+                let stats = SeasonStats {
+                    season,
+                    ..SeasonStats::default()
+                };
+                self.stats.insert(i, stats);
+                i
+            }
+        };
+        &mut self.stats[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HeadToHead, SeasonStats, Split, TeamRecord};
+    use crate::id::TeamId;
+    use crate::{Ballpark, BattedBall, Date, Expansion, Rng, RosterSize, Sim, Team, Weather};
+
+    #[test]
+    fn round_robin_day() {
+        let mut sim = Sim::with_rng(Rng::seeded(1, 2));
+        for _ in 0..4 {
+            let expansion = Expansion {
+                roster: Some(RosterSize::STANDARD),
+                ..Expansion::default()
+            };
+            let team = Team {
+                id: TeamId::new(),
+                ..Team::default()
+            };
+            sim.add_expansion_team(team, expansion).unwrap();
+        }
+        let games = sim.round_robin(0);
+        assert_eq!(games.len(), 2);
+        sim.start_day(Date::default(), games).unwrap();
+        sim.finish_day();
+
+        let stats = sim.stats(0).unwrap();
+        let standings = stats.standings();
+        assert_eq!(standings.len(), 4);
+        assert_eq!(standings.iter().map(|(_, r)| r.wins).sum::<u32>(), 2);
+        assert_eq!(standings[0].1.wins, 1);
+        assert!(sim.stats(1).is_none());
+
+        let json = serde_json::to_string(&sim).unwrap();
+        let other: Sim = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.stats(0), Some(stats));
+    }
+
+    #[test]
+    fn head_to_head() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let game = &sim.games_today()[0];
+        let winner = game.winner.unwrap();
+        let loser = game.teams.iter().find(|team| team.id != winner).unwrap().id;
+        let stats = sim.stats(0).unwrap();
+        let won = HeadToHead { wins: 1, losses: 0 };
+        let lost = HeadToHead { wins: 0, losses: 1 };
+        assert_eq!(stats.teams[&winner].against(loser), won);
+        assert_eq!(stats.teams[&loser].against(winner), lost);
+        assert_eq!(stats.teams[&winner].head_to_head.len(), 1);
+
+        // two teams with the same record are ordered by their games against each other, even
+        // when that goes against team ID order
+        let (first, second) = if winner < loser {
+            (loser, winner)
+        } else {
+            (winner, loser)
+        };
+        let record = |opponent: TeamId, h2h: HeadToHead| TeamRecord {
+            wins: 5,
+            losses: 5,
+            head_to_head: [(opponent, h2h)].into_iter().collect(),
+            ..TeamRecord::default()
+        };
+        let stats = SeasonStats {
+            teams: [(first, record(second, won)), (second, record(first, lost))]
+                .into_iter()
+                .collect(),
+            ..SeasonStats::default()
+        };
+        let order = stats
+            .standings()
+            .into_iter()
+            .map(|(team, _)| team)
+            .collect::<Vec<_>>();
+        assert_eq!(order, [first, second]);
+    }
+
+    #[test]
+    fn splits() {
+        let mut sim = crate::sim::tests::two_games();
+        let game = sim.games_today()[0].clone();
+        let (away, home) = (game.teams.away.id, game.teams.home.id);
+        let ballpark = crate::id::BallparkId::new();
+        sim.add_ballpark(Ballpark {
+            id: ballpark,
+            team_id: home,
+            ..Ballpark::default()
+        })
+        .unwrap();
+        sim.finish_day();
+
+        let stats = sim.stats(0).unwrap();
+        let play = sim.game(game.id).unwrap().plays.last().unwrap();
+        let (batter, pitcher) = (play.batter, play.pitcher);
+        let total = &stats.batting[&batter];
+        let side = if play.inning.batting() == crate::TeamSelect::Home {
+            (Split::Home, Split::Away, away)
+        } else {
+            (Split::Away, Split::Home, home)
+        };
+        assert_eq!(stats.batting_split(batter, side.0), Some(total));
+        assert_eq!(stats.batting_split(batter, side.1), None);
+        let opponent = Split::Opponent { team: side.2 };
+        assert_eq!(stats.batting_split(batter, opponent), Some(total));
+        let sun2 = Split::Weather {
+            weather: Weather::Sun2,
+        };
+        assert_eq!(stats.batting_split(batter, sun2), Some(total));
+        assert_eq!(
+            stats.pitching_split(pitcher, Split::Ballpark { ballpark }),
+            Some(&stats.pitching[&pitcher])
+        );
+        assert_eq!(stats.pitching_split(pitcher, side.0), None);
+
+        // every plate appearance is either at home or away
+        let split = |split| stats.split(split).unwrap().batting.values();
+        let plate_appearances = |stats: &crate::BattingStats| u64::from(stats.plate_appearances);
+        assert_eq!(
+            split(Split::Home).map(plate_appearances).sum::<u64>()
+                + split(Split::Away).map(plate_appearances).sum::<u64>(),
+            stats.batting.values().map(plate_appearances).sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn batted_balls() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.finish_day();
+        let stats = sim.stats(0).unwrap();
+        let count = |balls: &std::collections::BTreeMap<BattedBall, u32>| {
+            balls.values().map(|count| u64::from(*count)).sum::<u64>()
+        };
+        let batting = stats
+            .batting
+            .values()
+            .map(|s| count(&s.batted_balls))
+            .sum::<u64>();
+        let pitching = stats
+            .pitching
+            .values()
+            .map(|s| count(&s.batted_balls))
+            .sum::<u64>();
+        assert!(batting > 0);
+        assert_eq!(batting, pitching);
+
+        let (_, batter) = stats
+            .batting
+            .iter()
+            .find(|(_, s)| !s.batted_balls.is_empty())
+            .unwrap();
+        let rates = BattedBall::ALL.map(|ball| batter.batted_ball_rate(ball).unwrap());
+        assert!((rates.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(
+            crate::BattingStats::default().batted_ball_rate(BattedBall::Grounder),
+            None
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::database::Database;
+use crate::id::{GameId, PlayerId};
+use crate::{DatabaseError, Mutation, RngSource, RosterPosition, Sim};
+
+impl<R: RngSource> Sim<R> {
+    /// Take a player in a team's lineup out of one of today's games, replacing them with one of
+    /// the team's shadows. The substitute takes the player's place in the lineup (and their
+    /// position in the field, if they have one), and takes over for them if they're batting or on
+    /// base. The player goes to the substitute's place in the shadows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - the game is not one of today's games, or it's already over
+    /// - `player` is not in the lineup of one of the game's teams
+    /// - `substitute` is not in the same team's shadows, or is already in the game
+    pub fn substitute(
+        &mut self,
+        game: GameId,
+        player: PlayerId,
+        substitute: PlayerId,
+    ) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        let database = &mut self.database;
+        let game_data = &database.games_today[i];
+        if game_data.is_finished() {
+            return Err(DatabaseError::GameOver { game });
+        }
+        let (team, slot) = database.roster_slot_of(player)?;
+        if slot.position != RosterPosition::Lineup
+            || !game_data.teams.iter().any(|data| data.id == team)
+        {
+            return Err(DatabaseError::NotInLineup { team, player });
+        }
+        match database.roster_slot_of(substitute) {
+            Ok((sub_team, sub_slot))
+                if sub_team == team && sub_slot.position == RosterPosition::Shadows =>
+            {
+                if game_data.players().any(|p| *p == substitute) {
+                    return Err(DatabaseError::PlayerInUse {
+                        player: substitute,
+                        kind: "game",
+                        id: game.0,
+                    });
+                }
+                database.trade(team, slot, team, sub_slot)?;
+            }
+            _ => {
+                return Err(DatabaseError::NotInShadows {
+                    team,
+                    player: substitute,
+                })
+            }
+        }
+
+        database.substitute_in_game(i, player, substitute);
+        let data = team.load_mut(database);
+        // the substitute takes over the benched player's spot in the field
+        if let Some(position) = data.positions.remove(&player) {
+            data.positions.insert(substitute, position);
+        }
+        database.record(Mutation::Substituted {
+            game,
+            team,
+            player,
+            substitute,
+        });
+        database.check_after_mutation();
+        Ok(())
+    }
+
+    /// Send one of a team's shadows in to run for whoever is on `base` in one of today's games.
+    /// See [`Sim::substitute`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nobody is on `base`, or for any of the reasons [`Sim::substitute`]
+    /// would.
+    pub fn pinch_run(
+        &mut self,
+        game: GameId,
+        base: u8,
+        runner: PlayerId,
+    ) -> Result<(), DatabaseError> {
+        let i = self.game_index(game)?;
+        let on_base = self.database.games_today[i]
+            .baserunners
+            .iter()
+            .find(|baserunner| baserunner.base == base)
+            .ok_or(DatabaseError::EmptyBase { game, base })?
+            .player;
+        self.substitute(game, on_base, runner)
+    }
+}
+
+impl Database {
+    /// Have `substitute` take over for `player` at the plate or on base in one of today's games.
+    fn substitute_in_game(&mut self, i: usize, player: PlayerId, substitute: PlayerId) {
+        let game = &mut self.games_today[i];
+        if game.at_bat == Some(player) {
+            game.at_bat = Some(substitute);
+        }
+        for runner in &mut game.baserunners {
+            if runner.player == player {
+                runner.player = substitute;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DatabaseError, Game, Mutation, Position, Reached};
+
+    #[test]
+    fn substitutions() {
+        let mut sim = crate::sim::tests::two_games();
+        let ready = |game: &Game| !game.baserunners.is_empty() && game.at_bat.is_some();
+        while !ready(&sim.games_today()[0]) {
+            sim.tick();
+        }
+        let game = sim.games_today()[0].clone();
+        let on_base = game.baserunners[0].clone();
+        let team_id = game.teams.select(game.inning.batting()).id;
+        let team = sim.teams().get(&team_id).unwrap().clone();
+        let other_team = sim
+            .teams()
+            .get(&game.teams.select(game.inning.fielding()).id);
+        let [runner, hitter] = [team.shadows[0], team.shadows[1]];
+
+        // the runner has to come from the same team's shadows
+        let stranger = other_team.unwrap().shadows[0];
+        assert!(matches!(
+            sim.pinch_run(game.id, on_base.base, stranger),
+            Err(DatabaseError::NotInShadows { .. })
+        ));
+        assert!(matches!(
+            sim.pinch_run(game.id, game.home_base(), runner),
+            Err(DatabaseError::EmptyBase { .. })
+        ));
+
+        sim.set_position(team_id, on_base.player, Some(Position::Shortstop))
+            .unwrap();
+        sim.pinch_run(game.id, on_base.base, runner).unwrap();
+        let now = &sim.games_today()[0];
+        assert_eq!(now.baserunners[0].player, runner);
+        assert_eq!(now.baserunners[0].base, on_base.base);
+        assert_ne!(now.baserunners[0].reached, Reached::Other);
+        let edited = sim.teams().get(&team_id).unwrap();
+        let index = team.lineup.iter().position(|p| *p == on_base.player);
+        assert_eq!(edited.lineup[index.unwrap()], runner);
+        assert_eq!(edited.shadows[0], on_base.player);
+        // the runner takes over the benched player's position
+        assert_eq!(edited.positions.get(&runner), Some(&Position::Shortstop));
+        assert!(!edited.positions.contains_key(&on_base.player));
+        assert!(sim.audit_log().iter().any(|entry| entry.mutation
+            == Mutation::Substituted {
+                game: game.id,
+                team: team_id,
+                player: on_base.player,
+                substitute: runner,
+            }));
+
+        // a player who has left the lineup can't be substituted again
+        assert!(matches!(
+            sim.substitute(game.id, on_base.player, hitter),
+            Err(DatabaseError::NotInLineup { .. })
+        ));
+        sim.substitute(game.id, game.at_bat.unwrap(), hitter)
+            .unwrap();
+        assert_eq!(sim.games_today()[0].at_bat, Some(hitter));
+        sim.finish_day();
+    }
+}
@@ -0,0 +1,295 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::ops::{Bound, Index, RangeBounds};
+
+/// A map from IDs to objects, iterated in ID order. This is how the sim stores players, teams,
+/// and ballparks, and it has the read-only parts of the `BTreeMap` API, including indexing and
+/// [`Table::range`]. Code that needs an actual `BTreeMap` can convert one with `From`.
+///
+/// IDs are looked up several times per pitch, so lookups go through a hash index into a sorted
+/// array instead of walking a tree. Inserting and removing take linear time, but are rare.
+#[derive(Debug, Clone)]
+pub struct Table<K, V> {
+    entries: Vec<(K, V)>,
+    index: HashMap<K, usize, BuildHasherDefault<IdHasher>>,
+}
+
+impl<K: Copy + Ord + Hash, V> Table<K, V> {
+    pub fn new() -> Table<K, V> {
+        Table::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.index.get(key).map(|i| &self.entries[*i].1)
+    }
+
+    pub fn get_key_value(&self, key: &K) -> Option<(&K, &V)> {
+        self.index.get(key).map(|i| {
+            let (k, v) = &self.entries[*i];
+            (k, v)
+        })
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.entries.first().map(|(k, v)| (k, v))
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.entries.last().map(|(k, v)| (k, v))
+    }
+
+    /// The entries with keys in `range`, in key order.
+    pub fn range<R: RangeBounds<K>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator {
+        let start = match range.start_bound() {
+            Bound::Included(start) => self.entries.partition_point(|(k, _)| k < start),
+            Bound::Excluded(start) => self.entries.partition_point(|(k, _)| k <= start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => self.entries.partition_point(|(k, _)| k <= end),
+            Bound::Excluded(end) => self.entries.partition_point(|(k, _)| k < end),
+            Bound::Unbounded => self.entries.len(),
+        };
+        self.entries[start..end.max(start)]
+            .iter()
+            .map(|(k, v)| (k, v))
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.index.get(key).map(|i| &mut self.entries[*i].1)
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entries.binary_search_by(|(k, _)| k.cmp(&key)) {
+            Ok(i) => Some(std::mem::replace(&mut self.entries[i].1, value)),
+            Err(i) => {
+                self.entries.insert(i, (key, value));
+                self.reindex(i);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &K) -> Option<V> {
+        let i = self.index.remove(key)?;
+        let (_, value) = self.entries.remove(i);
+        self.reindex(i);
+        Some(value)
+    }
+
+    /// Update the index for every entry from `start` onward.
+    fn reindex(&mut self, start: usize) {
+        for (i, (key, _)) in self.entries.iter().enumerate().skip(start) {
+            self.index.insert(*key, i);
+        }
+    }
+}
+
+impl<K, V> Default for Table<K, V> {
+    fn default() -> Table<K, V> {
+        Table {
+            entries: Vec::new(),
+            index: HashMap::default(),
+        }
+    }
+}
+
+impl<K: Copy + Ord + Hash, V> Index<&K> for Table<K, V> {
+    type Output = V;
+
+    /// Panics if `key` isn't in the table, like indexing a `BTreeMap`.
+    fn index(&self, key: &K) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K: Ord, V> From<Table<K, V>> for BTreeMap<K, V> {
+    fn from(table: Table<K, V>) -> BTreeMap<K, V> {
+        table.entries.into_iter().collect()
+    }
+}
+
+// The index is derived from the entries, so only the entries need comparing.
+impl<K: PartialEq, V: PartialEq> PartialEq for Table<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<K: Copy + Ord + Hash, V> Extend<(K, V)> for Table<K, V> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Copy + Ord + Hash, V> FromIterator<(K, V)> for Table<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Table<K, V> {
+        // sort once instead of inserting one at a time
+        let entries = iter.into_iter().collect::<BTreeMap<K, V>>();
+        let mut table = Table {
+            entries: entries.into_iter().collect(),
+            index: HashMap::default(),
+        };
+        table.reindex(0);
+        table
+    }
+}
+
+impl<'a, K: Copy + Ord + Hash, V> IntoIterator for &'a Table<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = std::iter::Map<std::slice::Iter<'a, (K, V)>, fn(&'a (K, V)) -> (&'a K, &'a V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for Table<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.entries.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for Table<K, V>
+where
+    K: Copy + Ord + Hash + Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Table<K, V>, D::Error> {
+        Ok(BTreeMap::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl<K: schemars::JsonSchema, V: schemars::JsonSchema> schemars::JsonSchema for Table<K, V> {
+    fn schema_name() -> String {
+        BTreeMap::<K, V>::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        BTreeMap::<K, V>::json_schema(gen)
+    }
+}
+
+/// Tables only hold IDs the sim or its caller chose, so there's no need for a DoS-resistant hash.
+/// IDs aren't necessarily random, though (without OS entropy they come from a counter, and
+/// imported IDs can be anything), so the folded bytes are run through a finalizer that mixes
+/// every bit into every other.
+#[derive(Default)]
+struct IdHasher(u64);
+
+impl Hasher for IdHasher {
+    fn finish(&self) -> u64 {
+        // MurmurHash3's 64-bit finalizer
+        let mut z = self.0;
+        z = (z ^ (z >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
+        z = (z ^ (z >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        z ^ (z >> 33)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut buf = [0; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            self.0 = (self.0.rotate_left(5) ^ u64::from_le_bytes(buf))
+                .wrapping_mul(0x517c_c1b7_2722_0a95);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdHasher, Table};
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::hash::Hasher;
+    use std::ops::Bound;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut table = [(3, "c"), (1, "a")]
+            .into_iter()
+            .collect::<Table<u32, &str>>();
+        assert_eq!(table.insert(2, "b"), None);
+        assert_eq!(table.insert(3, "C"), Some("c"));
+        assert_eq!(table.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(table.remove(&1), Some("a"));
+        assert_eq!(table.remove(&1), None);
+        assert_eq!(table.get(&2), Some(&"b"));
+        assert_eq!(table.get(&3), Some(&"C"));
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn map_api() {
+        let table = [(1, "a"), (3, "c"), (5, "e"), (7, "g")]
+            .into_iter()
+            .collect::<Table<u32, &str>>();
+        assert_eq!(table[&3], "c");
+        let keys = |range: (Bound<u32>, Bound<u32>)| {
+            table.range(range).map(|(k, _)| *k).collect::<Vec<_>>()
+        };
+        assert_eq!(keys((Bound::Included(2), Bound::Included(5))), [3, 5]);
+        assert_eq!(keys((Bound::Excluded(3), Bound::Unbounded)), [5, 7]);
+        assert_eq!(keys((Bound::Included(3), Bound::Excluded(7))), [3, 5]);
+        assert_eq!(table.range(6..).next_back(), Some((&7, &"g")));
+        assert_eq!(table.range(4..4).count(), 0);
+        assert_eq!(table.first_key_value(), Some((&1, &"a")));
+        assert_eq!(table.last_key_value(), Some((&7, &"g")));
+        assert_eq!(table.get_key_value(&5), Some((&5, &"e")));
+        let map = BTreeMap::from(table.clone());
+        assert!(map.iter().eq(table.iter()));
+    }
+
+    #[test]
+    #[should_panic = "no entry found for key"]
+    fn index_missing() {
+        let table = [(1, "a")].into_iter().collect::<Table<u32, &str>>();
+        let _ = table[&2];
+    }
+
+    #[test]
+    fn hash_mixes_sequential_ids() {
+        // IDs that differ only in their last byte still spread across the low bits a hash table
+        // uses to pick a bucket
+        let buckets = (0..64_u8)
+            .map(|i| {
+                let mut hasher = IdHasher::default();
+                let mut id = [0; 16];
+                id[15] = i;
+                hasher.write(&id);
+                hasher.finish() % 64
+            })
+            .collect::<BTreeSet<_>>();
+        assert!(buckets.len() > 32);
+    }
+}
@@ -1,27 +1,105 @@
 use crate::database::{CheckEntity, Database};
-use crate::id::{PlayerId, TeamId};
-use crate::DatabaseError;
+use crate::id::{LeagueId, PlayerId, TeamId};
+use crate::{DatabaseError, Position};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[non_exhaustive]
 pub struct Team {
     pub id: TeamId,
     pub location: String,
     pub nickname: String,
     pub shorthand: String,
+    #[serde(default)]
+    pub emoji: String,
+    /// A CSS color, like `#ff0000`.
+    #[serde(default, alias = "mainColor")]
+    pub main_color: String,
+    #[serde(default, alias = "secondaryColor")]
+    pub secondary_color: String,
     pub lineup: Vec<PlayerId>,
     pub rotation: Vec<PlayerId>,
     pub shadows: Vec<PlayerId>,
     #[serde(alias = "rotationSlot")]
     pub rotation_slot: usize,
+    #[serde(default)]
+    pub mods: Vec<String>,
+    /// Where players in the lineup play in the field. See [`Team::position`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub positions: BTreeMap<PlayerId, Position>,
+    /// The [league](crate::League) the team plays in, or `None` for the main league.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub league: Option<LeagueId>,
 }
 
 impl Team {
     pub fn name(&self) -> String {
         format!("{} {}", self.location, self.nickname)
     }
+
+    /// Whether the team has a mod (see [`mods`](crate::mods)).
+    pub fn has_mod(&self, name: &str) -> bool {
+        self.mods.iter().any(|m| m == name)
+    }
+
+    /// Every player on the team: the lineup, then the rotation, then the shadows.
+    pub fn roster(&self) -> impl Iterator<Item = &PlayerId> {
+        self.lineup
+            .iter()
+            .chain(&self.rotation)
+            .chain(&self.shadows)
+    }
+
+    /// Check that the team has enough players to be scheduled for a game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any part of the roster is smaller than [`RosterSize::MINIMUM`].
+    pub fn check_schedulable(&self) -> Result<(), DatabaseError> {
+        for position in RosterPosition::ALL {
+            let minimum = RosterSize::MINIMUM.get(position);
+            if self.players(position).len() < minimum {
+                return Err(DatabaseError::RosterTooSmall {
+                    team: self.id,
+                    position,
+                    minimum,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn players(&self, position: RosterPosition) -> &[PlayerId] {
+        match position {
+            RosterPosition::Lineup => &self.lineup,
+            RosterPosition::Rotation => &self.rotation,
+            RosterPosition::Shadows => &self.shadows,
+        }
+    }
+
+    pub(crate) fn players_mut(&mut self, position: RosterPosition) -> &mut Vec<PlayerId> {
+        match position {
+            RosterPosition::Lineup => &mut self.lineup,
+            RosterPosition::Rotation => &mut self.rotation,
+            RosterPosition::Shadows => &mut self.shadows,
+        }
+    }
+
+    /// The player in a roster slot, if there is one.
+    pub fn get(&self, slot: RosterSlot) -> Option<PlayerId> {
+        self.players(slot.position).get(slot.index).copied()
+    }
+
+    /// Where a player is on the team's roster, if they're on it.
+    pub fn slot_of(&self, player: PlayerId) -> Option<RosterSlot> {
+        RosterPosition::ALL.into_iter().find_map(|position| {
+            let index = self.players(position).iter().position(|p| *p == player)?;
+            Some(RosterSlot { position, index })
+        })
+    }
 }
 
 impl CheckEntity for Team {
@@ -31,12 +109,7 @@ impl CheckEntity for Team {
             problems.push(DatabaseError::NilId);
         }
         let mut roster: HashMap<PlayerId, usize> = HashMap::new();
-        for player in self
-            .lineup
-            .iter()
-            .chain(&self.rotation)
-            .chain(&self.shadows)
-        {
+        for player in self.roster() {
             *roster.entry(*player).or_default() += 1;
         }
         for (player, count) in roster {
@@ -50,6 +123,95 @@ impl CheckEntity for Team {
                 problems.push(DatabaseError::DuplicatePlayer { player });
             }
         }
+        if let Some(league) = self.league {
+            if !database.leagues.contains_key(&league) {
+                problems.push(DatabaseError::BadReference {
+                    kind: "league",
+                    id: league.0,
+                });
+            }
+        }
         problems
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RosterPosition {
+    Lineup,
+    Rotation,
+    Shadows,
+}
+
+impl RosterPosition {
+    pub const ALL: [RosterPosition; 3] = [
+        RosterPosition::Lineup,
+        RosterPosition::Rotation,
+        RosterPosition::Shadows,
+    ];
+}
+
+impl Display for RosterPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RosterPosition::Lineup => "lineup",
+            RosterPosition::Rotation => "rotation",
+            RosterPosition::Shadows => "shadows",
+        })
+    }
+}
+
+/// A position on a team's roster: which list, and the index in that list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RosterSlot {
+    pub position: RosterPosition,
+    pub index: usize,
+}
+
+impl Display for RosterSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} slot {}", self.position, self.index)
+    }
+}
+
+/// The number of players in each part of a team's roster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RosterSize {
+    pub lineup: usize,
+    pub rotation: usize,
+    pub shadows: usize,
+}
+
+impl RosterSize {
+    /// The smallest roster a team can have and still be scheduled. A team needs someone to field
+    /// the ball; Pitching Machines are generated for teams without a rotation.
+    pub const MINIMUM: RosterSize = RosterSize {
+        lineup: 1,
+        rotation: 0,
+        shadows: 0,
+    };
+
+    /// The size of a roster at the start of Blaseball's Discipline Era.
+    pub const STANDARD: RosterSize = RosterSize {
+        lineup: 9,
+        rotation: 5,
+        shadows: 11,
+    };
+
+    pub fn get(self, position: RosterPosition) -> usize {
+        match position {
+            RosterPosition::Lineup => self.lineup,
+            RosterPosition::Rotation => self.rotation,
+            RosterPosition::Shadows => self.shadows,
+        }
+    }
+}
+
+impl Default for RosterSize {
+    fn default() -> RosterSize {
+        RosterSize::STANDARD
+    }
+}
@@ -1,16 +1,160 @@
+//! Dates, and helpers for rendering game state the same way the sim's own messages do.
+
 use std::fmt::{self, Display};
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Date {
     pub season: u16,
     pub day: u16,
 }
 
-pub(crate) struct BaseDisplay {
-    pub(crate) base: u8,
-    pub(crate) home: u8,
+impl Date {
+    /// The day after this one, rolling over to day 0 of the next season once the postseason (or
+    /// an offseason day) is over.
+    #[must_use]
+    pub fn next_day(self, config: &SeasonConfig) -> Date {
+        self.advance(1, config)
+    }
+
+    /// The date `days` days after this one.
+    #[must_use]
+    pub fn advance(self, days: u32, config: &SeasonConfig) -> Date {
+        if days == 0 {
+            return self;
+        }
+        let length = u32::from(config.length().max(1));
+        let (season, day) = if self.day >= config.length() {
+            // any day past the postseason is the offseason, which ends the next day
+            (u32::from(self.season) + 1, days - 1)
+        } else {
+            (u32::from(self.season), u32::from(self.day) + days)
+        };
+        let (season, day) = (season + day / length, day % length);
+        Date {
+            season: u16::try_from(season).unwrap_or(u16::MAX),
+            day: u16::try_from(day).unwrap_or(u16::MAX),
+        }
+    }
+
+    pub fn phase(self, config: &SeasonConfig) -> Phase {
+        if self.day < config.regular_season_days {
+            Phase::RegularSeason
+        } else if self.day < config.length() {
+            Phase::Postseason
+        } else {
+            Phase::Offseason
+        }
+    }
+
+    /// The part of the season this date falls in. The earlseason and lateseason are the first
+    /// and last 27 days of a 99-day regular season, scaled to the length of the regular season.
+    pub fn season_part(self, config: &SeasonConfig) -> SeasonPart {
+        let regular = u32::from(config.regular_season_days);
+        let part = regular * 27 / 99;
+        match self.phase(config) {
+            Phase::RegularSeason if u32::from(self.day) < part => SeasonPart::Earlseason,
+            Phase::RegularSeason if u32::from(self.day) >= regular - part => SeasonPart::Lateseason,
+            Phase::RegularSeason => SeasonPart::Midseason,
+            Phase::Postseason => SeasonPart::Postseason,
+            Phase::Offseason => SeasonPart::Offseason,
+        }
+    }
+}
+
+/// Seasons and days are zero-indexed, as in the official API, but displayed one-indexed, as on the
+/// site: `Date { season: 2, day: 44 }` is "Season 3, Day 45".
+impl Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Season {}, Day {}",
+            u32::from(self.season) + 1,
+            u32::from(self.day) + 1
+        )
+    }
+}
+
+/// How many days each part of a season lasts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct SeasonConfig {
+    pub regular_season_days: u16,
+    pub postseason_days: u16,
+    /// How many teams make the postseason, by regular season record.
+    #[serde(default = "default_postseason_teams")]
+    pub postseason_teams: u16,
+}
+
+fn default_postseason_teams() -> u16 {
+    8
+}
+
+impl SeasonConfig {
+    /// A config with the default number of [postseason teams](SeasonConfig::postseason_teams).
+    pub fn new(regular_season_days: u16, postseason_days: u16) -> SeasonConfig {
+        SeasonConfig {
+            regular_season_days,
+            postseason_days,
+            postseason_teams: default_postseason_teams(),
+        }
+    }
+
+    /// The number of days from the first day of the regular season to the last day of the
+    /// postseason.
+    pub fn length(&self) -> u16 {
+        self.regular_season_days
+            .saturating_add(self.postseason_days)
+    }
+}
+
+impl Default for SeasonConfig {
+    /// 99 regular season days, as in every season from Season 2 on, followed by up to 16
+    /// postseason days for eight teams.
+    fn default() -> SeasonConfig {
+        SeasonConfig::new(99, 16)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    RegularSeason,
+    Postseason,
+    Offseason,
+}
+
+/// A finer-grained [`Phase`], for mods that only apply during part of the season. See
+/// [`Date::season_part`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SeasonPart {
+    Earlseason,
+    Midseason,
+    Lateseason,
+    Postseason,
+    Offseason,
+}
+
+/// Displays a base by name, as in "steals third base!" or "steals home!".
+///
+/// `home` is the number of the game's home base (see [`Game::home_base`][crate::Game::home_base]),
+/// so that on a field with a fifth base, base 4 is "fourth base" rather than "home".
+#[derive(Debug, Clone, Copy)]
+pub struct BaseDisplay {
+    pub base: u8,
+    pub home: u8,
+}
+
+impl BaseDisplay {
+    pub fn new(base: u8, home: u8) -> BaseDisplay {
+        BaseDisplay { base, home }
+    }
 }
 
 impl Display for BaseDisplay {
@@ -21,20 +165,79 @@ impl Display for BaseDisplay {
             2 => write!(f, "second base"),
             3 => write!(f, "third base"),
             4 => write!(f, "fourth base"),
-            n => write!(
-                f,
-                "{}{} base",
-                n,
-                match n % 100 {
-                    11 | 12 | 13 => "th",
-                    _ => match n % 10 {
-                        1 => "st",
-                        2 => "nd",
-                        3 => "rd",
-                        _ => "th",
-                    },
-                }
-            ),
+            n => write!(f, "{} base", Ordinal(n.into())),
         }
     }
 }
+
+/// Displays a number as an ordinal: "1st", "2nd", "3rd", "11th", "22nd".
+#[derive(Debug, Clone, Copy)]
+pub struct Ordinal(pub u64);
+
+impl Display for Ordinal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self.0 % 100 {
+            11..=13 => "th",
+            _ => match self.0 % 10 {
+                1 => "st",
+                2 => "nd",
+                3 => "rd",
+                _ => "th",
+            },
+        };
+        write!(f, "{}{}", self.0, suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BaseDisplay, Date, Ordinal, Phase, SeasonConfig, SeasonPart};
+
+    #[test]
+    fn date_arithmetic() {
+        let config = SeasonConfig::new(99, 16);
+        let date = Date { season: 2, day: 44 };
+        assert_eq!(date.to_string(), "Season 3, Day 45");
+        assert_eq!(date.next_day(&config), Date { season: 2, day: 45 });
+        assert_eq!(date.phase(&config), Phase::RegularSeason);
+        assert_eq!(date.season_part(&config), SeasonPart::Midseason);
+        assert_eq!(
+            Date { season: 2, day: 26 }.season_part(&config),
+            SeasonPart::Earlseason
+        );
+        assert_eq!(
+            Date { season: 2, day: 72 }.season_part(&config),
+            SeasonPart::Lateseason
+        );
+
+        let last = Date {
+            season: 2,
+            day: 114,
+        };
+        assert_eq!(last.phase(&config), Phase::Postseason);
+        assert_eq!(last.next_day(&config), Date { season: 3, day: 0 });
+        assert_eq!(date.advance(115 * 2, &config), Date { season: 4, day: 44 });
+        assert_eq!(date.advance(0, &config), date);
+
+        let offseason = Date {
+            season: 2,
+            day: 200,
+        };
+        assert_eq!(offseason.phase(&config), Phase::Offseason);
+        assert_eq!(offseason.next_day(&config), Date { season: 3, day: 0 });
+        assert_eq!(offseason.advance(3, &config), Date { season: 3, day: 2 });
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(BaseDisplay::new(3, 4).to_string(), "third base");
+        assert_eq!(BaseDisplay::new(4, 4).to_string(), "home");
+        assert_eq!(BaseDisplay::new(4, 5).to_string(), "fourth base");
+        assert_eq!(BaseDisplay::new(12, 13).to_string(), "12th base");
+        let ordinals = [1, 2, 3, 4, 11, 12, 13, 21, 102, 111].map(|n| Ordinal(n).to_string());
+        assert_eq!(
+            ordinals,
+            ["1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "102nd", "111th"]
+        );
+    }
+}
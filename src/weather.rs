@@ -0,0 +1,226 @@
+use crate::id::{GameId, TeamId};
+use crate::{AwayHome, Date, Game, Phase, Rng, RngSource, Sim};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+/// The weather a game is played in.
+///
+/// Weather is recorded on each [`Game`](crate::Game), but doesn't yet have any effect on play.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Weather {
+    #[default]
+    Sun2,
+    Eclipse,
+    Glitter,
+    Blooddrain,
+    Peanuts,
+    Birds,
+    Feedback,
+    Reverb,
+    BlackHole,
+    Coffee,
+    Coffee2,
+    Coffee3s,
+    Flooding,
+    Salmon,
+    PolarityPlus,
+    PolarityMinus,
+    SunPointOne,
+    SumSun,
+    Night,
+}
+
+impl Weather {
+    /// Every kind of weather, in the order they're listed above.
+    pub const ALL: [Weather; 19] = [
+        Weather::Sun2,
+        Weather::Eclipse,
+        Weather::Glitter,
+        Weather::Blooddrain,
+        Weather::Peanuts,
+        Weather::Birds,
+        Weather::Feedback,
+        Weather::Reverb,
+        Weather::BlackHole,
+        Weather::Coffee,
+        Weather::Coffee2,
+        Weather::Coffee3s,
+        Weather::Flooding,
+        Weather::Salmon,
+        Weather::PolarityPlus,
+        Weather::PolarityMinus,
+        Weather::SunPointOne,
+        Weather::SumSun,
+        Weather::Night,
+    ];
+}
+
+impl Display for Weather {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Weather::Sun2 => "Sun 2",
+            Weather::Eclipse => "Solar Eclipse",
+            Weather::Glitter => "Glitter",
+            Weather::Blooddrain => "Blooddrain",
+            Weather::Peanuts => "Peanuts",
+            Weather::Birds => "Birds",
+            Weather::Feedback => "Feedback",
+            Weather::Reverb => "Reverb",
+            Weather::BlackHole => "Black Hole",
+            Weather::Coffee => "Coffee",
+            Weather::Coffee2 => "Coffee 2",
+            Weather::Coffee3s => "Coffee 3s",
+            Weather::Flooding => "Flooding",
+            Weather::Salmon => "Salmon",
+            Weather::PolarityPlus => "Polarity +",
+            Weather::PolarityMinus => "Polarity -",
+            Weather::SunPointOne => "Sun .1",
+            Weather::SumSun => "Sum Sun",
+            Weather::Night => "Night",
+        })
+    }
+}
+
+/// The weather rolled ahead of time for one day's games, from [`Sim::roll_forecast`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Forecast {
+    pub date: Date,
+    /// Each game's matchup and the weather it will be played in.
+    pub games: Vec<(AwayHome<TeamId>, Weather)>,
+}
+
+impl<R: RngSource> Sim<R> {
+    /// Roll the weather for every game left in the regular season after today, assuming the
+    /// schedule follows [`Sim::round_robin`], replacing any forecast rolled before. Weather is
+    /// drawn from the regular season [rules](crate::GameRules::weather), or from every kind of
+    /// weather if those don't say.
+    ///
+    /// Each game's weather is rolled from its own stream, derived from the game's date and place
+    /// in the day's schedule and a seed drawn from the sim's RNG. Sims with the same seed roll
+    /// the same forecast, and rolling it advances the sim's RNG by two values.
+    pub fn roll_forecast(&mut self) {
+        let seed = (
+            self.rng.next_labeled("forecast seed").to_bits(),
+            self.rng.next_labeled("forecast seed").to_bits(),
+        );
+        let config = self.database.season_config;
+        let pool = &self.database.regular_season_rules.weather;
+        let pool = if pool.is_empty() {
+            &Weather::ALL[..]
+        } else {
+            pool
+        };
+        let mut forecast = Vec::new();
+        let mut date = self.date().next_day(&config);
+        while date.season == self.date().season && date.phase(&config) == Phase::RegularSeason {
+            let games = self
+                .round_robin(date.day)
+                .into_iter()
+                .zip(0_u128..)
+                .map(|(game, i)| {
+                    let key = (u128::from(date.season) << 48) | (u128::from(date.day) << 32) | i;
+                    let mut rng = Rng::derived(seed, key);
+                    let weather = rng
+                        .choose_labeled("weather", pool)
+                        .copied()
+                        .unwrap_or_default();
+                    (game.teams.map(|team| team.id), weather)
+                })
+                .collect();
+            forecast.push(Forecast { date, games });
+            date = date.next_day(&config);
+        }
+        self.database.forecast = forecast;
+    }
+
+    /// The forecast for each upcoming day, soonest first.
+    pub fn forecast(&self) -> &[Forecast] {
+        &self.database.forecast
+    }
+
+    /// The forecast weather for a matchup on `date`, if it's in the forecast.
+    pub fn forecast_for(&self, date: Date, teams: AwayHome<TeamId>) -> Option<Weather> {
+        self.database
+            .forecast
+            .iter()
+            .find(|forecast| forecast.date == date)?
+            .games
+            .iter()
+            .find(|(matchup, _)| *matchup == teams)
+            .map(|(_, weather)| *weather)
+    }
+}
+
+impl crate::Database {
+    /// Give each of a new day's games its forecast weather, if it has one, and forget the
+    /// forecast for days that have started. Returns the games that had a forecast.
+    pub(crate) fn apply_forecast(&mut self, date: Date, games: &mut [Game]) -> Vec<GameId> {
+        let Some(i) = self
+            .forecast
+            .iter()
+            .position(|forecast| forecast.date >= date)
+        else {
+            self.forecast.clear();
+            return Vec::new();
+        };
+        self.forecast.drain(..i);
+        if self.forecast[0].date != date {
+            return Vec::new();
+        }
+        let forecast = self.forecast.remove(0);
+        let mut forecast_games = Vec::new();
+        for game in games {
+            let teams = game.teams.as_ref().map(|team| team.id);
+            if let Some((_, weather)) = forecast.games.iter().find(|(matchup, _)| *matchup == teams)
+            {
+                game.weather = *weather;
+                forecast_games.push(game.id);
+            }
+        }
+        forecast_games
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Date, Game, SeasonConfig, Sim};
+
+    #[test]
+    fn forecast() {
+        let mut sim = crate::sim::tests::two_games();
+        sim.set_season_config(SeasonConfig::new(4, 2));
+        let mut other = sim.clone();
+        sim.roll_forecast();
+        let forecast = sim.forecast().to_vec();
+        let dates = forecast.iter().map(|day| day.date.day).collect::<Vec<_>>();
+        assert_eq!(dates, [1, 2, 3]);
+        assert!(forecast.iter().all(|day| day.games.len() == 2));
+
+        // the forecast follows from the sim's seed
+        other.roll_forecast();
+        assert_eq!(other, sim);
+        let mut reseeded = Sim::seeded(5, 6);
+        reseeded.database = other.database.clone();
+        reseeded.roll_forecast();
+        assert_ne!(reseeded.forecast(), forecast);
+
+        let tomorrow = Date { season: 0, day: 1 };
+        let (teams, weather) = forecast[0].games[0];
+        assert_eq!(sim.forecast_for(tomorrow, teams), Some(weather));
+        let games = forecast[0]
+            .games
+            .iter()
+            .map(|(teams, _)| Game::new(*teams))
+            .collect();
+        sim.start_day(tomorrow, games).unwrap();
+        for (game, (_, weather)) in sim.games_today().iter().zip(&forecast[0].games) {
+            assert_eq!(game.weather, *weather);
+        }
+        assert_eq!(sim.forecast(), &forecast[1..]);
+    }
+}